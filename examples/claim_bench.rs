@@ -0,0 +1,90 @@
+//! Micro-benchmark of a PLIC claim/complete round trip.
+//!
+//! It compares [`CLAIM::claim`]/[`CLAIM::complete`] with their hot-path variants
+//! [`CLAIM::claim_raw`]/[`CLAIM::complete_raw`] on a memory-backed claim/complete register.
+//! On RISC-V targets, it reports the number of retired instructions (`instret`) per round trip,
+//! including the overhead of the benchmark loop. The volatile accesses to the register cannot be
+//! optimized away, so both variants perform the same number of memory accesses.
+//! It can be run on QEMU user-mode emulation:
+//!
+//! ```text
+//! CARGO_TARGET_RISCV64GC_UNKNOWN_LINUX_GNU_RUNNER=qemu-riscv64 \
+//!     cargo run --release --example claim_bench --target riscv64gc-unknown-linux-gnu
+//! ```
+//!
+//! On other targets, it reports the elapsed time per round trip instead.
+
+use riscv_peripheral::plic::{claim::CLAIM, InterruptNumber};
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Interrupt {
+    I1 = 1,
+}
+
+unsafe impl InterruptNumber for Interrupt {
+    const MAX_INTERRUPT_NUMBER: u16 = 1;
+
+    #[inline]
+    fn number(self) -> u16 {
+        self as _
+    }
+
+    #[inline]
+    fn from_number(number: u16) -> Result<Self, u16> {
+        match number {
+            1 => Ok(Interrupt::I1),
+            _ => Err(number),
+        }
+    }
+}
+
+const ROUNDS: u64 = 100_000;
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+const UNIT: &str = "instructions";
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+const UNIT: &str = "ns";
+
+/// Runs `ROUNDS` round trips of `f` and returns the average cost of a round trip.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+fn bench(mut f: impl FnMut()) -> f64 {
+    use riscv_peripheral::riscv::register::instret;
+    let start = instret::read64();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    (instret::read64() - start) as f64 / ROUNDS as f64
+}
+
+/// Runs `ROUNDS` round trips of `f` and returns the average cost of a round trip.
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn bench(mut f: impl FnMut()) -> f64 {
+    let start = std::time::Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed().as_nanos() as f64 / ROUNDS as f64
+}
+
+fn main() {
+    let mut raw_claim = Interrupt::I1 as u32;
+    // SAFETY: valid memory address
+    let claim = unsafe { CLAIM::new(&mut raw_claim as *mut u32 as _) };
+
+    let typed = bench(|| {
+        if let Some(source) = claim.claim::<Interrupt>() {
+            claim.complete(source);
+        }
+    });
+    let raw = bench(|| {
+        let source = claim.claim_raw();
+        if source != 0 {
+            claim.complete_raw(source);
+        }
+    });
+
+    println!("claim/complete round trip ({ROUNDS} rounds, {UNIT} per round):");
+    println!("  claim + complete:         {typed:.2}");
+    println!("  claim_raw + complete_raw: {raw:.2}");
+}
@@ -3,9 +3,15 @@
 //! CLINT pecification: <https://github.com/pulp-platform/clint>
 //! ACLINT Specification: <https://chromitem-soc.readthedocs.io/en/latest/clint.html>
 
+pub mod ipi;
 pub mod mswi;
 pub mod mtimer;
+#[cfg(feature = "soft-clint")]
+pub mod soft;
 pub mod sswi;
+pub mod time;
+#[cfg(feature = "async")]
+pub mod timer;
 
 /// Trait for enums of HART IDs in (A)CLINT peripherals.
 ///
@@ -14,6 +20,12 @@ pub mod sswi;
 /// If your target only has one HART (HART ID 0), you don't need to implement this trait.
 /// Instead, you can access directly to the base registers through the `(A)CLINT` structs.
 ///
+/// HART IDs do not need to be contiguous (e.g., a target may only have HARTs 0, 2, and 3).
+/// In that case, `from_number` must reject the numbers of absent HARTs. Iterators such as
+/// [`mtimer::MTIMER::harts`] and [`mswi::MSWI::harts`] go through all the numbers from 0 to
+/// `MAX_HART_ID_NUMBER` and skip the ones rejected by `from_number`, so they never yield
+/// registers of absent HARTs.
+///
 /// # Safety
 ///
 /// * This trait must only be implemented on a PAC of a target with a PLIC peripheral.
@@ -32,6 +44,33 @@ pub unsafe trait HartIdNumber: Copy {
     /// Tries to convert a number to a valid HART ID.
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u16) -> Result<Self, u16>;
+
+    /// Tries to convert a number to a valid HART ID.
+    /// If the conversion fails, it returns a [`crate::error::InvalidHartId`] error.
+    #[inline]
+    fn try_from_number(value: u16) -> Result<Self, crate::error::InvalidHartId> {
+        Self::from_number(value).map_err(crate::error::InvalidHartId)
+    }
+
+    /// Tries to convert a number to a valid HART ID.
+    /// If the conversion fails, it returns [`None`].
+    #[inline]
+    fn try_from_number_opt(value: u16) -> Option<Self> {
+        Self::from_number(value).ok()
+    }
+
+    /// Converts a number to a valid HART ID, saturating it to `MAX_HART_ID_NUMBER`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the saturated number is not a valid HART ID.
+    #[inline]
+    fn from_number_saturating(value: u16) -> Self {
+        match Self::from_number(value.min(Self::MAX_HART_ID_NUMBER)) {
+            Ok(v) => v,
+            Err(n) => panic!("invalid HART ID number: {}", n),
+        }
+    }
 }
 
 /// Trait for a CLINT peripheral.
@@ -40,11 +79,50 @@ pub unsafe trait HartIdNumber: Copy {
 ///
 /// * This trait must only be implemented on a PAC of a target with a CLINT peripheral.
 /// * The CLINT peripheral base address `BASE` must be valid for the target device.
+///
+/// # Memory map
+///
+/// By default, the CLINT follows the SiFive CLINT layout, which is also the layout
+/// of an ACLINT with contiguous `MSWI` and `MTIMER` devices:
+///
+/// | Register   | Offset from `BASE`              | Default value |
+/// |------------|---------------------------------|---------------|
+/// | `MSIP`     | `MSWI_OFFSET + 4 * hart`        | `0x0000`      |
+/// | `MTIMECMP` | `MTIMER_OFFSET + STRIDE * hart` | `0x4000`      |
+/// | `MTIME`    | `MTIME_OFFSET`                  | `0xBFF8`      |
+///
+/// where `STRIDE` is `MTIMECMP_STRIDE` (8 bytes by default).
+/// Targets with a non-standard layout can override these constants.
+///
+/// # ACLINT devices with independent base addresses
+///
+/// In a genuine ACLINT, the `MSWI` and `MTIMER` devices are not necessarily contiguous.
+/// Such targets can override the absolute addresses `MSWI_BASE`, `MTIMER_BASE`, and `MTIME_BASE`
+/// instead of the offsets above. By default, they are computed from `BASE` and the offsets.
+/// See the `mswi_base` and `mtimer_base` arms of [`crate::clint_codegen!`].
 pub unsafe trait Clint: Copy {
     /// Base address of the CLINT peripheral.
     const BASE: usize;
+    /// Offset of the `MSWI` device (i.e., `MSIP` of HART 0) relative to `BASE`.
+    const MSWI_OFFSET: usize = 0;
+    /// Offset of the `MTIMER` device (i.e., `MTIMECMP` of HART 0) relative to `BASE`.
+    const MTIMER_OFFSET: usize = 0x4000;
+    /// Separation (in bytes) between the `MTIMECMP` registers of consecutive HARTs.
+    const MTIMECMP_STRIDE: usize = 8;
+    /// Offset of the `MTIME` register relative to `BASE`.
+    const MTIME_OFFSET: usize = 0xBFF8;
+    /// Address of the `MSWI` device (i.e., `MSIP` of HART 0).
+    const MSWI_BASE: usize = Self::BASE + Self::MSWI_OFFSET;
+    /// Address of the `MTIMER` device (i.e., `MTIMECMP` of HART 0).
+    const MTIMER_BASE: usize = Self::BASE + Self::MTIMER_OFFSET;
+    /// Address of the `MTIME` register.
+    const MTIME_BASE: usize = Self::BASE + Self::MTIME_OFFSET;
 }
 
+/// Offset of the `MTIME` register relative to the base address of an ACLINT `MTIMER` device
+/// when `MTIME` is not placed at an independent address.
+pub const ACLINT_MTIME_OFFSET: usize = 0x7FF8;
+
 /// Interface for a CLINT peripheral.
 ///
 /// The RISC-V standard does not specify a fixed location for the CLINT.
@@ -55,21 +133,50 @@ pub unsafe trait Clint: Copy {
 /// Each HART has an assigned index starting from 0 to up to 4_094.
 /// In this way, each HART's timer and software interrupts can be independently configured.
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct CLINT<C: Clint> {
     _marker: core::marker::PhantomData<C>,
 }
 
 impl<C: Clint> CLINT<C> {
-    const MTIMECMP_OFFSET: usize = 0x4000;
+    /// Address of the `MSWI` device (i.e., `MSIP` of HART 0).
+    pub const MSWI_ADDR: usize = C::MSWI_BASE;
+    /// Address of the `MTIMER` device (i.e., `MTIMECMP` of HART 0).
+    pub const MTIMER_ADDR: usize = C::MTIMER_BASE;
+    /// Address of the `MTIME` register.
+    pub const MTIME_ADDR: usize = C::MTIME_BASE;
 
-    const MTIME_OFFSET: usize = 0xBFF8;
+    /// Creates a new CLINT proxy. It can be used in `const` contexts.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the address of the `MSIP` register of the HART with index `hart_index`.
+    #[inline]
+    pub const fn msip_addr(hart_index: u16) -> usize {
+        Self::MSWI_ADDR + 4 * hart_index as usize
+    }
+
+    /// Returns the address of the `MTIMECMP` register of the HART with index `hart_index`.
+    #[inline]
+    pub const fn mtimecmp_addr(hart_index: u16) -> usize {
+        Self::MTIMER_ADDR + C::MTIMECMP_STRIDE * hart_index as usize
+    }
 
     /// Returns the `MSWI` peripheral.
     #[inline]
     pub const fn mswi() -> mswi::MSWI {
         // SAFETY: valid base address
-        unsafe { mswi::MSWI::new(C::BASE) }
+        unsafe { mswi::MSWI::new(C::MSWI_BASE) }
+    }
+
+    /// Returns the inter-processor interrupts interface built on top of the `MSWI` peripheral.
+    #[inline]
+    pub const fn ipi() -> ipi::Ipi<C> {
+        ipi::Ipi::new()
     }
 
     /// Returns the `MTIMER` peripheral.
@@ -77,10 +184,162 @@ impl<C: Clint> CLINT<C> {
     pub const fn mtimer() -> mtimer::MTIMER {
         // SAFETY: valid base address
         unsafe {
-            mtimer::MTIMER::new(
-                C::BASE + Self::MTIMECMP_OFFSET,
-                C::BASE + Self::MTIME_OFFSET,
-            )
+            mtimer::MTIMER::new_with_stride(C::MTIMER_BASE, C::MTIME_BASE, C::MTIMECMP_STRIDE)
+        }
+    }
+
+    /// Returns a [`crate::hal::aclint::Delay`] bound to the `MTIMECMP` register of the HART which ID is `hart_id`.
+    /// `freq` is the frequency (in Hz) of the `MTIME` register.
+    ///
+    /// # Note
+    ///
+    /// Blocking delays busy-poll the `MTIME` register shared among all the HARTs. With the `async`
+    /// feature, asynchronous delays program the `MTIMECMP` register of `hart_id` and wait for a machine
+    /// timer interrupt. Thus, the interrupt-driven path requires `hart_id` to be the current HART.
+    #[inline]
+    pub fn delay_on<H: HartIdNumber>(hart_id: H, freq: usize) -> crate::hal::aclint::Delay {
+        let mtimer = Self::mtimer();
+        crate::hal::aclint::Delay::new_on(mtimer.mtime, mtimer.mtimecmp(hart_id), freq)
+    }
+
+    /// Waits for interrupts until `condition` returns `true`. Global machine interrupts are
+    /// enabled when this method returns.
+    ///
+    /// # Note
+    ///
+    /// `condition` is evaluated with the `MIE` bit of the `mstatus` CSR cleared, and the HART
+    /// executes `wfi` right after a `false` result without enabling interrupts in between. Thus,
+    /// an interrupt that arrives after the check cannot be handled before the HART goes to sleep.
+    /// The HART still wakes up when any interrupt enabled in the `mie` register becomes pending
+    /// (e.g., a machine timer interrupt when `mtime >= mtimecmp`, or a machine software
+    /// interrupt when the `msip` register of the HART is set). Then, global interrupts are
+    /// enabled, so the corresponding interrupt handler runs before `condition` is evaluated again.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub unsafe fn wait_for_interrupt<F: FnMut() -> bool>(condition: F) {
+        crate::csr::wait_until(condition);
+    }
+
+    /// Returns `true` if machine software interrupts can be delivered to the current HART,
+    /// i.e., if both the `msoft` bit of the `mie` CSR and the `MIE` bit of the `mstatus` CSR are set.
+    ///
+    /// # Note
+    ///
+    /// This method is intended for debugging the interrupt setup.
+    #[inline]
+    pub fn mswi_deliverable() -> bool {
+        crate::csr::mstatus::mie() && crate::csr::mie::msoft()
+    }
+
+    /// Returns `true` if the `MTIMECMP` register of the HART which ID is `hart_id` will deliver
+    /// a machine timer interrupt, i.e., if the `mtimer` bit of the `mie` CSR and the `MIE` bit of
+    /// the `mstatus` CSR are set, and the deadline of `MTIMECMP` is still in the future.
+    ///
+    /// # Note
+    ///
+    /// This method is intended for debugging the interrupt setup. The `mie` and `mstatus` CSRs
+    /// belong to the current HART, so `hart_id` must be the ID of the current HART.
+    /// If the deadline is already in the past, it returns `false` even if the interrupt is enabled,
+    /// as a pending machine timer interrupt is taken as soon as it is enabled.
+    #[inline]
+    pub fn mtimer_deliverable<H: HartIdNumber>(hart_id: H) -> bool {
+        let mtimer = Self::mtimer();
+        mtimer_deliverable(mtimer.mtimecmp(hart_id), mtimer.mtime)
+    }
+
+    /// Returns which CLINT interrupt sources are pending in the current HART.
+    ///
+    /// The `mip` CSR is read only once, so both flags are consistent with each other.
+    /// This is intended for dispatching CLINT interrupts in trap handlers.
+    #[inline]
+    pub fn interrupting_sources() -> InterruptSources {
+        InterruptSources::from_bits(crate::csr::mip::read())
+    }
+
+    /// Runs `f` with machine software **AND** timer interrupts disabled.
+    ///
+    /// The previous state of the `msoft` and `mtimer` bits of the `mie` register is
+    /// captured before disabling them, and restored afterwards, even if `f` returns early or panics.
+    /// Interrupts that were disabled before calling this method remain disabled.
+    #[inline]
+    pub fn with_interrupts_disabled<F: FnOnce() -> R, R>(f: F) -> R {
+        let _guard = InterruptsGuard::new();
+        f()
+    }
+}
+
+/// Pending CLINT interrupt sources of a HART, as returned by [`CLINT::interrupting_sources`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct InterruptSources {
+    /// `true` if a machine timer interrupt is pending (`mip.MTIP`).
+    pub timer: bool,
+    /// `true` if a machine software interrupt is pending (`mip.MSIP`).
+    pub software: bool,
+}
+
+impl InterruptSources {
+    /// Machine Software Interrupt Pending bit of the `mip` CSR.
+    const MSIP: usize = 1 << 3;
+    /// Machine Timer Interrupt Pending bit of the `mip` CSR.
+    const MTIP: usize = 1 << 7;
+
+    /// Decodes the CLINT interrupt sources from a raw `mip` value.
+    #[inline]
+    pub const fn from_bits(mip: usize) -> Self {
+        Self {
+            timer: mip & Self::MTIP != 0,
+            software: mip & Self::MSIP != 0,
+        }
+    }
+
+    /// Returns `true` if any CLINT interrupt source is pending.
+    #[inline]
+    pub const fn any(self) -> bool {
+        self.timer || self.software
+    }
+}
+
+/// Returns `true` if `mtimecmp` will deliver a machine timer interrupt to the current HART.
+/// See [`CLINT::mtimer_deliverable`] for more details.
+#[inline]
+fn mtimer_deliverable(mtimecmp: mtimer::MTIMECMP, mtime: mtimer::MTIME) -> bool {
+    crate::csr::mstatus::mie() && crate::csr::mie::mtimer() && !mtimecmp.is_expired(mtime)
+}
+
+/// Drop guard that restores the `msoft` and `mtimer` bits of the `mie` register.
+struct InterruptsGuard {
+    msoft: bool,
+    mtimer: bool,
+}
+
+impl InterruptsGuard {
+    /// Captures the current state of the `msoft` and `mtimer` bits and clears them.
+    #[inline]
+    fn new() -> Self {
+        let guard = Self {
+            msoft: crate::csr::mie::msoft(),
+            mtimer: crate::csr::mie::mtimer(),
+        };
+        crate::csr::mie::clear_msoft();
+        crate::csr::mie::clear_mtimer();
+        guard
+    }
+}
+
+impl Drop for InterruptsGuard {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: we only re-enable interrupts that were enabled before
+        unsafe {
+            if self.msoft {
+                crate::csr::mie::set_msoft();
+            }
+            if self.mtimer {
+                crate::csr::mie::set_mtimer();
+            }
         }
     }
 }
@@ -116,6 +375,44 @@ pub(crate) mod test {
         }
     }
 
+    /// HART IDs of a target without HART 1.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[repr(u16)]
+    pub(crate) enum GappedHartId {
+        H0 = 0,
+        H2 = 2,
+        H3 = 3,
+    }
+
+    unsafe impl HartIdNumber for GappedHartId {
+        const MAX_HART_ID_NUMBER: u16 = 3;
+
+        #[inline]
+        fn number(self) -> u16 {
+            self as _
+        }
+
+        #[inline]
+        fn from_number(number: u16) -> Result<Self, u16> {
+            match number {
+                0 => Ok(Self::H0),
+                2 => Ok(Self::H2),
+                3 => Ok(Self::H3),
+                _ => Err(number),
+            }
+        }
+    }
+
+    /// CLINT of the tests that only use its CSR-backed functionality.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    struct TestClint;
+
+    unsafe impl super::Clint for TestClint {
+        const BASE: usize = 0;
+    }
+
+    type Clint = super::CLINT<TestClint>;
+
     #[test]
     fn check_hart_id_enum() {
         assert_eq!(HartId::H0.number(), 0);
@@ -127,6 +424,183 @@ pub(crate) mod test {
         assert_eq!(HartId::from_number(2), Ok(HartId::H2));
 
         assert_eq!(HartId::from_number(3), Err(3));
+
+        assert_eq!(HartId::try_from_number_opt(1), Some(HartId::H1));
+        assert_eq!(HartId::try_from_number_opt(3), None);
+        assert_eq!(HartId::try_from_number(1), Ok(HartId::H1));
+        assert_eq!(
+            HartId::try_from_number(3),
+            Err(crate::error::InvalidHartId(3))
+        );
+        assert_eq!(HartId::from_number_saturating(1), HartId::H1);
+        assert_eq!(HartId::from_number_saturating(3), HartId::H2);
+        assert_eq!(HartId::from_number_saturating(u16::MAX), HartId::H2);
+    }
+
+    #[test]
+    fn check_custom_offsets() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct CustomClint;
+
+        unsafe impl super::Clint for CustomClint {
+            const BASE: usize = 0x0200_0000;
+            const MSWI_OFFSET: usize = 0x100;
+            const MTIMER_OFFSET: usize = 0x8000;
+            const MTIMECMP_STRIDE: usize = 0x10;
+            const MTIME_OFFSET: usize = 0x7FF8;
+        }
+
+        type Custom = super::CLINT<CustomClint>;
+
+        assert_eq!(Custom::mswi().msip0.get_ptr() as usize, 0x0200_0100);
+        let mtimer = Custom::mtimer();
+        assert_eq!(mtimer.mtimecmp0.get_ptr() as usize, 0x0200_8000);
+        assert_eq!(
+            mtimer.mtimecmp(HartId::H2).get_ptr() as usize,
+            0x0200_8000 + 2 * 0x10
+        );
+        assert_eq!(mtimer.mtime.get_ptr() as usize, 0x0200_7FF8);
+    }
+
+    #[test]
+    fn check_split_bases() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct SplitAclint;
+
+        unsafe impl super::Clint for SplitAclint {
+            const BASE: usize = 0x0200_0000;
+            const MSWI_BASE: usize = 0x0200_0000;
+            const MTIMER_BASE: usize = 0x0300_0000;
+            const MTIME_BASE: usize = 0x0300_0000 + super::ACLINT_MTIME_OFFSET;
+        }
+
+        type Split = super::CLINT<SplitAclint>;
+
+        assert_eq!(Split::mswi().msip0.get_ptr() as usize, 0x0200_0000);
+        let mtimer = Split::mtimer();
+        assert_eq!(mtimer.mtimecmp0.get_ptr() as usize, 0x0300_0000);
+        assert_eq!(
+            mtimer.mtimecmp(HartId::H2).get_ptr() as usize,
+            0x0300_0000 + 2 * 8
+        );
+        assert_eq!(mtimer.mtime.get_ptr() as usize, 0x0300_7FF8);
+    }
+
+    #[test]
+    fn check_wait_for_interrupt() {
+        use crate::csr::mstatus;
+
+        std::thread_local! {
+            static WFIS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+        }
+        // the HART must never sleep with global interrupts enabled
+        crate::csr::set_wfi_hook(Some(|| {
+            assert!(!mstatus::mie());
+            WFIS.with(|wfis| wfis.set(wfis.get() + 1));
+        }));
+
+        let mut checks = 0;
+        unsafe {
+            Clint::wait_for_interrupt(|| {
+                assert!(!mstatus::mie());
+                checks += 1;
+                WFIS.with(|wfis| wfis.get()) == 2
+            })
+        };
+        assert_eq!(checks, 3);
+        assert!(mstatus::mie());
+
+        // no wfi if the condition already holds
+        unsafe { Clint::wait_for_interrupt(|| true) };
+        assert_eq!(WFIS.with(|wfis| wfis.get()), 2);
+        assert!(mstatus::mie());
+
+        crate::csr::set_wfi_hook(None);
+        mstatus::clear_mie();
+    }
+
+    #[test]
+    fn check_with_interrupts_disabled() {
+        use crate::csr::mie;
+
+        // msoft on, mtimer off
+        unsafe { mie::set_msoft() };
+        mie::clear_mtimer();
+        let res = Clint::with_interrupts_disabled(|| {
+            assert!(!mie::msoft());
+            assert!(!mie::mtimer());
+            42
+        });
+        assert_eq!(res, 42);
+        assert!(mie::msoft());
+        assert!(!mie::mtimer());
+
+        // msoft off, mtimer on, restored even if the closure panics
+        mie::clear_msoft();
+        unsafe { mie::set_mtimer() };
+        let res = std::panic::catch_unwind(|| {
+            Clint::with_interrupts_disabled(|| panic!("closure panicked"))
+        });
+        assert!(res.is_err());
+        assert!(!mie::msoft());
+        assert!(mie::mtimer());
+    }
+
+    #[test]
+    fn check_interrupting_sources() {
+        use super::InterruptSources;
+
+        // supervisor timer pending bit (5) must be ignored
+        let cases = [
+            (0, false, false),
+            (1 << 3, false, true),
+            (1 << 7, true, false),
+            ((1 << 3) | (1 << 7) | (1 << 5), true, true),
+        ];
+        for (mip, timer, software) in cases {
+            crate::csr::mip::set(mip);
+            let sources = Clint::interrupting_sources();
+            assert_eq!(sources, InterruptSources { timer, software });
+            assert_eq!(sources, InterruptSources::from_bits(mip));
+            assert_eq!(sources.any(), timer || software);
+        }
+    }
+
+    #[test]
+    fn check_deliverable() {
+        use crate::csr::{mie, mstatus};
+
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                super::mtimer::MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                super::mtimer::MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+        mtime.write(100);
+        mtimecmp.write(200);
+
+        // both mie and mstatus.MIE are required
+        assert!(!Clint::mswi_deliverable());
+        assert!(!super::mtimer_deliverable(mtimecmp, mtime));
+        unsafe { mie::set_msoft() };
+        unsafe { mie::set_mtimer() };
+        assert!(!Clint::mswi_deliverable());
+        assert!(!super::mtimer_deliverable(mtimecmp, mtime));
+        unsafe { mstatus::set_mie() };
+        assert!(Clint::mswi_deliverable());
+        assert!(super::mtimer_deliverable(mtimecmp, mtime));
+        mie::clear_msoft();
+        assert!(!Clint::mswi_deliverable());
+
+        // the deadline must be in the future
+        mtime.write(200);
+        assert!(!super::mtimer_deliverable(mtimecmp, mtime));
+
+        mie::clear_mtimer();
+        mstatus::clear_mie();
     }
 
     #[allow(dead_code)]
@@ -137,11 +611,26 @@ pub(crate) mod test {
             base 0x0200_0000,
             mtimecmps [mtimecmp0=(HartId::H0,"`H0`"), mtimecmp1=(HartId::H1,"`H1`"), mtimecmp2=(HartId::H2,"`H2`")],
             msips [msip0=(HartId::H0,"`H0`"), msip1=(HartId::H1,"`H1`"), msip2=(HartId::H2,"`H2`")],
+            harts HartId,
         );
 
         let mswi = CLINT::mswi();
         let mtimer = CLINT::mtimer();
 
+        const MTIME_ADDR: usize = CLINT::MTIME_ADDR;
+        const MTIMECMP2_ADDR: usize = CLINT::mtimecmp_addr(2);
+        assert_eq!(CLINT::MSWI_ADDR, 0x0200_0000);
+        assert_eq!(CLINT::MTIMER_ADDR, 0x0200_4000);
+        assert_eq!(MTIME_ADDR, 0x0200_bff8);
+        assert_eq!(
+            CLINT::msip_addr(2),
+            mswi.msip(HartId::H2).get_ptr() as usize
+        );
+        assert_eq!(
+            MTIMECMP2_ADDR,
+            mtimer.mtimecmp(HartId::H2).get_ptr() as usize
+        );
+
         assert_eq!(mswi.msip0.get_ptr() as usize, 0x0200_0000);
         assert_eq!(mtimer.mtimecmp0.get_ptr() as usize, 0x0200_4000);
         assert_eq!(mtimer.mtime.get_ptr() as usize, 0x0200_bff8);
@@ -155,6 +644,18 @@ pub(crate) mod test {
         assert_eq!(mtimecmp2.get_ptr() as usize, 0x0200_4000 + 2 * 8);
 
         assert_eq!(CLINT::mtime(), mtimer.mtime);
+
+        let mut delay = CLINT::delay_with_freq(32_768);
+        assert_eq!(delay.get_mtime(), CLINT::mtime());
+        assert_eq!(delay.get_freq(), 32_768);
+        delay.set_freq(1_000_000);
+        assert_eq!(delay.get_freq(), 1_000_000);
+        assert_eq!(delay.get_mtimecmp(), None);
+
+        let delay = super::CLINT::<CLINT>::delay_on(HartId::H2, 32_768);
+        assert_eq!(delay.get_mtime(), CLINT::mtime());
+        assert_eq!(delay.get_mtimecmp(), Some(mtimer.mtimecmp(HartId::H2)));
+        assert_eq!(delay.get_freq(), 32_768);
         assert_eq!(CLINT::mtimecmp0(), mtimer.mtimecmp(HartId::H0));
         assert_eq!(CLINT::mtimecmp1(), mtimer.mtimecmp(HartId::H1));
         assert_eq!(CLINT::mtimecmp2(), mtimer.mtimecmp(HartId::H2));
@@ -162,5 +663,13 @@ pub(crate) mod test {
         assert_eq!(CLINT::msip0(), mswi.msip(HartId::H0));
         assert_eq!(CLINT::msip1(), mswi.msip(HartId::H1));
         assert_eq!(CLINT::msip2(), mswi.msip(HartId::H2));
+
+        assert_eq!(CLINT::mtimecmp(HartId::H0), CLINT::mtimecmp0());
+        assert_eq!(CLINT::mtimecmp(HartId::H1), CLINT::mtimecmp1());
+        assert_eq!(CLINT::mtimecmp(HartId::H2), CLINT::mtimecmp2());
+
+        assert_eq!(CLINT::msip(HartId::H0), CLINT::msip0());
+        assert_eq!(CLINT::msip(HartId::H1), CLINT::msip1());
+        assert_eq!(CLINT::msip(HartId::H2), CLINT::msip2());
     }
 }
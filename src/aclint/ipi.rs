@@ -0,0 +1,135 @@
+//! Inter-Processor Interrupts (IPI) over the Machine-level Software Interrupt Device.
+
+pub use super::HartIdNumber;
+use super::{mswi::MSWI, Clint, CLINT};
+use crate::error::InvalidHartId;
+
+/// Inter-processor interrupts interface of the CLINT `C`.
+///
+/// It uses the `MSIP` registers of the `MSWI` peripheral of `C` to send
+/// machine software interrupts to the HARTs of the target.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ipi<C: Clint> {
+    mswi: MSWI,
+    _marker: core::marker::PhantomData<C>,
+}
+
+impl<C: Clint> Ipi<C> {
+    /// Creates a new `Ipi` interface from the `MSWI` peripheral of the CLINT `C`.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_mswi(CLINT::<C>::mswi())
+    }
+
+    /// Creates a new `Ipi` interface backed by an arbitrary `MSWI` peripheral (e.g., host memory).
+    #[inline]
+    const fn with_mswi(mswi: MSWI) -> Self {
+        Self {
+            mswi,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the underlying `MSWI` peripheral.
+    #[inline]
+    pub const fn mswi(self) -> MSWI {
+        self.mswi
+    }
+
+    /// Sends an inter-processor interrupt to the HART which ID is `hart_id`.
+    ///
+    /// # Safety
+    ///
+    /// Pending a machine software interrupt may break mask-based critical sections.
+    #[inline]
+    pub unsafe fn notify<H: HartIdNumber>(self, hart_id: H) {
+        self.mswi.msip(hart_id).pend();
+    }
+
+    /// Sends an inter-processor interrupt to all the HARTs in `hart_ids`.
+    ///
+    /// # Safety
+    ///
+    /// Pending a machine software interrupt may break mask-based critical sections.
+    #[inline]
+    pub unsafe fn notify_all<H: HartIdNumber>(self, hart_ids: impl IntoIterator<Item = H>) {
+        for hart_id in hart_ids {
+            self.notify(hart_id);
+        }
+    }
+
+    /// Clears the pending inter-processor interrupt of the current HART.
+    ///
+    /// # Note
+    ///
+    /// The current HART ID is read from the `mhartid` CSR. If it does not correspond
+    /// to any HART ID of `H`, no register is accessed and it returns an error.
+    #[inline]
+    pub fn clear<H: HartIdNumber>(self) -> Result<(), InvalidHartId> {
+        let hart_id = u16::try_from(crate::csr::mhartid::read()).unwrap_or(u16::MAX);
+        let hart_id = H::try_from_number(hart_id)?;
+        self.mswi.msip(hart_id).unpend();
+        Ok(())
+    }
+}
+
+impl<C: Clint> Default for Ipi<C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::HartId;
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    struct TestClint;
+
+    unsafe impl Clint for TestClint {
+        const BASE: usize = 0x0200_0000;
+        const MSWI_OFFSET: usize = 0x100;
+    }
+
+    #[test]
+    fn test_ipi_address() {
+        let ipi = Ipi::<TestClint>::new();
+        assert_eq!(ipi.mswi(), CLINT::<TestClint>::mswi());
+        assert_eq!(ipi.mswi().msip0.get_ptr() as usize, 0x0200_0100);
+    }
+
+    #[test]
+    fn test_ipi() {
+        // slice to emulate the MSIP registers
+        let mut raw_reg = [0u32; HartId::MAX_HART_ID_NUMBER as usize + 1];
+        // SAFETY: valid memory address
+        let ipi = Ipi::<TestClint>::with_mswi(unsafe { MSWI::new(raw_reg.as_mut_ptr() as _) });
+
+        unsafe { ipi.notify(HartId::H2) };
+        assert_eq!(raw_reg[0], 0);
+        assert_eq!(raw_reg[1], 0);
+        assert_ne!(raw_reg[2], 0);
+
+        unsafe { ipi.notify_all([HartId::H0, HartId::H1]) };
+        for hart_id in [HartId::H0, HartId::H1, HartId::H2] {
+            assert!(ipi.mswi().msip(hart_id).is_pending());
+        }
+
+        // only the IPI of the current HART is cleared
+        crate::csr::mhartid::set(1);
+        assert_eq!(ipi.clear::<HartId>(), Ok(()));
+        assert!(ipi.mswi().msip(HartId::H0).is_pending());
+        assert!(!ipi.mswi().msip(HartId::H1).is_pending());
+        assert!(ipi.mswi().msip(HartId::H2).is_pending());
+
+        // HART IDs unknown to the PAC are reported instead of panicking
+        crate::csr::mhartid::set(7);
+        assert_eq!(ipi.clear::<HartId>(), Err(InvalidHartId(7)));
+        crate::csr::mhartid::set(usize::MAX);
+        assert_eq!(ipi.clear::<HartId>(), Err(InvalidHartId(u16::MAX)));
+        assert!(ipi.mswi().msip(HartId::H0).is_pending());
+        assert!(ipi.mswi().msip(HartId::H2).is_pending());
+    }
+}
@@ -3,6 +3,9 @@
 pub use super::HartIdNumber;
 use crate::common::unsafe_peripheral;
 
+/// Highest HART index supported by the MSWI device.
+const MAX_HART_INDEX: u16 = 4094;
+
 /// MSWI peripheral.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(transparent)]
@@ -35,6 +38,35 @@ impl MSWI {
         // SAFETY: `hart_id` is valid for the target
         unsafe { MSIP::new(self.msip0.get_ptr().offset(hart_id.number() as _) as _) }
     }
+
+    /// Returns the `MSIP` register for the HART which index is `hart_index`.
+    ///
+    /// Unlike [`MSWI::msip`], it does not require a [`HartIdNumber`] type.
+    /// Thus, it can be used very early in the boot process (e.g., for waking up secondary HARTs).
+    ///
+    /// # Note
+    ///
+    /// In debug builds, it panics if `hart_index` exceeds the 4_095 HARTs supported by the MSWI device.
+    ///
+    /// # Safety
+    ///
+    /// `hart_index` must correspond to a HART of the target.
+    #[inline]
+    pub unsafe fn msip_raw(&self, hart_index: u16) -> MSIP {
+        debug_assert!(hart_index <= MAX_HART_INDEX, "HART index out of bounds");
+        // SAFETY: the caller guarantees that `hart_index` is a HART of the target, so the
+        // offset stays within the `MSIP` registers of the MSWI device
+        MSIP::new(self.msip0.get_ptr().add(hart_index as _) as _)
+    }
+
+    /// Returns an iterator over all the HARTs of the target and their `MSIP` registers.
+    /// HART IDs go from 0 to `H::MAX_HART_ID_NUMBER`, skipping the ones rejected by `H::from_number`.
+    #[inline]
+    pub fn harts<H: HartIdNumber>(self) -> impl Iterator<Item = (H, MSIP)> {
+        (0..=H::MAX_HART_ID_NUMBER)
+            .filter_map(|n| H::from_number(n).ok())
+            .map(move |hart_id| (hart_id, self.msip(hart_id)))
+    }
 }
 
 unsafe_peripheral!(MSIP, u32, RW);
@@ -47,8 +79,12 @@ impl MSIP {
     }
 
     /// Writes to the register to trigger a machine software interrupt.
+    ///
+    /// # Safety
+    ///
+    /// Pending a machine software interrupt may break mask-based critical sections.
     #[inline]
-    pub fn pend(self) {
+    pub unsafe fn pend(self) {
         self.register.write(1);
     }
 
@@ -76,12 +112,22 @@ mod test {
             let msip = mswi.msip(hart_id);
             assert!(!msip.is_pending());
             assert_eq!(raw_reg[i as usize], 0);
-            msip.pend();
+            unsafe { msip.pend() };
             assert!(msip.is_pending());
             assert_ne!(raw_reg[i as usize], 0);
             msip.unpend();
             assert!(!msip.is_pending());
             assert_eq!(raw_reg[i as usize], 0);
         }
+
+        let base = raw_reg.as_ptr() as usize;
+        assert_eq!(unsafe { mswi.msip_raw(0) }, mswi.msip0);
+        assert_eq!(unsafe { mswi.msip_raw(2) }.get_ptr() as usize, base + 8);
+        assert_eq!(unsafe { mswi.msip_raw(2) }, mswi.msip(HartId::H2));
+
+        assert_eq!(mswi.harts::<HartId>().count(), 3);
+        for (hart_id, msip) in mswi.harts::<HartId>() {
+            assert_eq!(msip, mswi.msip(hart_id));
+        }
     }
 }
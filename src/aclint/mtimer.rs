@@ -1,16 +1,37 @@
 //! Machine-level Timer Device.
+//!
+//! # Supervisor timer interrupts without Sstc
+//!
+//! On targets without the Sstc extension, S-mode cannot program a timer by itself.
+//! Instead, the M-mode firmware emulates it with the `MTIMECMP` register of each HART:
+//!
+//! 1. S-mode requests a deadline (e.g., with the SBI `set_timer` call). The M-mode firmware
+//!    calls `MTIMECMP::set_supervisor_deadline`, which clears `mip.STIP` and programs the deadline.
+//! 2. When the deadline is reached, M-mode takes a machine timer interrupt. Its handler calls
+//!    `MTIMECMP::forward_to_supervisor`, which clears the machine timer interrupt (by setting the
+//!    register to [`u64::MAX`]) and sets `mip.STIP` to raise the supervisor timer interrupt.
+//! 3. S-mode handles the supervisor timer interrupt (delegated via `mideleg`) and goes back to step 1.
+//!    The pending condition is only cleared by step 1, as S-mode cannot write `sip.STIP`.
+//!
+//! These methods are only available with the `s-mode-timer` feature.
+//! Virtual supervisor timer interrupts (`hvip.VSTIP`) are managed by the hypervisor in HS-mode,
+//! and are out of the scope of this module.
 
 pub use super::HartIdNumber;
-use crate::common::safe_peripheral;
+use crate::common::{safe_peripheral, Reg, RW};
+use crate::error::{InvalidPeriod, TimeoutError};
 
 /// MTIMER peripheral.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct MTIMER {
     /// `MTIMECMP` register for HART ID 0.  In multi-HART architectures,
     /// use [`MTIMER::mtimecmp`] for accessing the `MTIMECMP` of other HARTs.
     pub mtimecmp0: MTIMECMP,
     /// The `MTIME` register is shared among all the HARTs.
     pub mtime: MTIME,
+    /// Separation (in bytes) between the `MTIMECMP` registers of consecutive HARTs.
+    mtimecmp_stride: usize,
 }
 
 impl MTIMER {
@@ -21,9 +42,26 @@ impl MTIMER {
     /// The base addresses must point to valid `MTIMECMP` and `MTIME` peripherals.
     #[inline]
     pub const unsafe fn new(mtimecmp: usize, mtime: usize) -> Self {
+        Self::new_with_stride(mtimecmp, mtime, core::mem::size_of::<u64>())
+    }
+
+    /// Creates a new `MTIMER` peripheral from a base address and a custom separation
+    /// (in bytes) between the `MTIMECMP` registers of consecutive HARTs.
+    ///
+    /// # Safety
+    ///
+    /// The base addresses must point to valid `MTIMECMP` and `MTIME` peripherals,
+    /// and `mtimecmp_stride` must match the layout of the target.
+    #[inline]
+    pub const unsafe fn new_with_stride(
+        mtimecmp: usize,
+        mtime: usize,
+        mtimecmp_stride: usize,
+    ) -> Self {
         Self {
             mtimecmp0: MTIMECMP::new(mtimecmp),
             mtime: MTIME::new(mtime),
+            mtimecmp_stride,
         }
     }
 
@@ -32,19 +70,480 @@ impl MTIMER {
     /// # Note
     ///
     /// For HART ID 0, you can simply use [`MTIMER::mtimecmp0`].
+    ///
+    /// This method does not perform any bounds check, as the safety contract of
+    /// [`HartIdNumber`] guarantees that `hart_id` is a valid HART ID for the target.
+    /// Use [`MTIMER::try_mtimecmp`] for raw HART ID numbers.
     #[inline]
     pub fn mtimecmp<H: HartIdNumber>(&self, hart_id: H) -> MTIMECMP {
         // SAFETY: `hart_id` is valid for the target
-        unsafe { MTIMECMP::new(self.mtimecmp0.get_ptr().offset(hart_id.number() as _) as _) }
+        unsafe {
+            MTIMECMP::new(
+                self.mtimecmp0.get_ptr() as usize
+                    + hart_id.number() as usize * self.mtimecmp_stride,
+            )
+        }
+    }
+
+    /// Returns the `MTIMECMP` register for the HART which raw ID number is `hart_id`.
+    /// It returns `None` if `hart_id` is not a valid HART ID number for `H`.
+    #[inline]
+    pub fn try_mtimecmp<H: HartIdNumber>(&self, hart_id: u16) -> Option<MTIMECMP> {
+        if hart_id > H::MAX_HART_ID_NUMBER {
+            return None;
+        }
+        H::from_number(hart_id)
+            .ok()
+            .map(|hart_id| self.mtimecmp(hart_id))
+    }
+
+    /// Estimates the frequency (in Hz) of the `MTIME` counter.
+    ///
+    /// It reads `mtime`, busy-waits for at least `sample_cycles` CPU cycles (measured with `rdcycle`),
+    /// reads `mtime` again, and scales the elapsed ticks by the CPU frequency `cpu_hz`.
+    ///
+    /// # Note
+    ///
+    /// The result is only an estimate, intended for bring-up of targets with an unknown timer frequency:
+    ///
+    /// * The resolution is `cpu_hz / sample_cycles` Hz, so longer samples give more accurate results.
+    /// * `cpu_hz` must be the actual frequency of the `cycle` counter, which must be running.
+    /// * Interrupts taken during the sample are accounted correctly, but frequency scaling is not.
+    #[inline]
+    pub fn estimate_frequency(&self, cpu_hz: u64, sample_cycles: u64) -> u64 {
+        let (t0, c0) = (self.mtime.read(), crate::csr::cycle::read64());
+        let mut c1 = c0;
+        while c1.wrapping_sub(c0) < sample_cycles {
+            c1 = crate::csr::cycle::read64();
+        }
+        let t1 = self.mtime.read();
+        scale_frequency(t1.wrapping_sub(t0), c1.wrapping_sub(c0), cpu_hz)
+    }
+
+    /// Busy-waits until `cond` returns `true` or `timeout_ticks` ticks of `MTIME` elapse.
+    ///
+    /// On success, it returns the number of elapsed ticks when `cond` returned `true`.
+    /// Otherwise, it returns a [`TimeoutError`] with the number of elapsed ticks.
+    ///
+    /// # Note
+    ///
+    /// Elapsed ticks are computed with wrapping arithmetic, so the timeout is correct
+    /// even if `MTIME` wraps around while waiting.
+    #[inline]
+    pub fn wait_until<F: FnMut() -> bool>(
+        &self,
+        timeout_ticks: u64,
+        mut cond: F,
+    ) -> Result<u64, TimeoutError> {
+        let t0 = self.mtime.read();
+        loop {
+            let elapsed = self.mtime.read().wrapping_sub(t0);
+            if cond() {
+                return Ok(elapsed);
+            }
+            if elapsed >= timeout_ticks {
+                return Err(TimeoutError(elapsed));
+            }
+        }
+    }
+
+    /// Returns an iterator over all the HARTs of the target and their `MTIMECMP` registers.
+    /// HART IDs go from 0 to `H::MAX_HART_ID_NUMBER`, skipping the ones rejected by `H::from_number`.
+    #[inline]
+    pub fn harts<H: HartIdNumber>(self) -> impl Iterator<Item = (H, MTIMECMP)> {
+        (0..=H::MAX_HART_ID_NUMBER)
+            .filter_map(|n| H::from_number(n).ok())
+            .map(move |hart_id| (hart_id, self.mtimecmp(hart_id)))
+    }
+
+    /// Returns a future that resolves `ticks` ticks after the current value of `mtime`.
+    ///
+    /// # Note
+    ///
+    /// The returned future uses the [`MTIMER::mtimecmp0`] register. Check the [`super::timer`]
+    /// module documentation for the requirements that must be fulfilled to use it.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub fn sleep(self, ticks: u64) -> super::timer::TimerFuture {
+        let deadline = self.mtime.read().saturating_add(ticks);
+        super::timer::TimerFuture::new(self, deadline)
+    }
+
+    /// Returns a future that resolves `duration` after the current value of `mtime`.
+    /// `FREQ` must be the frequency (in Hz) of the `MTIME` counter.
+    ///
+    /// With the `fugit` feature, `duration` can also be any `fugit` duration.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub fn sleep_for<const FREQ: u32>(
+        self,
+        duration: impl Into<super::time::Duration<FREQ>>,
+    ) -> super::timer::TimerFuture {
+        self.sleep(duration.into().ticks())
+    }
+
+    /// Programs the `MTIMECMP` register of the HART which ID is `hart_id` to fire
+    /// every `period_ticks` ticks, starting `period_ticks` ticks after the current value of `mtime`.
+    ///
+    /// Call [`PeriodicTick::on_tick`] on the returned handle from the machine timer interrupt handler
+    /// to reload the register for the next period.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `period_ticks` is zero.
+    #[inline]
+    pub fn schedule_periodic<H: HartIdNumber>(
+        &self,
+        hart_id: H,
+        period_ticks: u64,
+    ) -> PeriodicTick {
+        assert!(period_ticks > 0, "the period must not be zero");
+        let tick = PeriodicTick {
+            mtimecmp: self.mtimecmp(hart_id),
+            mtime: self.mtime,
+            period: period_ticks,
+        };
+        tick.mtimecmp
+            .set_deadline_from_now(tick.mtime, period_ticks);
+        tick
+    }
+
+    /// Programs the `MTIMECMP` register of the HART which ID is `hart_id` to fire `us` microseconds
+    /// after the current value of `mtime`. `freq` is the frequency (in Hz) of the `MTIME` counter.
+    ///
+    /// It returns the period in ticks, rounded to the nearest tick. Use it for drift-free reloads
+    /// of the register (e.g., with [`MTIMER::schedule_periodic`]). If the period rounds to zero ticks,
+    /// it returns an [`InvalidPeriod`] error and the register is not modified.
+    #[inline]
+    pub fn set_tick_period_us<H: HartIdNumber>(
+        &self,
+        hart_id: H,
+        us: u64,
+        freq: u64,
+    ) -> Result<u64, InvalidPeriod> {
+        let ticks = (us as u128 * freq as u128 + 500_000) / 1_000_000;
+        match ticks {
+            0 => Err(InvalidPeriod(us)),
+            _ => {
+                let ticks = ticks.min(u64::MAX as u128) as u64;
+                self.mtimecmp(hart_id)
+                    .set_deadline_from_now(self.mtime, ticks);
+                Ok(ticks)
+            }
+        }
+    }
+}
+
+/// Periodic machine timer interrupt of a HART, created with [`MTIMER::schedule_periodic`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PeriodicTick {
+    mtimecmp: MTIMECMP,
+    mtime: MTIME,
+    period: u64,
+}
+
+impl PeriodicTick {
+    /// Returns the period (in `MTIME` ticks).
+    #[inline]
+    pub const fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Returns the `MTIMECMP` register used by the periodic tick.
+    #[inline]
+    pub const fn mtimecmp(&self) -> MTIMECMP {
+        self.mtimecmp
+    }
+
+    /// Reloads the `MTIMECMP` register for the next period and returns the number of elapsed periods.
+    ///
+    /// The next deadline is computed relative to the *previous* deadline, not to the current time,
+    /// so the latency of the interrupt handler does not accumulate over time:
+    ///
+    /// `next = prev + k * period`, with `k = (now - prev) / period + 1`
+    ///
+    /// Usually, `k` is 1. If the handler was delayed past several periods, the missed periods are
+    /// skipped and `k` is greater than 1, so the next deadline is always in the future.
+    /// If the current deadline has not been reached yet (e.g., spurious calls), the register is
+    /// left unchanged and it returns 0.
+    ///
+    /// # Note
+    ///
+    /// This method **must** be called from the machine timer interrupt handler.
+    #[inline]
+    pub fn on_tick(&self) -> u64 {
+        let prev = self.mtimecmp.read();
+        let now = self.mtime.read();
+        if now < prev {
+            return 0;
+        }
+        let elapsed = (now - prev) / self.period + 1;
+        self.mtimecmp
+            .set_deadline(prev.saturating_add(elapsed.saturating_mul(self.period)));
+        elapsed
     }
 }
 
+/// Returns the frequency of a counter that advanced `ticks` ticks during `cycles` cycles of a `cpu_hz` Hz clock.
+fn scale_frequency(ticks: u64, cycles: u64, cpu_hz: u64) -> u64 {
+    match cycles {
+        0 => 0,
+        _ => (ticks as u128 * cpu_hz as u128 / cycles as u128).min(u64::MAX as u128) as u64,
+    }
+}
+
+/// Returns a 32-bit word of a 64-bit register (0 for the low half, 1 for the high half).
+#[inline]
+fn half_word(register: Reg<u64, RW>, word: usize) -> Reg<u32, RW> {
+    debug_assert!(word < 2);
+    // SAFETY: valid address within the 64-bit register
+    unsafe { Reg::new((register.get_ptr() as *mut u32).add(word)) }
+}
+
 // MTIMECMP register.
 safe_peripheral!(MTIMECMP, u64, RW);
 
+impl MTIMECMP {
+    /// Sequence of `(word, value)` writes of [`MTIMECMP::set_deadline_split`].
+    /// Word 0 is the low half of the register and word 1 is the high half.
+    #[inline]
+    const fn split_sequence(deadline: u64) -> [(usize, u32); 3] {
+        [
+            (1, u32::MAX),
+            (0, deadline as u32),
+            (1, (deadline >> 32) as u32),
+        ]
+    }
+
+    /// Writes a 32-bit word of the register (0 for the low half, 1 for the high half).
+    #[inline]
+    fn write_word(self, word: usize, value: u32) {
+        half_word(self.register, word).write(value);
+    }
+
+    /// Writes the low 32 bits of the register (offset `+0`), leaving the high half unchanged.
+    ///
+    /// # Note
+    ///
+    /// Writing a single half may make the register temporarily hold an earlier deadline,
+    /// triggering a spurious interrupt. Use [`MTIMECMP::set_deadline_split`] unless you
+    /// manage the write sequence yourself.
+    #[inline]
+    pub fn write_lo(self, value: u32) {
+        self.write_word(0, value);
+    }
+
+    /// Writes the high 32 bits of the register (offset `+4`), leaving the low half unchanged.
+    ///
+    /// # Note
+    ///
+    /// Writing a single half may make the register temporarily hold an earlier deadline,
+    /// triggering a spurious interrupt. Use [`MTIMECMP::set_deadline_split`] unless you
+    /// manage the write sequence yourself.
+    #[inline]
+    pub fn write_hi(self, value: u32) {
+        self.write_word(1, value);
+    }
+
+    /// Programs the register with an absolute `deadline` as two 32-bit halves.
+    ///
+    /// It follows the canonical sequence of the RISC-V privileged specification:
+    ///
+    /// 1. The high half is set to all ones, so the register holds a value in the far future.
+    /// 2. The low half is set to the low half of `deadline`.
+    /// 3. The high half is set to the high half of `deadline`.
+    ///
+    /// Thus, the register never holds an intermediate value earlier than `deadline`.
+    #[inline]
+    pub fn set_deadline_split(self, deadline: u64) {
+        for (word, value) in Self::split_sequence(deadline) {
+            self.write_word(word, value);
+        }
+    }
+
+    /// Programs the register with an absolute `deadline` (in `MTIME` ticks).
+    ///
+    /// # Note
+    ///
+    /// On 32-bit targets, the register is updated as two 32-bit halves
+    /// following the sequence of [`MTIMECMP::set_deadline_split`].
+    #[inline]
+    pub fn set_deadline(self, deadline: u64) {
+        #[cfg(target_pointer_width = "32")]
+        self.set_deadline_split(deadline);
+        #[cfg(not(target_pointer_width = "32"))]
+        self.register.write(deadline);
+    }
+
+    /// Programs the register to trigger an interrupt `ticks` ticks after the current value of `mtime`.
+    /// It returns the programmed deadline.
+    ///
+    /// # Note
+    ///
+    /// The comparison performed by the hardware is unsigned. Thus, if the deadline overflows,
+    /// it saturates to [`u64::MAX`] instead of wrapping around and triggering an early interrupt.
+    #[inline]
+    pub fn set_deadline_from_now(self, mtime: MTIME, ticks: u64) -> u64 {
+        let deadline = mtime.read().saturating_add(ticks);
+        self.set_deadline(deadline);
+        deadline
+    }
+
+    /// Puts the HART to sleep until `mtime` reaches `deadline`.
+    ///
+    /// It programs the register with `deadline` and executes `wfi` in a loop until the deadline is reached.
+    /// Afterwards, the register is set to [`u64::MAX`] to clear the machine timer interrupt.
+    ///
+    /// # Note
+    ///
+    /// This register must belong to the current HART, and machine timer interrupts must be enabled
+    /// in the `mie` register. Otherwise, `wfi` may not wake up the HART when the deadline is reached.
+    /// Global interrupts (`mstatus.MIE`) do not need to be enabled: a pending interrupt wakes up
+    /// the HART even if it is not taken. If they are enabled, the machine timer interrupt handler
+    /// runs before this method returns, and other interrupts may also wake up the HART.
+    #[inline]
+    pub fn sleep_until(self, mtime: MTIME, deadline: u64) {
+        self.set_deadline(deadline);
+        while mtime.read() < deadline {
+            crate::csr::wfi();
+        }
+        self.set_deadline(u64::MAX);
+    }
+
+    /// Returns `true` if the programmed deadline is in the past (i.e., `mtime >= mtimecmp`).
+    #[inline]
+    pub fn is_expired(self, mtime: MTIME) -> bool {
+        mtime.is_after(self)
+    }
+}
+
+#[cfg(feature = "s-mode-timer")]
+impl MTIMECMP {
+    /// Programs a supervisor timer `deadline` (in `MTIME` ticks) on behalf of S-mode
+    /// (e.g., from the handler of the SBI `set_timer` call).
+    ///
+    /// It clears any pending supervisor timer interrupt (`mip.STIP`) before programming the deadline.
+    /// See the [module-level documentation](self) for details.
+    ///
+    /// # Note
+    ///
+    /// This register must belong to the current HART.
+    #[inline]
+    pub fn set_supervisor_deadline(self, deadline: u64) {
+        crate::csr::mip::clear_stimer();
+        self.set_deadline(deadline);
+    }
+
+    /// Forwards an expired deadline to S-mode as a supervisor timer interrupt.
+    /// It must be called from the machine timer interrupt handler.
+    ///
+    /// It sets the register to [`u64::MAX`] to clear the machine timer interrupt,
+    /// and then sets the supervisor timer interrupt pending bit (`mip.STIP`).
+    /// See the [module-level documentation](self) for details.
+    ///
+    /// # Note
+    ///
+    /// This register must belong to the current HART.
+    ///
+    /// # Safety
+    ///
+    /// Pending a supervisor timer interrupt may break mask-based critical sections.
+    #[inline]
+    pub unsafe fn forward_to_supervisor(self) {
+        self.set_deadline(u64::MAX);
+        crate::csr::mip::set_stimer();
+    }
+
+    /// Returns `true` if a supervisor timer interrupt is pending in the current HART (`mip.STIP`).
+    #[inline]
+    pub fn is_supervisor_pending() -> bool {
+        crate::csr::mip::stimer()
+    }
+}
+
 // MTIME register.
 safe_peripheral!(MTIME, u64, RW);
 
+impl MTIME {
+    /// Reads a 32-bit word of the register (0 for the low half, 1 for the high half).
+    #[cfg(any(test, target_pointer_width = "32"))]
+    #[inline]
+    fn read_word(self, word: usize) -> u32 {
+        half_word(self.register, word).read()
+    }
+
+    /// Writes a 32-bit word of the register (0 for the low half, 1 for the high half).
+    #[cfg(any(test, target_pointer_width = "32"))]
+    #[inline]
+    fn write_word(self, word: usize, value: u32) {
+        half_word(self.register, word).write(value);
+    }
+
+    /// Reads the register as two 32-bit halves.
+    ///
+    /// The high half is read before and after the low half. If they differ,
+    /// the low half overflowed in between and the read is retried.
+    #[cfg(any(test, target_pointer_width = "32"))]
+    #[inline]
+    fn read_split(mut read_word: impl FnMut(usize) -> u32) -> u64 {
+        loop {
+            let hi = read_word(1);
+            let lo = read_word(0);
+            if hi == read_word(1) {
+                return ((hi as u64) << 32) | lo as u64;
+            }
+        }
+    }
+
+    /// Writes the register as two 32-bit halves.
+    ///
+    /// The low half is cleared first, so it cannot overflow into the high half
+    /// before the new low half is written.
+    #[cfg(any(test, target_pointer_width = "32"))]
+    #[inline]
+    fn write_split(value: u64, mut write_word: impl FnMut(usize, u32)) {
+        write_word(0, 0);
+        write_word(1, (value >> 32) as u32);
+        write_word(0, value as u32);
+    }
+
+    /// Reads the current value of the register.
+    ///
+    /// # Note
+    ///
+    /// On 32-bit targets, the register is read as two 32-bit halves. The high half is read
+    /// again after the low half to detect (and retry on) an overflow of the low half.
+    /// On 64-bit targets, the register is read with a single 64-bit access.
+    #[inline]
+    pub fn read(self) -> u64 {
+        #[cfg(target_pointer_width = "32")]
+        return Self::read_split(|word| self.read_word(word));
+        #[cfg(not(target_pointer_width = "32"))]
+        return self.register.read();
+    }
+
+    /// Writes a new value to the register.
+    ///
+    /// # Note
+    ///
+    /// On 32-bit targets, the register is written as two 32-bit halves. The low half is
+    /// cleared first to avoid a carry into the high half while the halves are written.
+    /// On 64-bit targets, the register is written with a single 64-bit access.
+    #[inline]
+    pub fn write(self, value: u64) {
+        #[cfg(target_pointer_width = "32")]
+        Self::write_split(value, |word, value| self.write_word(word, value));
+        #[cfg(not(target_pointer_width = "32"))]
+        self.register.write(value);
+    }
+
+    /// Returns `true` if the current time has reached the deadline programmed in `mtimecmp`.
+    #[inline]
+    pub fn is_after(self, mtimecmp: MTIMECMP) -> bool {
+        self.read() >= mtimecmp.read()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::super::test::HartId;
@@ -76,4 +575,360 @@ mod test {
             &raw_mtime as *const u64 as _
         );
     }
+
+    #[test]
+    fn check_harts() {
+        // slice to emulate the mtimecmp registers
+        let raw_mtimecmp = [0u64; HartId::MAX_HART_ID_NUMBER as usize + 1];
+        let raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer =
+            unsafe { MTIMER::new(raw_mtimecmp.as_ptr() as _, &raw_mtime as *const u64 as _) };
+
+        assert_eq!(mtimer.harts::<HartId>().count(), 3);
+        for (hart_id, mtimecmp) in mtimer.harts::<HartId>() {
+            assert_eq!(mtimecmp, mtimer.mtimecmp(hart_id));
+        }
+    }
+
+    #[test]
+    fn check_gapped_harts() {
+        use super::super::test::GappedHartId;
+
+        let raw_mtimecmp = [0u64; GappedHartId::MAX_HART_ID_NUMBER as usize + 1];
+        let raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer =
+            unsafe { MTIMER::new(raw_mtimecmp.as_ptr() as _, &raw_mtime as *const u64 as _) };
+
+        // HART 1 does not exist, so it has no MTIMECMP register
+        let base = raw_mtimecmp.as_ptr() as usize;
+        let harts: std::vec::Vec<_> = mtimer
+            .harts::<GappedHartId>()
+            .map(|(hart_id, mtimecmp)| (hart_id, mtimecmp.get_ptr() as usize - base))
+            .collect();
+        assert_eq!(
+            harts,
+            [
+                (GappedHartId::H0, 0),
+                (GappedHartId::H2, 16),
+                (GappedHartId::H3, 24)
+            ]
+        );
+        assert!(mtimer.try_mtimecmp::<GappedHartId>(1).is_none());
+        assert!(mtimer.try_mtimecmp::<GappedHartId>(2).is_some());
+    }
+
+    #[test]
+    fn check_try_mtimecmp() {
+        let raw_mtimecmp = [0u64; HartId::MAX_HART_ID_NUMBER as usize + 1];
+        let raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer =
+            unsafe { MTIMER::new(raw_mtimecmp.as_ptr() as _, &raw_mtime as *const u64 as _) };
+
+        assert_eq!(
+            mtimer.try_mtimecmp::<HartId>(2),
+            Some(mtimer.mtimecmp(HartId::H2))
+        );
+        assert_eq!(mtimer.try_mtimecmp::<HartId>(3), None);
+        assert_eq!(mtimer.try_mtimecmp::<HartId>(u16::MAX), None);
+    }
+
+    #[test]
+    fn check_sleep_until() {
+        std::thread_local! {
+            static MTIME_ADDR: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+        }
+
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+
+        // every wfi advances mtime by 10 ticks
+        MTIME_ADDR.with(|addr| addr.set(mtime.get_ptr() as usize));
+        crate::csr::set_wfi_hook(Some(|| {
+            let mtime = unsafe { MTIME::new(MTIME_ADDR.with(|addr| addr.get())) };
+            mtime.write(mtime.read() + 10);
+        }));
+
+        mtimecmp.sleep_until(mtime, 95);
+        crate::csr::set_wfi_hook(None);
+        assert_eq!(mtime.read(), 100);
+        assert_eq!(mtimecmp.read(), u64::MAX);
+
+        // deadlines in the past return immediately
+        mtimecmp.sleep_until(mtime, 50);
+        assert_eq!(mtime.read(), 100);
+    }
+
+    #[test]
+    fn check_estimate_frequency() {
+        assert_eq!(
+            super::scale_frequency(32_768, 100_000_000, 100_000_000),
+            32_768
+        );
+        assert_eq!(super::scale_frequency(1_000, 50_000, 50_000_000), 1_000_000);
+        assert_eq!(super::scale_frequency(1, 0, 50_000_000), 0);
+
+        let raw_mtimecmp = 0u64;
+        let raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                &raw_mtimecmp as *const u64 as _,
+                &raw_mtime as *const u64 as _,
+            )
+        };
+        // the emulated MTIME counter does not advance
+        assert_eq!(mtimer.estimate_frequency(100_000_000, 1_000), 0);
+    }
+
+    #[test]
+    fn check_wait_until() {
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = u64::MAX - 2;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                &mut raw_mtimecmp as *mut u64 as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+        // the emulated MTIME counter advances one tick per poll, wrapping around
+        let mut polls = 0;
+        let res = mtimer.wait_until(10, || {
+            mtimer.mtime.write(mtimer.mtime.read().wrapping_add(1));
+            polls += 1;
+            polls == 5
+        });
+        assert_eq!(res, Ok(4));
+        assert_eq!(mtimer.mtime.read(), 2);
+
+        let res = mtimer.wait_until(10, || {
+            mtimer.mtime.write(mtimer.mtime.read().wrapping_add(1));
+            false
+        });
+        assert_eq!(res, Err(TimeoutError(10)));
+    }
+
+    #[test]
+    fn check_split_deadline() {
+        let now = 0x0000_0000_FFFF_FFF0u64;
+        let deadline = 0x0000_0001_0000_0010u64;
+
+        // no intermediate value of the sequence triggers a spurious interrupt
+        let mut words = [0u32; 2];
+        for (word, value) in MTIMECMP::split_sequence(deadline) {
+            words[word] = value;
+            let current = ((words[1] as u64) << 32) | words[0] as u64;
+            assert!(current > now);
+        }
+
+        // 64-bit aligned backing store of two 32-bit halves
+        #[repr(align(8))]
+        struct Halves([u32; 2]);
+        let mut raw_mtimecmp = Halves([0; 2]);
+        // SAFETY: valid memory address
+        let mtimecmp = unsafe { MTIMECMP::new(raw_mtimecmp.0.as_mut_ptr() as _) };
+        mtimecmp.set_deadline_split(deadline);
+        assert_eq!(mtimecmp.read(), deadline);
+
+        mtimecmp.write_lo(0x1234_5678);
+        assert_eq!(mtimecmp.read(), 0x0000_0001_1234_5678);
+        mtimecmp.write_hi(0x9ABC_DEF0);
+        assert_eq!(mtimecmp.read(), 0x9ABC_DEF0_1234_5678);
+    }
+
+    #[test]
+    fn check_mtime_access() {
+        // the low half overflows between the first two reads of the high half
+        let mut reads = [
+            0x0000_0000,
+            0xFFFF_FFFF,
+            0x0000_0001,
+            0x0000_0001,
+            0x0000_0000,
+            0x0000_0001,
+        ]
+        .into_iter();
+        let mut words = std::vec::Vec::new();
+        let value = MTIME::read_split(|word| {
+            words.push(word);
+            reads.next().unwrap()
+        });
+        assert_eq!(value, 0x0000_0001_0000_0000);
+        assert_eq!(words, [1, 0, 1, 1, 0, 1]);
+
+        // the low half is cleared before writing the high half
+        let mut writes = std::vec::Vec::new();
+        MTIME::write_split(0x1234_5678_9ABC_DEF0, |word, value| {
+            writes.push((word, value))
+        });
+        assert_eq!(writes, [(0, 0), (1, 0x1234_5678), (0, 0x9ABC_DEF0)]);
+
+        // 64-bit aligned backing store of two 32-bit halves
+        #[repr(align(8))]
+        struct Halves([u32; 2]);
+        let mut raw_mtime = Halves([0; 2]);
+        // SAFETY: valid memory address
+        let mtime = unsafe { MTIME::new(raw_mtime.0.as_mut_ptr() as _) };
+
+        // 32-bit path
+        MTIME::write_split(0x0000_0002_FFFF_FFFE, |word, value| {
+            mtime.write_word(word, value)
+        });
+        assert_eq!(
+            MTIME::read_split(|word| mtime.read_word(word)),
+            0x0000_0002_FFFF_FFFE
+        );
+        assert_eq!(mtime.get_register().read(), 0x0000_0002_FFFF_FFFE);
+
+        // native path
+        mtime.write(0x0000_0003_0000_0001);
+        assert_eq!(mtime.read(), 0x0000_0003_0000_0001);
+        assert_eq!(
+            MTIME::read_split(|word| mtime.read_word(word)),
+            0x0000_0003_0000_0001
+        );
+    }
+
+    #[test]
+    fn check_periodic_tick() {
+        let mut raw_mtimecmp = [0u64; HartId::MAX_HART_ID_NUMBER as usize + 1];
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                raw_mtimecmp.as_mut_ptr() as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+
+        let tick = mtimer.schedule_periodic(HartId::H1, 100);
+        assert_eq!(tick.period(), 100);
+        assert_eq!(tick.mtimecmp(), mtimer.mtimecmp(HartId::H1));
+        assert_eq!(tick.mtimecmp().read(), 100);
+
+        // spurious call before the deadline
+        assert_eq!(tick.on_tick(), 0);
+        assert_eq!(tick.mtimecmp().read(), 100);
+
+        // handler latency does not accumulate
+        for i in 1..=1000 {
+            mtimer.mtime.write(tick.mtimecmp().read() + i % 50);
+            assert_eq!(tick.on_tick(), 1);
+            assert_eq!(tick.mtimecmp().read(), 100 * (i + 1));
+        }
+
+        // catch up after missing two periods
+        mtimer.mtime.write(tick.mtimecmp().read() + 250);
+        assert_eq!(tick.on_tick(), 3);
+        assert_eq!(tick.mtimecmp().read(), 100 * 1004);
+    }
+
+    #[test]
+    fn check_tick_period_us() {
+        let mut raw_mtimecmp = [0u64; HartId::MAX_HART_ID_NUMBER as usize + 1];
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                raw_mtimecmp.as_mut_ptr() as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+        mtimer.mtime.write(1_000);
+
+        // 1 ms at 32.768 kHz is 32.768 ticks
+        assert_eq!(mtimer.set_tick_period_us(HartId::H2, 1_000, 32_768), Ok(33));
+        assert_eq!(mtimer.mtimecmp(HartId::H2).read(), 1_033);
+        assert_eq!(mtimer.set_tick_period_us(HartId::H1, 16, 32_768), Ok(1));
+        assert_eq!(mtimer.mtimecmp(HartId::H1).read(), 1_001);
+
+        // periods that round to zero ticks are rejected
+        assert_eq!(
+            mtimer.set_tick_period_us(HartId::H0, 15, 32_768),
+            Err(InvalidPeriod(15))
+        );
+        assert_eq!(
+            mtimer.set_tick_period_us(HartId::H0, 0, 10_000_000),
+            Err(InvalidPeriod(0))
+        );
+        assert_eq!(mtimer.mtimecmp0.read(), 0);
+
+        // the period saturates
+        assert_eq!(
+            mtimer.set_tick_period_us(HartId::H0, u64::MAX, u64::MAX),
+            Ok(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn check_deadline() {
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+
+        mtime.write(1_000);
+        assert_eq!(mtimecmp.set_deadline_from_now(mtime, 500), 1_500);
+        assert_eq!(mtimecmp.read(), 1_500);
+        assert!(!mtimecmp.is_expired(mtime));
+        mtime.write(1_499);
+        assert!(!mtimecmp.is_expired(mtime));
+        mtime.write(1_500);
+        assert!(mtimecmp.is_expired(mtime));
+        assert!(mtime.is_after(mtimecmp));
+
+        // near wrap-around, the deadline saturates instead of firing early
+        mtime.write(u64::MAX - 10);
+        assert_eq!(mtimecmp.set_deadline_from_now(mtime, 20), u64::MAX);
+        assert_eq!(mtimecmp.read(), u64::MAX);
+        assert!(!mtimecmp.is_expired(mtime));
+        mtime.write(u64::MAX);
+        assert!(mtimecmp.is_expired(mtime));
+    }
+
+    #[cfg(feature = "s-mode-timer")]
+    #[test]
+    fn check_supervisor_timer() {
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+
+        // S-mode requests a deadline
+        mtimecmp.set_supervisor_deadline(100);
+        assert_eq!(mtimecmp.read(), 100);
+        assert!(!MTIMECMP::is_supervisor_pending());
+
+        // M-mode forwards the expired deadline
+        mtime.write(100);
+        assert!(mtimecmp.is_expired(mtime));
+        unsafe { mtimecmp.forward_to_supervisor() };
+        assert!(!mtimecmp.is_expired(mtime));
+        assert!(MTIMECMP::is_supervisor_pending());
+
+        // S-mode requests the next deadline, which clears the pending condition
+        mtimecmp.set_supervisor_deadline(200);
+        assert_eq!(mtimecmp.read(), 200);
+        assert!(!MTIMECMP::is_supervisor_pending());
+    }
 }
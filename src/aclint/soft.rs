@@ -0,0 +1,164 @@
+//! Software CLINT for targets without a Machine-level Timer Device.
+//!
+//! [`SoftClint`] provides the timer surface of the `MTIMER` peripheral (i.e., a 64-bit
+//! `MTIME` counter and an `MTIMECMP` deadline) on top of a caller-provided [`TickSource`].
+//! For instance, you can use [`CycleCounter`] to run the same delay code on a core without
+//! a real `MTIME` register. As there is no hardware comparator, deadlines are never signaled
+//! with interrupts: you must poll [`SoftClint::is_expired`] instead.
+
+use crate::aclint::mtimer::MTIME;
+use crate::hal::delay::DelayNs;
+
+/// Trait for monotonic 64-bit tick counters that can replace the `MTIME` register.
+pub trait TickSource {
+    /// Returns the current value of the tick counter.
+    fn ticks(&self) -> u64;
+}
+
+impl<F: Fn() -> u64> TickSource for F {
+    #[inline]
+    fn ticks(&self) -> u64 {
+        self()
+    }
+}
+
+impl TickSource for MTIME {
+    #[inline]
+    fn ticks(&self) -> u64 {
+        self.read()
+    }
+}
+
+/// Tick source based on the `cycle` counter of the current HART.
+///
+/// # Note
+///
+/// The frequency of this tick source is the CPU clock frequency.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CycleCounter;
+
+impl TickSource for CycleCounter {
+    #[inline]
+    fn ticks(&self) -> u64 {
+        crate::csr::cycle::read64()
+    }
+}
+
+/// Software CLINT backed by a [`TickSource`] with a frequency of `freq` Hz.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SoftClint<T: TickSource> {
+    source: T,
+    freq: usize,
+    mtimecmp: u64,
+}
+
+impl<T: TickSource> SoftClint<T> {
+    /// Creates a new software CLINT. The deadline is initialized to [`u64::MAX`].
+    #[inline]
+    pub const fn new(source: T, freq: usize) -> Self {
+        Self {
+            source,
+            freq,
+            mtimecmp: u64::MAX,
+        }
+    }
+
+    /// Returns a reference to the tick source.
+    #[inline]
+    pub const fn source(&self) -> &T {
+        &self.source
+    }
+
+    /// Returns the frequency of the tick source.
+    #[inline]
+    pub const fn get_freq(&self) -> usize {
+        self.freq
+    }
+
+    /// Sets the frequency of the tick source.
+    #[inline]
+    pub fn set_freq(&mut self, freq: usize) {
+        self.freq = freq;
+    }
+
+    /// Returns the current value of the emulated `MTIME` register.
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.source.ticks()
+    }
+
+    /// Returns the deadline of the emulated `MTIMECMP` register.
+    #[inline]
+    pub const fn deadline(&self) -> u64 {
+        self.mtimecmp
+    }
+
+    /// Sets the deadline of the emulated `MTIMECMP` register.
+    #[inline]
+    pub fn set_deadline(&mut self, deadline: u64) {
+        self.mtimecmp = deadline;
+    }
+
+    /// Sets the deadline of the emulated `MTIMECMP` register to `ticks` ticks from now.
+    #[inline]
+    pub fn set_deadline_from_now(&mut self, ticks: u64) {
+        self.mtimecmp = self.now().saturating_add(ticks);
+    }
+
+    /// Returns `true` if the programmed deadline is in the past (i.e., `mtime >= mtimecmp`).
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        self.now() >= self.mtimecmp
+    }
+
+    /// Busy-waits until the programmed deadline is reached.
+    #[inline]
+    pub fn wait(&self) {
+        while !self.is_expired() {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<T: TickSource> DelayNs for SoftClint<T> {
+    #[inline]
+    fn delay_ns(&mut self, ns: u32) {
+        let t0 = self.now();
+        let n_ticks = crate::aclint::time::ns_to_ticks(ns as u64, self.freq as u64);
+        while self.now().wrapping_sub(t0) < n_ticks {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn check_soft_clint() {
+        let ticks = Cell::new(0u64);
+        let source = || {
+            ticks.set(ticks.get() + 1);
+            ticks.get()
+        };
+        let mut clint = SoftClint::new(source, 1_000_000);
+        assert_eq!(clint.deadline(), u64::MAX);
+
+        clint.set_deadline_from_now(10);
+        assert_eq!(clint.deadline(), 11);
+        assert!(!clint.is_expired());
+        clint.wait();
+        assert!(ticks.get() >= 11);
+
+        let t0 = ticks.get();
+        clint.delay_us(100);
+        assert!(ticks.get() - t0 >= 100);
+    }
+
+    #[test]
+    fn check_cycle_counter() {
+        let clint = SoftClint::new(CycleCounter, 1);
+        let t0 = clint.now();
+        assert!(clint.now() > t0);
+    }
+}
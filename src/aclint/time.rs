@@ -0,0 +1,790 @@
+//! Time abstractions over the `MTIME` counter.
+//!
+//! [`Instant`] and [`Duration`] are thin wrappers over raw `MTIME` ticks.
+//! The frequency (in Hz) of the `MTIME` counter is part of the type, so conversions
+//! from and to time units are resolved at compile time.
+//!
+//! # Example
+//!
+//! ```
+//! use riscv_peripheral::aclint::time::{Duration, Instant};
+//!
+//! let now = Instant::<32_768>::from_ticks(1_000);
+//! let deadline = now + Duration::from_millis(5);
+//! assert_eq!(deadline.ticks(), 1_000 + 164);
+//! assert!(deadline > now);
+//! ```
+//!
+//! # `fugit` integration
+//!
+//! With the `fugit` feature, [`Instant`] and [`Duration`] can be converted from and to
+//! [`fugit::TimerInstantU64`] and [`fugit::TimerDurationU64`] with the same frequency.
+//! Any other `fugit` duration can be converted into a [`Duration`], rounding up to the next tick.
+//!
+//! # Raw ticks
+//!
+//! [`Ticks`] is a frequency-agnostic newtype over raw `MTIME` values, for code that only
+//! knows the frequency at runtime. [`MTIME::read_ticks`] and [`MTIMECMP::write_ticks`] read
+//! and program the registers with [`Ticks`] instead of plain `u64` values, so tick counts
+//! cannot be mixed up with time units by mistake.
+//!
+//! # Conversion helpers
+//!
+//! For frequencies only known at runtime, [`secs_to_ticks`], [`ms_to_ticks`], [`us_to_ticks`],
+//! and [`ns_to_ticks`] convert time units to ticks (rounding up), and [`ticks_to_secs`],
+//! [`ticks_to_ms`], [`ticks_to_us`], and [`ticks_to_ns`] convert ticks to time units (rounding down).
+
+use super::mtimer::{MTIME, MTIMECMP};
+
+/// Divides `a * b` by `d`, rounding up. It saturates to [`u64::MAX`] on overflow.
+const fn mul_div_ceil(a: u64, b: u64, d: u64) -> u64 {
+    let (n, d) = (a as u128 * b as u128, d as u128);
+    saturate(n / d + (n % d != 0) as u128)
+}
+
+/// Divides `a * b` by `d`, rounding down. It saturates to [`u64::MAX`] on overflow.
+const fn mul_div_floor(a: u64, b: u64, d: u64) -> u64 {
+    saturate(a as u128 * b as u128 / d as u128)
+}
+
+/// Converts a 128-bit intermediate result to `u64`, saturating to [`u64::MAX`].
+const fn saturate(value: u128) -> u64 {
+    if value > u64::MAX as u128 {
+        u64::MAX
+    } else {
+        value as u64
+    }
+}
+
+/// Converts seconds to ticks of a counter running at `freq` Hz.
+///
+/// The products are computed with 128-bit intermediates, so they never overflow.
+/// The result rounds up to the next tick, so delays never take less than requested,
+/// and saturates to [`u64::MAX`] if it does not fit in 64 bits.
+#[inline]
+pub const fn secs_to_ticks(secs: u64, freq: u64) -> u64 {
+    mul_div_ceil(secs, freq, 1)
+}
+
+/// Converts milliseconds to ticks of a counter running at `freq` Hz.
+/// It rounds up and saturates as [`secs_to_ticks`].
+#[inline]
+pub const fn ms_to_ticks(ms: u64, freq: u64) -> u64 {
+    mul_div_ceil(ms, freq, 1_000)
+}
+
+/// Converts microseconds to ticks of a counter running at `freq` Hz.
+/// It rounds up and saturates as [`secs_to_ticks`].
+#[inline]
+pub const fn us_to_ticks(us: u64, freq: u64) -> u64 {
+    mul_div_ceil(us, freq, 1_000_000)
+}
+
+/// Converts nanoseconds to ticks of a counter running at `freq` Hz.
+/// It rounds up and saturates as [`secs_to_ticks`].
+#[inline]
+pub const fn ns_to_ticks(ns: u64, freq: u64) -> u64 {
+    mul_div_ceil(ns, freq, 1_000_000_000)
+}
+
+/// Converts `periods` periods of `nom / denom` seconds (e.g., the ticks of a `fugit` duration)
+/// to ticks of a counter running at `freq` Hz. It rounds up and saturates as [`secs_to_ticks`].
+///
+/// # Panics
+///
+/// It panics if `denom` is 0.
+#[inline]
+pub const fn ratio_to_ticks(periods: u64, nom: u32, denom: u32, freq: u64) -> u64 {
+    match (periods as u128 * nom as u128).checked_mul(freq as u128) {
+        Some(n) => {
+            let d = denom as u128;
+            saturate(n / d + (n % d != 0) as u128)
+        }
+        // the quotient exceeds 2^96, which does not fit in 64 bits either
+        None => u64::MAX,
+    }
+}
+
+/// Converts ticks of a counter running at `freq` Hz to whole seconds.
+///
+/// The products are computed with 128-bit intermediates, so they never overflow.
+/// The result rounds down, so elapsed times never exceed the actual ones,
+/// and saturates to [`u64::MAX`] if it does not fit in 64 bits.
+///
+/// # Panics
+///
+/// It panics if `freq` is 0.
+#[inline]
+pub const fn ticks_to_secs(ticks: u64, freq: u64) -> u64 {
+    mul_div_floor(ticks, 1, freq)
+}
+
+/// Converts ticks of a counter running at `freq` Hz to whole milliseconds.
+/// It rounds down and saturates as [`ticks_to_secs`].
+///
+/// # Panics
+///
+/// It panics if `freq` is 0.
+#[inline]
+pub const fn ticks_to_ms(ticks: u64, freq: u64) -> u64 {
+    mul_div_floor(ticks, 1_000, freq)
+}
+
+/// Converts ticks of a counter running at `freq` Hz to whole microseconds.
+/// It rounds down and saturates as [`ticks_to_secs`].
+///
+/// # Panics
+///
+/// It panics if `freq` is 0.
+#[inline]
+pub const fn ticks_to_us(ticks: u64, freq: u64) -> u64 {
+    mul_div_floor(ticks, 1_000_000, freq)
+}
+
+/// Converts ticks of a counter running at `freq` Hz to whole nanoseconds.
+/// It rounds down and saturates as [`ticks_to_secs`].
+///
+/// # Panics
+///
+/// It panics if `freq` is 0.
+#[inline]
+pub const fn ticks_to_ns(ticks: u64, freq: u64) -> u64 {
+    mul_div_floor(ticks, 1_000_000_000, freq)
+}
+
+/// Raw number of `MTIME` ticks.
+///
+/// It can represent both a value of the `MTIME` counter and a span of ticks.
+///
+/// # Wraparound
+///
+/// The `MTIME` counter wraps around to 0 after [`u64::MAX`], so the `+` and `-` operators
+/// (and their assigning variants) wrap around as well, and never panic. Use
+/// [`Ticks::saturating_add`] and [`Ticks::saturating_sub`] to clamp the result instead.
+///
+/// Comparisons are plain numeric comparisons of the raw values. They do not account for
+/// wraparound, so a deadline computed with `+` past [`u64::MAX`] compares lower than the
+/// instant it was computed from. Use [`Ticks::is_after`] to compare values of the counter
+/// across the wraparound boundary.
+///
+/// In practice, a 64-bit `MTIME` counter running at 1 GHz wraps around every 584 years, and
+/// [`Ticks::is_after`] is correct for values up to 292 years apart. However, some platforms
+/// only implement the lower 32 bits of the counter and read the upper half as zero. Such a
+/// counter wraps around after `2^32` ticks (e.g., every 36 hours at 32.768 kHz), and neither
+/// the comparisons nor [`Ticks::is_after`] account for it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ticks(pub u64);
+
+impl Ticks {
+    /// Zero ticks.
+    pub const ZERO: Self = Self(0);
+
+    /// Returns the raw number of ticks.
+    #[inline]
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Adds two tick counts, wrapping around on overflow.
+    #[inline]
+    pub const fn wrapping_add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+
+    /// Subtracts two tick counts, wrapping around on underflow.
+    ///
+    /// If `rhs` is an earlier value of the `MTIME` counter, the result is the number of
+    /// ticks elapsed since `rhs`, even if the counter wrapped around in between.
+    #[inline]
+    pub const fn wrapping_sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+
+    /// Returns `true` if the counter value `self` comes after `other`, accounting for wraparound.
+    ///
+    /// It computes the wrapping difference `self - other` and checks that it is positive when
+    /// interpreted as a signed number. Thus, the result is correct as long as both values are
+    /// less than `2^63` ticks apart, even if the counter wrapped around in between. For instance,
+    /// a deadline computed just before the counter wraps around is not considered elapsed just
+    /// after the wraparound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use riscv_peripheral::aclint::time::Ticks;
+    ///
+    /// let now = Ticks(u64::MAX - 10);
+    /// let deadline = now + Ticks(20); // wraps around to 9
+    /// assert!(deadline < now); // plain comparisons do not account for wraparound
+    /// assert!(deadline.is_after(now));
+    /// assert!(!now.is_after(deadline));
+    /// ```
+    #[inline]
+    pub const fn is_after(self, other: Self) -> bool {
+        (self.0.wrapping_sub(other.0) as i64) > 0
+    }
+
+    /// Adds two tick counts, saturating to [`u64::MAX`] on overflow.
+    #[inline]
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Subtracts two tick counts, saturating to 0 on underflow.
+    #[inline]
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl From<u64> for Ticks {
+    #[inline]
+    fn from(ticks: u64) -> Self {
+        Self(ticks)
+    }
+}
+
+impl From<Ticks> for u64 {
+    #[inline]
+    fn from(ticks: Ticks) -> Self {
+        ticks.0
+    }
+}
+
+impl core::ops::Add for Ticks {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.wrapping_add(rhs)
+    }
+}
+
+impl core::ops::AddAssign for Ticks {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl core::ops::Sub for Ticks {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.wrapping_sub(rhs)
+    }
+}
+
+impl core::ops::SubAssign for Ticks {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// Span of time measured in `MTIME` ticks of a counter running at `FREQ` Hz.
+///
+/// # Note
+///
+/// Conversions from time units round up to the next tick, so waiting for a [`Duration`]
+/// never takes less than requested. Conversions to time units round down.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration<const FREQ: u32> {
+    ticks: u64,
+}
+
+impl<const FREQ: u32> Duration<FREQ> {
+    /// Zero duration.
+    pub const ZERO: Self = Self::from_ticks(0);
+
+    /// Creates a new duration from a number of `MTIME` ticks.
+    #[inline]
+    pub const fn from_ticks(ticks: u64) -> Self {
+        Self { ticks }
+    }
+
+    /// Creates a new duration from a number of seconds.
+    #[inline]
+    pub const fn from_secs(secs: u64) -> Self {
+        Self::from_ticks(secs_to_ticks(secs, FREQ as u64))
+    }
+
+    /// Creates a new duration from a number of milliseconds.
+    #[inline]
+    pub const fn from_millis(millis: u64) -> Self {
+        Self::from_ticks(ms_to_ticks(millis, FREQ as u64))
+    }
+
+    /// Creates a new duration from a number of microseconds.
+    #[inline]
+    pub const fn from_micros(micros: u64) -> Self {
+        Self::from_ticks(us_to_ticks(micros, FREQ as u64))
+    }
+
+    /// Creates a new duration from a number of nanoseconds.
+    #[inline]
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self::from_ticks(ns_to_ticks(nanos, FREQ as u64))
+    }
+
+    /// Returns the number of `MTIME` ticks of the duration.
+    #[inline]
+    pub const fn ticks(self) -> u64 {
+        self.ticks
+    }
+
+    /// Returns the number of whole seconds of the duration.
+    #[inline]
+    pub const fn to_secs(self) -> u64 {
+        ticks_to_secs(self.ticks, FREQ as u64)
+    }
+
+    /// Returns the number of whole milliseconds of the duration.
+    #[inline]
+    pub const fn to_millis(self) -> u64 {
+        ticks_to_ms(self.ticks, FREQ as u64)
+    }
+
+    /// Returns the number of whole microseconds of the duration.
+    #[inline]
+    pub const fn to_micros(self) -> u64 {
+        ticks_to_us(self.ticks, FREQ as u64)
+    }
+
+    /// Returns the number of whole nanoseconds of the duration.
+    #[inline]
+    pub const fn to_nanos(self) -> u64 {
+        ticks_to_ns(self.ticks, FREQ as u64)
+    }
+
+    /// Adds two durations, returning `None` on overflow.
+    #[inline]
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.ticks.checked_add(rhs.ticks) {
+            Some(ticks) => Some(Self::from_ticks(ticks)),
+            None => None,
+        }
+    }
+
+    /// Subtracts two durations, returning `None` on underflow.
+    #[inline]
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.ticks.checked_sub(rhs.ticks) {
+            Some(ticks) => Some(Self::from_ticks(ticks)),
+            None => None,
+        }
+    }
+}
+
+impl<const FREQ: u32> core::ops::Add for Duration<FREQ> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::from_ticks(self.ticks + rhs.ticks)
+    }
+}
+
+impl<const FREQ: u32> core::ops::AddAssign for Duration<FREQ> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const FREQ: u32> core::ops::Sub for Duration<FREQ> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_ticks(self.ticks - rhs.ticks)
+    }
+}
+
+impl<const FREQ: u32> core::ops::SubAssign for Duration<FREQ> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl<const FREQ: u32> From<Duration<FREQ>> for fugit::TimerDurationU64<FREQ> {
+    #[inline]
+    fn from(duration: Duration<FREQ>) -> Self {
+        Self::from_ticks(duration.ticks())
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl<const NOM: u32, const DENOM: u32, const FREQ: u32> From<fugit::Duration<u64, NOM, DENOM>>
+    for Duration<FREQ>
+{
+    #[inline]
+    fn from(duration: fugit::Duration<u64, NOM, DENOM>) -> Self {
+        Self::from_ticks(ratio_to_ticks(duration.ticks(), NOM, DENOM, FREQ as u64))
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl<const NOM: u32, const DENOM: u32, const FREQ: u32> From<fugit::Duration<u32, NOM, DENOM>>
+    for Duration<FREQ>
+{
+    #[inline]
+    fn from(duration: fugit::Duration<u32, NOM, DENOM>) -> Self {
+        fugit::Duration::<u64, NOM, DENOM>::from(duration).into()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const FREQ: u32> defmt::Format for Duration<FREQ> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u64} ticks @ {=u32} Hz", self.ticks, FREQ)
+    }
+}
+
+/// Point in time measured as the value of an `MTIME` counter running at `FREQ` Hz.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Instant<const FREQ: u32> {
+    ticks: u64,
+}
+
+impl<const FREQ: u32> Instant<FREQ> {
+    /// Creates a new instant from a raw `MTIME` value.
+    #[inline]
+    pub const fn from_ticks(ticks: u64) -> Self {
+        Self { ticks }
+    }
+
+    /// Returns the raw `MTIME` value of the instant.
+    #[inline]
+    pub const fn ticks(self) -> u64 {
+        self.ticks
+    }
+
+    /// Returns the duration elapsed from `earlier` to `self`, or `None` if `earlier` is later than `self`.
+    #[inline]
+    pub const fn checked_duration_since(self, earlier: Self) -> Option<Duration<FREQ>> {
+        match self.ticks.checked_sub(earlier.ticks) {
+            Some(ticks) => Some(Duration::from_ticks(ticks)),
+            None => None,
+        }
+    }
+
+    /// Returns the duration elapsed from `earlier` to `self`, or zero if `earlier` is later than `self`.
+    #[inline]
+    pub const fn saturating_duration_since(self, earlier: Self) -> Duration<FREQ> {
+        Duration::from_ticks(self.ticks.saturating_sub(earlier.ticks))
+    }
+
+    /// Adds a duration to the instant, returning `None` on overflow.
+    #[inline]
+    pub const fn checked_add(self, duration: Duration<FREQ>) -> Option<Self> {
+        match self.ticks.checked_add(duration.ticks()) {
+            Some(ticks) => Some(Self::from_ticks(ticks)),
+            None => None,
+        }
+    }
+
+    /// Subtracts a duration from the instant, returning `None` on underflow.
+    #[inline]
+    pub const fn checked_sub(self, duration: Duration<FREQ>) -> Option<Self> {
+        match self.ticks.checked_sub(duration.ticks()) {
+            Some(ticks) => Some(Self::from_ticks(ticks)),
+            None => None,
+        }
+    }
+}
+
+impl<const FREQ: u32> core::ops::Add<Duration<FREQ>> for Instant<FREQ> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Duration<FREQ>) -> Self {
+        Self::from_ticks(self.ticks + rhs.ticks())
+    }
+}
+
+impl<const FREQ: u32> core::ops::AddAssign<Duration<FREQ>> for Instant<FREQ> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Duration<FREQ>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const FREQ: u32> core::ops::Sub<Duration<FREQ>> for Instant<FREQ> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Duration<FREQ>) -> Self {
+        Self::from_ticks(self.ticks - rhs.ticks())
+    }
+}
+
+impl<const FREQ: u32> core::ops::SubAssign<Duration<FREQ>> for Instant<FREQ> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Duration<FREQ>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const FREQ: u32> core::ops::Sub for Instant<FREQ> {
+    type Output = Duration<FREQ>;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Duration<FREQ> {
+        Duration::from_ticks(self.ticks - rhs.ticks)
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl<const FREQ: u32> From<Instant<FREQ>> for fugit::TimerInstantU64<FREQ> {
+    #[inline]
+    fn from(instant: Instant<FREQ>) -> Self {
+        Self::from_ticks(instant.ticks())
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl<const FREQ: u32> From<fugit::TimerInstantU64<FREQ>> for Instant<FREQ> {
+    #[inline]
+    fn from(instant: fugit::TimerInstantU64<FREQ>) -> Self {
+        Self::from_ticks(instant.ticks())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const FREQ: u32> defmt::Format for Instant<FREQ> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u64} ticks @ {=u32} Hz", self.ticks, FREQ)
+    }
+}
+
+impl MTIME {
+    /// Returns the current value of the `MTIME` register as an [`Instant`].
+    /// `FREQ` must be the frequency (in Hz) of the `MTIME` counter.
+    #[inline]
+    pub fn now<const FREQ: u32>(self) -> Instant<FREQ> {
+        Instant::from_ticks(self.read())
+    }
+
+    /// Reads the current value of the register as [`Ticks`].
+    #[inline]
+    pub fn read_ticks(self) -> Ticks {
+        Ticks(self.read())
+    }
+}
+
+impl MTIMECMP {
+    /// Returns the deadline programmed in the register as an [`Instant`].
+    /// `FREQ` must be the frequency (in Hz) of the `MTIME` counter.
+    #[inline]
+    pub fn instant<const FREQ: u32>(self) -> Instant<FREQ> {
+        Instant::from_ticks(self.read())
+    }
+
+    /// Programs the register with an absolute `deadline`.
+    #[inline]
+    pub fn set_instant<const FREQ: u32>(self, deadline: Instant<FREQ>) {
+        self.set_deadline(deadline.ticks());
+    }
+
+    /// Returns the deadline programmed in the register as [`Ticks`].
+    #[inline]
+    pub fn read_ticks(self) -> Ticks {
+        Ticks(self.read())
+    }
+
+    /// Programs the register with an absolute `deadline`.
+    ///
+    /// As [`MTIMECMP::set_deadline`], it never triggers a spurious interrupt on 32-bit targets.
+    #[inline]
+    pub fn write_ticks(self, deadline: Ticks) {
+        self.set_deadline(deadline.as_u64());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_duration() {
+        type D = Duration<32_768>;
+
+        assert_eq!(D::from_secs(2).ticks(), 65_536);
+        assert_eq!(D::from_millis(1_000), D::from_secs(1));
+        // conversions from time units round up
+        assert_eq!(D::from_millis(5).ticks(), 164);
+        assert_eq!(D::from_micros(1).ticks(), 1);
+        assert_eq!(D::from_nanos(0), D::ZERO);
+        // conversions to time units round down
+        assert_eq!(D::from_ticks(164).to_millis(), 5);
+        assert_eq!(D::from_ticks(32_767).to_secs(), 0);
+        assert_eq!(D::from_ticks(1).to_nanos(), 30_517);
+        // huge values saturate instead of overflowing
+        assert_eq!(D::from_secs(u64::MAX).ticks(), u64::MAX);
+
+        assert_eq!(D::from_ticks(3) + D::from_ticks(2), D::from_ticks(5));
+        assert_eq!(D::from_ticks(3) - D::from_ticks(2), D::from_ticks(1));
+        assert_eq!(D::from_ticks(u64::MAX).checked_add(D::from_ticks(1)), None);
+        assert_eq!(D::from_ticks(1).checked_sub(D::from_ticks(2)), None);
+        assert!(D::from_millis(1) < D::from_millis(2));
+    }
+
+    #[test]
+    fn check_conversions() {
+        const GHZ: u64 = 1_000_000_000;
+
+        // 1 Hz: sub-second delays round up to one tick, elapsed times round down
+        assert_eq!(secs_to_ticks(3, 1), 3);
+        assert_eq!(ms_to_ticks(1, 1), 1);
+        assert_eq!(us_to_ticks(1, 1), 1);
+        assert_eq!(ns_to_ticks(1, 1), 1);
+        assert_eq!(ns_to_ticks(0, 1), 0);
+        assert_eq!(ticks_to_secs(3, 1), 3);
+        assert_eq!(ticks_to_ms(3, 1), 3_000);
+        assert_eq!(ticks_to_ns(u64::MAX / GHZ, 1), u64::MAX / GHZ * GHZ);
+        // the result does not fit in 64 bits
+        assert_eq!(ticks_to_ns(u64::MAX, 1), u64::MAX);
+
+        // 1 GHz: exact conversions
+        assert_eq!(secs_to_ticks(1, GHZ), GHZ);
+        assert_eq!(ms_to_ticks(1, GHZ), 1_000_000);
+        assert_eq!(us_to_ticks(1, GHZ), 1_000);
+        assert_eq!(ns_to_ticks(1, GHZ), 1);
+        assert_eq!(ticks_to_ns(1, GHZ), 1);
+        assert_eq!(ticks_to_us(1_999, GHZ), 1);
+
+        // long durations do not overflow the intermediate products
+        assert_eq!(ns_to_ticks(u64::MAX, GHZ), u64::MAX);
+        assert_eq!(us_to_ticks(u64::MAX / 1_000, GHZ), u64::MAX / 1_000 * 1_000);
+        assert_eq!(ticks_to_secs(u64::MAX, GHZ), u64::MAX / GHZ);
+        assert_eq!(ticks_to_ms(u64::MAX, GHZ), u64::MAX / 1_000_000);
+        // 100 years at 1 GHz
+        let secs = 100 * 365 * 24 * 3_600;
+        assert_eq!(secs_to_ticks(secs, GHZ), secs * GHZ);
+        assert_eq!(ticks_to_secs(secs * GHZ, GHZ), secs);
+        // the result does not fit in 64 bits
+        assert_eq!(secs_to_ticks(u64::MAX, GHZ), u64::MAX);
+
+        // fractional periods (e.g., 1 us at 32.768 kHz) round up to one tick
+        assert_eq!(ratio_to_ticks(1, 1, 1_000_000, 32_768), 1);
+        assert_eq!(ratio_to_ticks(0, 1, 1_000_000, 32_768), 0);
+        assert_eq!(ratio_to_ticks(1_000, 1, 1_000, 32_768), 32_768);
+        assert_eq!(ratio_to_ticks(3, 1, 1_000, 1_000), 3);
+        // the intermediate product does not fit in 128 bits
+        assert_eq!(ratio_to_ticks(u64::MAX, u32::MAX, 1, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn check_instant() {
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+
+        mtime.write(1_000);
+        let now = mtime.now::<1_000_000>();
+        assert_eq!(now.ticks(), 1_000);
+
+        let deadline = now + Duration::from_millis(5);
+        assert_eq!(deadline.ticks(), 6_000);
+        assert!(deadline > now);
+        assert_eq!(deadline - now, Duration::from_micros(5_000));
+        assert_eq!(now.checked_duration_since(deadline), None);
+        assert_eq!(now.saturating_duration_since(deadline), Duration::ZERO);
+
+        mtimecmp.set_instant(deadline);
+        assert_eq!(mtimecmp.read(), 6_000);
+        assert_eq!(mtimecmp.instant(), deadline);
+    }
+
+    #[test]
+    fn check_ticks() {
+        let max = Ticks(u64::MAX);
+
+        assert_eq!(Ticks(3) + Ticks(2), Ticks(5));
+        assert_eq!(Ticks(3) - Ticks(2), Ticks(1));
+        // the operators wrap around as the MTIME counter
+        assert_eq!(max + Ticks(2), Ticks(1));
+        assert_eq!(Ticks(1) - max, Ticks(2));
+        assert_eq!(max.wrapping_add(Ticks(1)), Ticks::ZERO);
+        assert_eq!(Ticks::ZERO.wrapping_sub(Ticks(1)), max);
+        assert_eq!(max.saturating_add(Ticks(1)), max);
+        assert_eq!(Ticks(1).saturating_sub(Ticks(2)), Ticks::ZERO);
+
+        let mut ticks = Ticks(10);
+        ticks += Ticks(5);
+        ticks -= Ticks(3);
+        assert_eq!(ticks.as_u64(), 12);
+        assert_eq!(u64::from(ticks), 12);
+        assert_eq!(Ticks::from(12), ticks);
+        assert!(Ticks(1) < Ticks(2));
+        // comparisons are not wrap-aware
+        assert!(max + Ticks(1) < max);
+
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+
+        // deadline set just before the counter wraps around
+        mtime.write(u64::MAX - 1);
+        let deadline = mtime.read_ticks() + Ticks(4);
+        assert_eq!(deadline, Ticks(2));
+        for now in [u64::MAX - 1, u64::MAX, 0, 1] {
+            assert!(deadline.is_after(Ticks(now)));
+            assert!(!Ticks(now).is_after(deadline));
+        }
+        assert!(!deadline.is_after(deadline));
+        assert!(Ticks(3).is_after(deadline));
+
+        // correct for values less than 2^63 ticks apart
+        let half = Ticks(1 << 63);
+        assert!((Ticks::ZERO + half - Ticks(1)).is_after(Ticks::ZERO));
+        assert!(!(Ticks::ZERO + half).is_after(Ticks::ZERO));
+
+        mtime.write(1_000);
+        let deadline = mtime.read_ticks() + Ticks(500);
+        mtimecmp.write_ticks(deadline);
+        assert_eq!(mtimecmp.read(), 1_500);
+        assert_eq!(mtimecmp.read_ticks(), deadline);
+    }
+
+    #[cfg(feature = "fugit")]
+    #[test]
+    fn check_fugit() {
+        type D = Duration<32_768>;
+
+        let duration = fugit::TimerDurationU64::<32_768>::from_ticks(100);
+        assert_eq!(D::from(duration).ticks(), 100);
+        assert_eq!(fugit::TimerDurationU64::from(D::from_ticks(100)), duration);
+
+        // conversions from other frequencies round up to the next tick
+        assert_eq!(
+            D::from(fugit::MillisDurationU32::from_ticks(5)).ticks(),
+            164
+        );
+        assert_eq!(D::from(fugit::MicrosDurationU64::from_ticks(1)).ticks(), 1);
+
+        let instant = fugit::TimerInstantU64::<32_768>::from_ticks(1_000);
+        assert_eq!(Instant::from(instant).ticks(), 1_000);
+        assert_eq!(
+            fugit::TimerInstantU64::from(Instant::from(instant)),
+            instant
+        );
+    }
+}
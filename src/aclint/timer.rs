@@ -0,0 +1,446 @@
+//! Asynchronous timer for the Machine-level Timer Device.
+//!
+//! # Note
+//!
+//! Pending [`TimerFuture`]s register their wakers in a fixed-size table of [`QUEUE_LENGTH`] entries.
+//! Each `MTIMECMP` register is programmed with the earliest deadline among the futures that target it,
+//! or with [`u64::MAX`] when no future targets it. [`TimerFuture::new`] targets the `MTIMECMP` register of HART 0. On multi-HART systems,
+//! use [`TimerFuture::new_on`] (or [`crate::aclint::CLINT::delay_on`]) to target the register of the
+//! HART that handles the machine timer interrupt.
+//! To make pending futures progress, you **must** enable machine timer interrupts and
+//! call [`TimerFuture::on_interrupt`] from the machine timer interrupt handler.
+//!
+//! # Multi-HART safety
+//!
+//! The table is protected by disabling machine interrupts in the current HART.
+//! If atomic compare-and-swap operations are available (natively or through the
+//! `portable-atomic` feature), the table is also protected by a spin lock, so timer futures
+//! can be polled from several HARTs. Otherwise, timer futures must only be used from a single HART.
+//!
+//! With the `critical-section` backend of `portable-atomic`, the spin lock is only as strong as
+//! the `critical-section` implementation of the target. If timer futures are polled from
+//! several HARTs, make sure that such implementation is multi-HART safe.
+
+use super::mtimer::{MTIME, MTIMECMP, MTIMER};
+use core::{
+    cell::UnsafeCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// Maximum number of timer futures that can wait for a machine timer interrupt at the same time.
+///
+/// # Note
+///
+/// If the table is full, new timer futures fall back to busy polling the `MTIME` register.
+pub const QUEUE_LENGTH: usize = 8;
+
+/// Waker registered in a slot of the table.
+struct Entry {
+    /// Registration ID, so a future can tell whether the slot still holds its waker.
+    id: usize,
+    mtimecmp: MTIMECMP,
+    mtime: MTIME,
+    deadline: u64,
+    waker: Waker,
+}
+
+/// Slot index and registration ID of a future registered in the table.
+type Handle = (usize, usize);
+
+/// Table of wakers waiting for a machine timer interrupt.
+struct Queue {
+    next_id: usize,
+    slots: [Option<Entry>; QUEUE_LENGTH],
+}
+
+impl Queue {
+    const EMPTY: Option<Entry> = None;
+
+    const fn new() -> Self {
+        Self {
+            next_id: 0,
+            slots: [Self::EMPTY; QUEUE_LENGTH],
+        }
+    }
+
+    /// Returns the entry registered with `handle`, if it still occupies its slot.
+    fn entry_mut(&mut self, handle: Handle) -> Option<&mut Entry> {
+        let (slot, id) = handle;
+        self.slots[slot].as_mut().filter(|entry| entry.id == id)
+    }
+
+    /// Registers a waker in the table and returns the handle of the slot it occupies.
+    /// If `handle` still owns its slot, the slot is updated. Otherwise, a free slot is taken.
+    /// If the table is full, it returns [`None`].
+    fn register(
+        &mut self,
+        timer: (MTIMECMP, MTIME),
+        handle: Option<Handle>,
+        deadline: u64,
+        waker: &Waker,
+    ) -> Option<Handle> {
+        let handle = match handle.and_then(|h| self.entry_mut(h).map(|entry| (h, entry))) {
+            Some((handle, entry)) => {
+                entry.deadline = deadline;
+                if !entry.waker.will_wake(waker) {
+                    entry.waker = waker.clone();
+                }
+                handle
+            }
+            None => {
+                let slot = self.slots.iter().position(Option::is_none)?;
+                let id = self.next_id;
+                self.next_id = id.wrapping_add(1);
+                self.slots[slot] = Some(Entry {
+                    id,
+                    mtimecmp: timer.0,
+                    mtime: timer.1,
+                    deadline,
+                    waker: waker.clone(),
+                });
+                (slot, id)
+            }
+        };
+        self.reprogram(timer.0);
+        Some(handle)
+    }
+
+    /// Removes the waker registered with `handle`, if it still occupies its slot.
+    fn remove(&mut self, handle: Handle) {
+        if let Some(entry) = self.entry_mut(handle) {
+            let mtimecmp = entry.mtimecmp;
+            self.slots[handle.0] = None;
+            self.reprogram(mtimecmp);
+        }
+    }
+
+    /// Wakes all the wakers whose deadline has been reached and frees their slots.
+    /// Then, it reprograms the `MTIMECMP` registers of all the futures that were in the table.
+    fn wake_expired(&mut self) {
+        let mut timers = [None; QUEUE_LENGTH];
+        for (entry, timer) in self.slots.iter_mut().zip(timers.iter_mut()) {
+            let expired = match entry {
+                Some(e) => {
+                    *timer = Some(e.mtimecmp);
+                    e.deadline <= e.mtime.read()
+                }
+                None => false,
+            };
+            if expired {
+                if let Some(e) = entry.take() {
+                    e.waker.wake();
+                }
+            }
+        }
+        for (i, mtimecmp) in timers.iter().enumerate() {
+            match mtimecmp {
+                Some(mtimecmp) if !timers[..i].contains(&Some(*mtimecmp)) => {
+                    self.reprogram(*mtimecmp)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Programs `mtimecmp` with the earliest deadline among the futures that target it.
+    /// If there is none, it sets the register to [`u64::MAX`] to clear the interrupt.
+    fn reprogram(&self, mtimecmp: MTIMECMP) {
+        let next = self.slots.iter().flatten();
+        let next = next
+            .filter(|e| e.mtimecmp == mtimecmp)
+            .map(|e| e.deadline)
+            .min();
+        mtimecmp.set_deadline(next.unwrap_or(u64::MAX));
+    }
+}
+
+struct SyncQueue(UnsafeCell<Queue>);
+
+// SAFETY: the queue is only accessed within critical sections
+unsafe impl Sync for SyncQueue {}
+
+static QUEUE: SyncQueue = SyncQueue(UnsafeCell::new(Queue::new()));
+
+/// Spin lock that protects the queue from concurrent accesses of other HARTs.
+#[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+static LOCK: crate::sync::AtomicBool = crate::sync::AtomicBool::new(false);
+
+/// Runs `f` with machine interrupts disabled.
+/// If atomic compare-and-swap operations are available, it also holds [`LOCK`].
+#[inline]
+fn critical_section<R>(f: impl FnOnce() -> R) -> R {
+    let mie = crate::csr::mstatus::mie();
+    crate::csr::mstatus::clear_mie();
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+    while LOCK
+        .compare_exchange_weak(
+            false,
+            true,
+            crate::sync::Ordering::Acquire,
+            crate::sync::Ordering::Relaxed,
+        )
+        .is_err()
+    {
+        core::hint::spin_loop();
+    }
+    let res = f();
+    #[cfg(any(feature = "portable-atomic", target_has_atomic = "8"))]
+    LOCK.store(false, crate::sync::Ordering::Release);
+    if mie {
+        // SAFETY: interrupts were enabled before entering the critical section
+        unsafe { crate::csr::mstatus::set_mie() };
+    }
+    res
+}
+
+#[inline]
+fn with_queue<R>(f: impl FnOnce(&mut Queue) -> R) -> R {
+    // SAFETY: the queue is only accessed within critical sections
+    critical_section(|| f(unsafe { &mut *QUEUE.0.get() }))
+}
+
+/// Future that resolves when the `MTIME` register reaches a given deadline.
+#[derive(Debug)]
+pub struct TimerFuture {
+    mtimecmp: MTIMECMP,
+    mtime: MTIME,
+    deadline: u64,
+    handle: Option<Handle>,
+}
+
+impl TimerFuture {
+    /// Creates a new timer future that resolves when the `MTIME` register reaches `deadline`.
+    /// It programs the `MTIMECMP` register of HART 0 (i.e., [`MTIMER::mtimecmp0`]).
+    #[inline]
+    pub const fn new(mtimer: MTIMER, deadline: u64) -> Self {
+        Self::new_on(mtimer.mtimecmp0, mtimer.mtime, deadline)
+    }
+
+    /// Creates a new timer future that resolves when `mtime` reaches `deadline`.
+    /// It programs `mtimecmp`, which must belong to the HART that handles the machine timer interrupt.
+    #[inline]
+    pub const fn new_on(mtimecmp: MTIMECMP, mtime: MTIME, deadline: u64) -> Self {
+        Self {
+            mtimecmp,
+            mtime,
+            deadline,
+            handle: None,
+        }
+    }
+
+    /// Returns the deadline (in `MTIME` ticks) of the timer future.
+    #[inline]
+    pub const fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    /// Wakes all the pending timer futures whose deadline has been reached
+    /// and programs the `MTIMECMP` register with the next deadline.
+    ///
+    /// # Note
+    ///
+    /// This function **must** be called from the machine timer interrupt handler.
+    #[inline]
+    pub fn on_interrupt() {
+        with_queue(Queue::wake_expired);
+    }
+}
+
+impl Future for TimerFuture {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.mtime.read() >= self.deadline {
+            if let Some(handle) = self.handle.take() {
+                with_queue(|queue| queue.remove(handle));
+            }
+            return Poll::Ready(());
+        }
+        let (timer, handle, deadline) = ((self.mtimecmp, self.mtime), self.handle, self.deadline);
+        self.handle = with_queue(|queue| queue.register(timer, handle, deadline, cx.waker()));
+        if self.handle.is_none() {
+            // the table is full: fall back to busy polling
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for TimerFuture {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            with_queue(|queue| queue.remove(handle));
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::task::{RawWaker, RawWakerVTable};
+
+    pub(crate) static WAKES: AtomicUsize = AtomicUsize::new(0);
+
+    /// Serializes the tests that use the global table of wakers.
+    pub(crate) static QUEUE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Returns a waker that counts its wake-ups in [`WAKES`].
+    pub(crate) fn waker() -> Waker {
+        const VTABLE: RawWakerVTable = RawWakerVTable::new(
+            |_| RawWaker::new(core::ptr::null(), &VTABLE),
+            |_| {
+                WAKES.fetch_add(1, Ordering::SeqCst);
+            },
+            |_| {
+                WAKES.fetch_add(1, Ordering::SeqCst);
+            },
+            |_| {},
+        );
+        // SAFETY: the vtable functions do not use the data pointer
+        unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn check_timer_future() {
+        let _lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let wakes = WAKES.load(Ordering::SeqCst);
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                &mut raw_mtimecmp as *mut u64 as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+        let waker = waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = mtimer.sleep(100);
+        assert_eq!(future.deadline(), 100);
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Pending);
+        assert_eq!(mtimer.mtimecmp0.read(), 100);
+
+        mtimer.mtime.write(99);
+        TimerFuture::on_interrupt();
+        assert_eq!(WAKES.load(Ordering::SeqCst), wakes);
+        assert_eq!(mtimer.mtimecmp0.read(), 100);
+
+        mtimer.mtime.write(100);
+        TimerFuture::on_interrupt();
+        assert_eq!(WAKES.load(Ordering::SeqCst), wakes + 1);
+        assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(()));
+
+        // timer future bound to the `MTIMECMP` register of another HART
+        let mut raw_mtimecmp1 = 0u64;
+        // SAFETY: valid memory address
+        let mtimecmp1 = unsafe { MTIMECMP::new(&mut raw_mtimecmp1 as *mut u64 as _) };
+        let mut future = TimerFuture::new_on(mtimecmp1, mtimer.mtime, 200);
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Pending);
+        assert_eq!(mtimecmp1.read(), 200);
+        assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
+
+        mtimer.mtime.write(200);
+        TimerFuture::on_interrupt();
+        assert_eq!(WAKES.load(Ordering::SeqCst), wakes + 2);
+        assert_eq!(mtimecmp1.read(), u64::MAX);
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn check_reused_slot() {
+        let _lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                &mut raw_mtimecmp as *mut u64 as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+        let waker = waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // A expires and its slot is freed before A is polled again
+        let mut a = mtimer.sleep(100);
+        assert_eq!(Pin::new(&mut a).poll(&mut cx), Poll::Pending);
+        mtimer.mtime.write(100);
+        TimerFuture::on_interrupt();
+        assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
+
+        // B takes the slot of A
+        let mut b = mtimer.sleep(100);
+        assert_eq!(Pin::new(&mut b).poll(&mut cx), Poll::Pending);
+        assert_eq!(b.handle.map(|h| h.0), a.handle.map(|h| h.0));
+        assert_eq!(mtimer.mtimecmp0.read(), 200);
+
+        // A resolves without removing the waker of B
+        assert_eq!(Pin::new(&mut a).poll(&mut cx), Poll::Ready(()));
+        drop(a);
+        assert_eq!(mtimer.mtimecmp0.read(), 200);
+        let b_handle = b.handle.unwrap();
+        assert!(with_queue(|queue| queue.entry_mut(b_handle).is_some()));
+
+        let wakes = WAKES.load(Ordering::SeqCst);
+        mtimer.mtime.write(200);
+        TimerFuture::on_interrupt();
+        assert_eq!(WAKES.load(Ordering::SeqCst), wakes + 1);
+        assert_eq!(Pin::new(&mut b).poll(&mut cx), Poll::Ready(()));
+        assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
+    }
+
+    #[test]
+    fn check_two_harts() {
+        let _lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut raw_mtimecmp = [0u64; 2];
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp0, mtimecmp1, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp[0] as *mut u64 as _),
+                MTIMECMP::new(&mut raw_mtimecmp[1] as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+        let waker = waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut f0 = TimerFuture::new_on(mtimecmp0, mtime, 100);
+        let mut f1 = TimerFuture::new_on(mtimecmp1, mtime, 200);
+        assert_eq!(Pin::new(&mut f0).poll(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut f1).poll(&mut cx), Poll::Pending);
+        // each HART gets its own deadline
+        assert_eq!(mtimecmp0.read(), 100);
+        assert_eq!(mtimecmp1.read(), 200);
+
+        // the expired HART is cleared, the other one keeps its deadline
+        mtime.write(100);
+        TimerFuture::on_interrupt();
+        assert_eq!(mtimecmp0.read(), u64::MAX);
+        assert_eq!(mtimecmp1.read(), 200);
+        assert_eq!(Pin::new(&mut f0).poll(&mut cx), Poll::Ready(()));
+
+        // a new deadline of HART 0 does not overwrite HART 1
+        let mut f2 = TimerFuture::new_on(mtimecmp0, mtime, 300);
+        assert_eq!(Pin::new(&mut f2).poll(&mut cx), Poll::Pending);
+        assert_eq!(mtimecmp0.read(), 300);
+        assert_eq!(mtimecmp1.read(), 200);
+
+        // dropping the future of HART 1 clears its compare register
+        drop(f1);
+        assert_eq!(mtimecmp0.read(), 300);
+        assert_eq!(mtimecmp1.read(), u64::MAX);
+
+        mtime.write(300);
+        TimerFuture::on_interrupt();
+        assert_eq!(mtimecmp0.read(), u64::MAX);
+        assert_eq!(Pin::new(&mut f2).poll(&mut cx), Poll::Ready(()));
+    }
+}
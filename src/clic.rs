@@ -2,7 +2,98 @@
 //!
 //! Specification: <https://github.com/riscv/riscv-plic-spec/blob/master/riscv-plic.adoc>
 
+use crate::common::{Reg, RW};
+
+pub mod cause;
 pub mod interrupt;
+
+/// Highest interrupt number allowed by the CLIC specification.
+pub const MAX_INTERRUPT_NUMBER: u16 = 4095;
+
+/// Asserts that the interrupt numbers of `I` fit in the CLIC interrupt control registers region.
+///
+/// # Note
+///
+/// This function is intended to be used in `const` contexts, turning an invalid
+/// `I::MAX_INTERRUPT_NUMBER` into a compilation error:
+///
+/// ```compile_fail
+/// # use riscv_peripheral::clic::{assert_max_interrupt, InterruptNumber};
+/// # #[derive(Clone, Copy)]
+/// # struct Interrupt;
+/// unsafe impl InterruptNumber for Interrupt {
+///     const MAX_INTERRUPT_NUMBER: u16 = 4096;
+///     // ...
+/// #   fn number(self) -> u16 { 4096 }
+/// #   fn from_number(value: u16) -> Result<Self, u16> { Err(value) }
+/// }
+///
+/// const _: () = assert_max_interrupt::<Interrupt>(); // fails to compile
+/// ```
+#[inline]
+pub const fn assert_max_interrupt<I: InterruptNumber>() {
+    assert!(
+        I::MAX_INTERRUPT_NUMBER <= MAX_INTERRUPT_NUMBER,
+        "MAX_INTERRUPT_NUMBER exceeds the CLIC interrupt control registers region"
+    );
+}
+
+/// Returns the mask of the `clicintctl` bits below the `nlbits` most significant (level) bits.
+#[inline]
+const fn priority_mask(nlbits: u8) -> u8 {
+    match nlbits {
+        0 => u8::MAX,
+        n if n >= 8 => 0,
+        n => u8::MAX >> n,
+    }
+}
+
+/// Returns the lowest 8-bit interrupt level greater than `level` for a CLIC that implements
+/// `nlbits` level bits, or [`None`] if `level` is already the highest one.
+///
+/// # Note
+///
+/// The CLIC left-justifies the level field in the most significant bits of `clicintctl`,
+/// and the resulting 8-bit interrupt level (e.g., `mintstatus.MIL` or `mintthresh`) has the
+/// remaining bits set to one. Thus, with `nlbits = 2`, the valid levels are `0x3F`, `0x7F`,
+/// `0xBF`, and `0xFF`, and `next_level_up(0x3F, 2)` is `0x7F`. With `nlbits = 0`, all the
+/// interrupts have level `0xFF`. An `nlbits` greater than 8 is treated as 8.
+///
+/// Setting `mintthresh` to the returned value masks all the interrupts up to that level.
+#[inline]
+pub const fn next_level_up(level: u8, nlbits: u8) -> Option<u8> {
+    let mask = priority_mask(nlbits);
+    let candidate = level | mask; // level of the same step as `level`
+    if candidate > level {
+        Some(candidate)
+    } else if candidate == u8::MAX {
+        None
+    } else {
+        Some(candidate + mask + 1) // next step, with the lower bits set to one
+    }
+}
+
+/// Encodes a `level` and a `priority` in the left-justified 8-bit format of `clicintctl` and
+/// `mintthresh` for a CLIC that implements `nlbits` level bits.
+///
+/// # Note
+///
+/// `level` is the right-justified level number (from `0` to `2^nlbits - 1`), and it is placed in
+/// the `nlbits` most significant bits. `priority` fills the remaining least significant bits.
+/// Bits of `level` and `priority` that do not fit in their fields are discarded.
+/// For instance, with `nlbits = 2`, `encode_threshold(1, 3, 2)` is `0b01_000011`.
+/// An `nlbits` greater than 8 is treated as 8.
+#[inline]
+pub const fn encode_threshold(level: u8, priority: u8, nlbits: u8) -> u8 {
+    let mask = priority_mask(nlbits);
+    let level = match nlbits {
+        0 => 0,
+        n if n >= 8 => level,
+        n => level << (8 - n),
+    };
+    (level & !mask) | (priority & mask)
+}
+
 /// Trait for enums of interrupt numbers.
 ///
 /// This trait should be implemented by a peripheral access crate (PAC)
@@ -11,7 +102,15 @@ pub mod interrupt;
 ///
 /// # Note
 ///
-/// Recall that the interrupt number `0` is reserved as "no interrupt".
+/// Unlike in the PLIC, the interrupt number `0` is a valid CLIC interrupt (the CLIC specification
+/// assigns the IDs `0` to `15` to the local interrupts of the privileged architecture), and its
+/// configuration registers are the first ones of the CLIC. Thus, "no interrupt" is never encoded as
+/// interrupt number `0`. Instead, methods such as [`CLIC::next_interrupt_handler`] return [`None`].
+///
+/// # Map keys
+///
+/// As with [`crate::plic::InterruptNumber`], PACs should derive `Hash` to use interrupt sources
+/// as keys of hash maps, and wrap them in [`Source`] to sort them by number.
 ///
 /// # Safety
 ///
@@ -31,6 +130,51 @@ pub unsafe trait InterruptNumber: Copy {
     /// Tries to convert a number to a valid interrupt source.
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u16) -> Result<Self, u16>;
+
+    /// Tries to convert a number to a valid interrupt source.
+    /// If the conversion fails, it returns a [`crate::error::InvalidInterruptNumber`] error.
+    #[inline]
+    fn try_from_number(value: u16) -> Result<Self, crate::error::InvalidInterruptNumber> {
+        Self::from_number(value).map_err(crate::error::InvalidInterruptNumber)
+    }
+
+    /// Tries to convert a number to a valid interrupt source.
+    /// If the conversion fails, it returns [`None`].
+    #[inline]
+    fn try_from_number_opt(value: u16) -> Option<Self> {
+        Self::from_number(value).ok()
+    }
+
+    /// Converts a number to a valid interrupt source, saturating it to `MAX_INTERRUPT_NUMBER`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the saturated number is not a valid interrupt source.
+    #[inline]
+    fn from_number_saturating(value: u16) -> Self {
+        match Self::from_number(value.min(Self::MAX_INTERRUPT_NUMBER)) {
+            Ok(v) => v,
+            Err(n) => panic!("invalid interrupt source number: {}", n),
+        }
+    }
+
+    /// Returns the name of an interrupt source (e.g., `UART0`), if any.
+    ///
+    /// By default, it returns [`None`]. PACs may override it to provide device-specific names.
+    #[inline]
+    fn name(self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns a human-readable name of an interrupt source for logging.
+    /// It formats as [`Self::name`] or, if there is none, as `IRQ` followed by the interrupt number.
+    #[inline]
+    fn display_name(self) -> crate::common::InterruptName {
+        crate::common::InterruptName {
+            number: self.number(),
+            name: self.name(),
+        }
+    }
 }
 /// Trait for enums of priority levels.
 ///
@@ -61,17 +205,118 @@ pub unsafe trait PriorityNumber: Copy {
     /// Tries to convert a number to a valid priority level.
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u8) -> Result<Self, u8>;
+
+    /// Tries to convert a number to a valid priority level.
+    /// If the conversion fails, it returns a [`crate::error::InvalidPriorityNumber`] error.
+    #[inline]
+    fn try_from_number(value: u8) -> Result<Self, crate::error::InvalidPriorityNumber> {
+        Self::from_number(value).map_err(crate::error::InvalidPriorityNumber)
+    }
+
+    /// Tries to convert a number to a valid priority level.
+    /// If the conversion fails, it returns [`None`].
+    #[inline]
+    fn try_from_number_opt(value: u8) -> Option<Self> {
+        Self::from_number(value).ok()
+    }
+
+    /// Converts a number to a valid priority level, saturating it to `MAX_PRIORITY_NUMBER`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the saturated number is not a valid priority level.
+    #[inline]
+    fn from_number_saturating(value: u8) -> Self {
+        match Self::from_number(value.min(Self::MAX_PRIORITY_NUMBER)) {
+            Ok(v) => v,
+            Err(n) => panic!("invalid priority level number: {}", n),
+        }
+    }
 }
+crate::common::ordered_number!(
+    Priority,
+    PriorityNumber,
+    "Priority level of a CLIC interrupt source that can be compared with other priority levels."
+);
+
+crate::common::ordered_number!(
+    Source,
+    InterruptNumber,
+    "CLIC interrupt source that can be compared with other interrupt sources."
+);
+
 /// Trait for a CLIC peripheral.
 ///
+/// # Field-presence model
+///
+/// Each interrupt source owns a 32-bit configuration word with four byte-wide registers
+/// (see [`interrupt::IrqField`]). `clicintip` and `clicintie` are always implemented, but a
+/// CLIC may omit `clicintattr` or `clicintctl`, as described by [`Clic::HAS_ATTR`] and
+/// [`Clic::HAS_CTL`]. Absent registers are never accessed by byte-wide methods:
+///
+/// * Getters return the hardwired value of the register without reading it: the `clicintattr`
+///   of a level-triggered (active-high), non-vectored machine mode interrupt, and a `clicintctl`
+///   without implemented bits (i.e., all the interrupts have the maximum level).
+/// * Setters do nothing. As the presence of each register is a constant, the compiler removes
+///   the access altogether. Their `try_` variants return an [`interrupt::UnimplementedField`] error,
+///   and checked methods such as [`interrupt::INTERRUPTS::set_mode_range`] also report the absent register.
+///
+/// Word-wide methods (e.g., [`interrupt::INTERRUPTS::read_raw`]) still access the whole word,
+/// so the bytes of absent registers must read as zero and ignore writes.
+///
 /// # Safety
 ///
 /// * This trait must only be implemented on a PAC of a target with a CLIC peripheral.
 /// * The CLIC peripheral base address `BASE` must be valid for the target device.
+/// * In multi-core targets, `BASE + core * STRIDE` must be the base address of the CLIC of each core.
+/// * `HAS_ATTR` and `HAS_CTL` must reflect which registers are implemented by the CLIC.
 pub unsafe trait Clic: Copy {
-    /// Base address of the CLIC peripheral.
+    /// Base address of the CLIC peripheral (of core 0 in multi-core targets).
     const BASE: usize;
+
+    /// Separation (in bytes) between the CLICs of consecutive cores in multi-core targets.
+    /// By default, it is 0 (i.e., the target has a single CLIC).
+    const STRIDE: usize = 0;
+
+    /// Number of bits implemented in the `clicintctl` registers (`CLICINTCTLBITS` parameter of the CLIC).
+    /// Implemented bits are the most significant ones, and it must be less than or equal to 8.
+    const CLICINTCTLBITS: u8 = 8;
+
+    /// `true` if the CLIC implements the `clicintattr` registers. By default, it is `true`.
+    const HAS_ATTR: bool = true;
+
+    /// `true` if the CLIC implements the `clicintctl` registers. By default, it is `true`.
+    const HAS_CTL: bool = true;
+}
+/// Table of interrupt handlers for the selective hardware vectoring mode of the CLIC.
+///
+/// Entry `i` holds the address of the handler of interrupt source `i`. The HART jumps directly to it,
+/// so it must follow the interrupt ABI (see the notes of [`crate::clic_vector_table!`]).
+/// The `mtvt` CSR must point to the table, which is aligned to 64 bytes as required
+/// by the CLIC specification. Use the [`crate::clic_vector_table!`] macro to build it.
+#[repr(C, align(64))]
+pub struct VectorTable<const N: usize>(pub [unsafe extern "C" fn(); N]);
+
+impl<const N: usize> VectorTable<N> {
+    /// Returns the number of entries of the table.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the table has no entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns the address of the table, to be written to the `mtvt` CSR.
+    #[inline]
+    pub fn address(&self) -> usize {
+        self.0.as_ptr() as usize
+    }
 }
+
 /// Core-Local Interrupt Controler (CLIC) peripheral.
 ///
 /// The RISC-V standard does not specify a fixed location for the CLIC.
@@ -88,15 +333,51 @@ pub struct CLIC<C: Clic> {
 }
 
 impl<C: Clic> CLIC<C> {
+    /// Offset to the `cliccfg` register
+    const CLICCFG_OFFSET: usize = 0x0;
+
     /// Offset to the interrupt control register block
     const INTERRUPTS_OFFSET: usize = 0x1000;
 
+    /// Address of the `cliccfg` register.
+    pub const CLICCFG_ADDR: usize = C::BASE + Self::CLICCFG_OFFSET;
+    /// Address of the interrupt control register block (i.e., the registers of interrupt source 0).
+    pub const INTERRUPTS_ADDR: usize = C::BASE + Self::INTERRUPTS_OFFSET;
+
+    /// Creates a new CLIC proxy. It can be used in `const` contexts.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns `true` if the HART is handling a CLIC interrupt, i.e., if the
+    /// machine interrupt level of the `mintstatus` CSR is not zero.
+    #[inline]
+    pub fn is_interrupting() -> bool {
+        Self::interrupt_status().mil != 0
+    }
+
+    /// Reads and decodes the `mintstatus` CSR, which holds the current interrupt level of each privilege mode.
+    ///
+    /// This is useful for debugging preemption, e.g., to verify that nested interrupts run at the expected level.
+    #[inline]
+    pub fn interrupt_status() -> InterruptStatus {
+        InterruptStatus::from_bits(crate::csr::mintstatus::read())
+    }
+
+    /// Returns `true` if the Machine Mode Interrupt Enable bit of the `mstatus` CSR is set.
+    #[inline]
+    pub fn is_enabled() -> bool {
+        crate::csr::mstatus::mie()
+    }
+
     /// Clears the Machine Mode Interrupt Enable bit of the `mstatus` CSR.
     /// When cleared, CLIC interrupts are effectively disabled.
     #[inline]
     pub fn disable() {
-        // SAFETY: it is safe to disable interrupts
-        unsafe { riscv::register::mstatus::clear_mie() };
+        crate::csr::mstatus::clear_mie();
     }
 
     /// Sets the Machine Mode Interrupt Enable bit of the `mstatus` CSR.
@@ -107,32 +388,607 @@ impl<C: Clic> CLIC<C> {
     /// Enabling interrupts may break critical sections.
     #[inline]
     pub fn enable() {
-        unsafe { riscv::register::mstatus::set_mie() };
+        unsafe { crate::csr::mstatus::set_mie() };
     }
+
+    /// Waits for interrupts until `condition` returns `true`. Global machine interrupts are
+    /// enabled when this method returns.
+    ///
+    /// # Note
+    ///
+    /// `condition` is evaluated with the `MIE` bit of the `mstatus` CSR cleared, and the HART
+    /// executes `wfi` right after a `false` result without enabling interrupts in between. Thus,
+    /// an interrupt that arrives after the check cannot be handled before the HART goes to sleep.
+    /// The HART still wakes up when an enabled CLIC interrupt becomes pending with a level higher
+    /// than both the current interrupt level and the global threshold (see [`CLIC::get_threshold`]).
+    /// Then, global interrupts are enabled, so the corresponding interrupt handler runs before
+    /// `condition` is evaluated again.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub unsafe fn wait_for_interrupt<F: FnMut() -> bool>(condition: F) {
+        crate::csr::wait_until(condition);
+    }
+
     /// Sets the global priority threshold against which all pending interrupts are filtered.
     #[inline]
     pub fn set_threshold(thresh: usize) {
-        unsafe { core::arch::asm!("csrrw x0, 0x347 , {0}", in(reg) thresh as isize) };
+        crate::csr::mintthresh::write(thresh);
     }
     /// Returns the current global priority threshold against which all pending interrupts are
     /// filtered.
     #[inline]
     pub fn get_threshold() -> usize {
-        let r: usize;
-        unsafe { core::arch::asm!(concat!("csrrs {0}, 0x347 , x0"), out(reg) r) };
-        r
+        crate::csr::mintthresh::read()
+    }
+
+    /// Sets the global priority threshold and returns the previous one.
+    ///
+    /// The threshold is read and written with a single `csrrw` instruction, so no interrupt
+    /// can change the threshold between both accesses. This is the building block of
+    /// threshold-based critical sections: save the previous threshold and raise it at once.
+    #[inline]
+    pub fn swap_threshold(thresh: usize) -> usize {
+        crate::csr::mintthresh::swap(thresh)
+    }
+
+    /// Runs `f` with the global priority threshold set to `thresh`.
+    /// The previous threshold is saved and restored afterwards, even if `f` returns early or panics.
+    ///
+    /// This is the CLIC analog of a `BASEPRI`-based critical section: interrupts with a level
+    /// less than or equal to `thresh` cannot preempt `f`.
+    ///
+    /// # Note
+    ///
+    /// Restoring the previous threshold may lower it. In that case, any pending interrupt above the
+    /// restored threshold is taken immediately after the restore, i.e., right after `f` returns and
+    /// before the caller regains control. A compiler fence keeps the compiler from moving the memory
+    /// accesses of `f` past the restore, but it does not order them in hardware. If a handler taken
+    /// after the restore must observe device accesses performed by `f`, `f` must end with a `fence`.
+    #[inline]
+    pub fn with_threshold<F: FnOnce() -> R, R>(thresh: usize, f: F) -> R {
+        let _guard = ThresholdGuard::new(thresh);
+        f()
+    }
+
+    /// Raises the global priority threshold to the level of `level`, masking all the interrupts
+    /// with a level less than or equal to it. It returns a [`ThresholdGuard`] that restores the
+    /// previous threshold when dropped.
+    ///
+    /// The level is converted to the raw `mintthresh` encoding (i.e., the `clicintctl` value of `level`).
+    /// If the current threshold is already higher, it is left unchanged.
+    #[inline]
+    pub fn raise_threshold_to<P: PriorityNumber>(level: P) -> ThresholdGuard {
+        let thresh = level.number() as usize;
+        ThresholdGuard::new(thresh.max(crate::csr::mintthresh::read()))
+    }
+
+    /// Clears the Machine Mode Interrupt Enable bit of the `mstatus` CSR and returns the previous
+    /// [`CriticalState`], i.e., the previous value of `mstatus.MIE` and of the global priority threshold.
+    ///
+    /// Global interrupts are disabled before the threshold is read, so no interrupt handler can
+    /// change the threshold after it is captured. The critical section may then change the threshold
+    /// freely: [`CLIC::exit_critical`] restores both values.
+    #[inline]
+    pub fn enter_critical() -> CriticalState {
+        let mie = crate::csr::mstatus::mie();
+        crate::csr::mstatus::clear_mie();
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        CriticalState {
+            threshold: crate::csr::mintthresh::read(),
+            mie,
+        }
     }
+
+    /// Restores the global priority threshold and the Machine Mode Interrupt Enable bit of the
+    /// `mstatus` CSR, as returned by [`CLIC::enter_critical`].
+    ///
+    /// The threshold is restored before `mstatus.MIE` is set again. Otherwise, an interrupt
+    /// masked by the previous threshold could be taken in between.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections. The state must be restored in the reverse
+    /// order of [`CLIC::enter_critical`] calls.
+    #[inline]
+    pub unsafe fn exit_critical(state: CriticalState) {
+        exit_critical(state)
+    }
+
+    /// Runs `f` with global interrupts disabled. The previous global priority threshold and
+    /// `mstatus.MIE` are restored afterwards, even if `f` returns early or panics.
+    #[inline]
+    pub fn with_critical<F: FnOnce() -> R, R>(f: F) -> R {
+        let _guard = CriticalGuard(Self::enter_critical());
+        f()
+    }
+
     /// Returns the interrupt configuration registers of the CLIC.
     #[inline]
-    pub fn interrupts() -> interrupt::INTERRUPTS {
+    pub const fn interrupts() -> interrupt::INTERRUPTS<C> {
+        // SAFETY: Valid address
+        unsafe { interrupt::INTERRUPTS::new(Self::INTERRUPTS_ADDR) }
+    }
+
+    /// Returns the address of the 32-bit configuration word of the interrupt source number `source`.
+    /// See [`interrupt::IrqField`] for the layout of the word.
+    #[inline]
+    pub const fn interrupt_addr(source: u16) -> usize {
+        Self::INTERRUPTS_ADDR + 4 * source as usize
+    }
+
+    /// Returns the base address of the CLIC of the core which index is `core`,
+    /// i.e., `C::BASE + core * C::STRIDE`.
+    ///
+    /// # Note
+    ///
+    /// In debug builds, it panics if `core` is not 0 and `C::STRIDE` is 0.
+    #[inline]
+    pub const fn base(core: usize) -> usize {
+        debug_assert!(core == 0 || C::STRIDE != 0, "CLIC STRIDE is not defined");
+        C::BASE + core * C::STRIDE
+    }
+
+    /// Returns the interrupt configuration registers of the CLIC of the core which index is `core`.
+    ///
+    /// # Note
+    ///
+    /// The CLIC is core-local: interrupts configured in the CLIC of another core are delivered to
+    /// that core, not to the current one. Accessing the CLIC of another core is only intended for
+    /// debugging and bring-up purposes. Use [`CLIC::interrupts`] for the CLIC of the current core.
+    #[inline]
+    pub const fn interrupts_of(core: usize) -> interrupt::INTERRUPTS<C> {
         // SAFETY: Valid address
-        unsafe { interrupt::INTERRUPTS::new(C::BASE + Self::INTERRUPTS_OFFSET) }
+        unsafe { interrupt::INTERRUPTS::new(Self::base(core) + Self::INTERRUPTS_OFFSET) }
+    }
+
+    /// Claims the next pending interrupt for software dispatch via the `mnxti` CSR.
+    ///
+    /// It reads `mnxti` with `csrrsi rd, mnxti, MIE` (encoding `0x34546073 | rd << 7`), which
+    /// atomically sets `mstatus.MIE` and, if there is a pending and enabled horizontal interrupt
+    /// with a level higher than both `mcause.MPIL` and `mintthresh`, claims it. Claiming an
+    /// interrupt raises `mintstatus.MIL` to its level, sets `mcause.EXCCODE` to its number,
+    /// and clears its pending bit if it is edge-triggered.
+    ///
+    /// If an interrupt is claimed, it returns its number and a [`MnxtiToken`], and interrupts
+    /// remain enabled so that higher-level interrupts can preempt its handler. Otherwise,
+    /// it clears `mstatus.MIE` with `csrrci zero, mstatus, MIE` (encoding `0x30047073`)
+    /// and returns [`None`].
+    ///
+    /// # Note
+    ///
+    /// This method must only be called from a non-vectored CLIC trap handler, after saving
+    /// the context of the interrupted code. The usual dispatch loop looks like this:
+    ///
+    /// ```no_run
+    /// # use riscv_peripheral::clic::{Clic, CLIC};
+    /// # #[derive(Clone, Copy)]
+    /// # struct MyClic;
+    /// # unsafe impl Clic for MyClic { const BASE: usize = 0x0C00_0000; }
+    /// # fn dispatch(_number: u16) {}
+    /// while let Some((number, token)) = unsafe { CLIC::<MyClic>::next_interrupt_handler() } {
+    ///     dispatch(number);
+    ///     token.complete(); // disables interrupts before claiming the next one
+    /// }
+    /// // restore the context and `mret`
+    /// ```
+    ///
+    /// `mintstatus.MIL` stays at the level of the last claimed interrupt until `mret`, which
+    /// restores the previous level from `mcause.MPIL` and thus resumes lower-level interrupts.
+    /// As the comparison uses `mcause.MPIL`, successive calls also claim pending interrupts
+    /// with a level lower than the last claimed one (but higher than the interrupted code).
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub unsafe fn next_interrupt_handler() -> Option<(u16, MnxtiToken)> {
+        match crate::csr::mnxti::read_set_mie() {
+            0 => {
+                crate::csr::mstatus::clear_mie();
+                None
+            }
+            entry => {
+                let number = cause::read().exception_code;
+                Some((number, MnxtiToken::new(entry)))
+            }
+        }
+    }
+
+    /// Returns the `cliccfg` register of the CLIC.
+    #[inline]
+    fn cliccfg() -> Reg<u8, RW> {
+        // SAFETY: valid address
+        unsafe { Reg::new(Self::CLICCFG_ADDR as _) }
+    }
+
+    /// Returns `true` if selective hardware vectoring is enabled in the `nvbits` field of `cliccfg`.
+    #[inline]
+    pub fn is_vectored() -> bool {
+        is_vectored(Self::cliccfg())
+    }
+
+    /// Enables or disables selective hardware vectoring via the `nvbits` field of `cliccfg`.
+    ///
+    /// # Note
+    ///
+    /// Global vectoring and the per-interrupt `shv` bit of `clicintattr` work together:
+    ///
+    /// * If vectoring is disabled, all the interrupts jump to the common trap handler
+    ///   pointed by `mtvec`, regardless of their `shv` bit.
+    /// * If vectoring is enabled, the interrupts with the `shv` bit set jump to their entry
+    ///   in the vector table pointed by `mtvt`, while the rest jump to the common trap handler.
+    ///
+    /// Use [`CLIC::init_vectored`] to set the vector table and enable vectoring in one call.
+    /// Some CLIC implementations hardwire `nvbits`. In that case, this method has no effect.
+    ///
+    /// # Safety
+    ///
+    /// Switching modes while interrupts are enabled may dispatch interrupts to the wrong handler.
+    /// It must only be called during initialization, before interrupts are enabled.
+    #[inline]
+    pub unsafe fn set_vectored(enabled: bool) {
+        set_vectored(Self::cliccfg(), enabled);
+    }
+
+    /// Points the `mtvt` CSR to `table` and enables selective hardware vectoring.
+    /// The table is usually generated with the [`crate::clic_vector_table!`] macro.
+    ///
+    /// # Note
+    ///
+    /// Only the interrupts with the `shv` bit of `clicintattr` set are vectored
+    /// (see [`CLIC::set_vectored`]). The `mtvec` CSR must be set to CLIC mode.
+    ///
+    /// # Safety
+    ///
+    /// It must only be called during initialization, before interrupts are enabled.
+    #[inline]
+    pub unsafe fn init_vectored<const N: usize>(table: &'static VectorTable<N>) {
+        crate::csr::mtvt::write(table.address());
+        set_vectored(Self::cliccfg(), true);
+    }
+
+    /// Probes the number of interrupt sources implemented by the CLIC.
+    /// See [`interrupt::INTERRUPTS::num_interrupts`] for details.
+    ///
+    /// # Note
+    ///
+    /// This method is intended to be used during initialization, before interrupts are enabled.
+    #[inline]
+    pub fn num_interrupts() -> u16 {
+        Self::interrupts().num_interrupts()
+    }
+
+    /// Probes the number of `clicintctl` bits implemented by the CLIC, using the registers of `source`.
+    /// See [`interrupt::INTERRUPTS::implemented_ctl_bits`] for details.
+    ///
+    /// Portable code can use it to adapt the mapping of priority levels to the actual hardware
+    /// instead of relying on [`Clic::CLICINTCTLBITS`].
+    ///
+    /// # Note
+    ///
+    /// This method is intended to be used during initialization, before interrupts are enabled.
+    #[inline]
+    pub fn implemented_ctl_bits<I: InterruptNumber>(source: I) -> u8 {
+        Self::interrupts().implemented_ctl_bits(source)
+    }
+
+    /// Enables an interrupt source and then sets the Machine Mode Interrupt Enable bit of the `mstatus` CSR.
+    /// It returns the previous [`EnableState`], which can be restored with [`CLIC::restore_source_and_global`].
+    ///
+    /// # Note
+    ///
+    /// This method is intended for bringing up a single interrupt source. It is not a substitute
+    /// for a careful ordering of the configuration steps in complex setups (e.g., configuring the
+    /// priority and trigger of every source before enabling global interrupts).
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub unsafe fn enable_source_and_global<I: InterruptNumber>(source: I) -> EnableState {
+        enable_source_and_global(Self::interrupts(), source)
+    }
+
+    /// Clears the Machine Mode Interrupt Enable bit of the `mstatus` CSR and then disables an interrupt source.
+    /// It returns the previous [`EnableState`], which can be restored with [`CLIC::restore_source_and_global`].
+    #[inline]
+    pub fn disable_source_and_global<I: InterruptNumber>(source: I) -> EnableState {
+        disable_source_and_global(Self::interrupts(), source)
+    }
+
+    /// Restores the enable state of an interrupt source and of global interrupts, as returned by
+    /// [`CLIC::enable_source_and_global`] or [`CLIC::disable_source_and_global`].
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub unsafe fn restore_source_and_global<I: InterruptNumber>(source: I, state: EnableState) {
+        restore_source_and_global(Self::interrupts(), source, state)
+    }
+}
+
+/// Decoded value of the `mintstatus` CSR.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct InterruptStatus {
+    /// Current machine mode interrupt level (bits `31:24`).
+    pub mil: u8,
+    /// Current supervisor mode interrupt level (bits `15:8`).
+    pub sil: u8,
+    /// Current user mode interrupt level (bits `7:0`).
+    pub uil: u8,
+}
+
+impl InterruptStatus {
+    /// Decodes a raw `mintstatus` value.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self {
+            mil: (bits >> 24) as u8,
+            sil: (bits >> 8) as u8,
+            uil: bits as u8,
+        }
+    }
+}
+
+/// Mask of the `nvbits` field of `cliccfg`.
+const CLICCFG_NVBITS: u8 = 0x1;
+
+/// See [`CLIC::is_vectored`].
+#[inline]
+fn is_vectored(cliccfg: Reg<u8, RW>) -> bool {
+    cliccfg.read() & CLICCFG_NVBITS != 0
+}
+
+/// See [`CLIC::set_vectored`].
+#[inline]
+fn set_vectored(cliccfg: Reg<u8, RW>, enabled: bool) {
+    cliccfg.modify(|r| match enabled {
+        true => *r |= CLICCFG_NVBITS,
+        false => *r &= !CLICCFG_NVBITS,
+    });
+}
+
+/// Token of an interrupt claimed with [`CLIC::next_interrupt_handler`].
+///
+/// When completed or dropped, it clears `mstatus.MIE` with `csrrci zero, mstatus, MIE`
+/// (encoding `0x30047073`), so that the trap handler can claim the next interrupt
+/// or restore the interrupted context without being preempted.
+/// As `mnxti` is HART-local, the token cannot be sent to other threads.
+#[must_use = "interrupts are disabled as soon as the token is dropped"]
+#[derive(Debug)]
+pub struct MnxtiToken {
+    entry: usize,
+    _marker: core::marker::PhantomData<*const ()>,
+}
+
+impl MnxtiToken {
+    #[inline]
+    const fn new(entry: usize) -> Self {
+        Self {
+            entry,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the address of the vector table entry of the claimed interrupt, as returned by `mnxti`.
+    #[inline]
+    pub const fn entry(&self) -> usize {
+        self.entry
+    }
+
+    /// Completes the handling of the claimed interrupt, disabling interrupts.
+    /// It is equivalent to dropping the token.
+    #[inline]
+    pub fn complete(self) {}
+}
+
+impl Drop for MnxtiToken {
+    #[inline]
+    fn drop(&mut self) {
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        crate::csr::mstatus::clear_mie();
+    }
+}
+
+/// Enable state of an interrupt source and of global machine interrupts (i.e., `mstatus.MIE`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct EnableState {
+    /// `true` if the interrupt source was enabled.
+    pub source: bool,
+    /// `true` if global machine interrupts were enabled.
+    pub global: bool,
+}
+
+/// See [`CLIC::enable_source_and_global`].
+#[inline]
+unsafe fn enable_source_and_global<C: Clic, I: InterruptNumber>(
+    interrupts: interrupt::INTERRUPTS<C>,
+    source: I,
+) -> EnableState {
+    let state = EnableState {
+        source: interrupts.is_enabled(source),
+        global: crate::csr::mstatus::mie(),
+    };
+    interrupts.enable(source);
+    crate::csr::mstatus::set_mie();
+    state
+}
+
+/// See [`CLIC::disable_source_and_global`].
+#[inline]
+fn disable_source_and_global<C: Clic, I: InterruptNumber>(
+    interrupts: interrupt::INTERRUPTS<C>,
+    source: I,
+) -> EnableState {
+    let state = EnableState {
+        source: interrupts.is_enabled(source),
+        global: crate::csr::mstatus::mie(),
+    };
+    crate::csr::mstatus::clear_mie();
+    interrupts.disable(source);
+    state
+}
+
+/// See [`CLIC::restore_source_and_global`].
+#[inline]
+unsafe fn restore_source_and_global<C: Clic, I: InterruptNumber>(
+    interrupts: interrupt::INTERRUPTS<C>,
+    source: I,
+    state: EnableState,
+) {
+    crate::csr::mstatus::clear_mie();
+    match state.source {
+        true => interrupts.enable(source),
+        false => interrupts.disable(source),
+    }
+    if state.global {
+        crate::csr::mstatus::set_mie();
+    }
+}
+
+/// Global interrupt state of a CLIC critical section, as returned by [`CLIC::enter_critical`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct CriticalState {
+    /// Previous value of the `mintthresh` register.
+    pub threshold: usize,
+    /// `true` if global machine interrupts were enabled.
+    pub mie: bool,
+}
+
+/// Drop guard of [`CLIC::with_critical`].
+struct CriticalGuard(CriticalState);
+
+impl Drop for CriticalGuard {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the state was captured by the matching `enter_critical` call
+        unsafe { exit_critical(self.0) };
+    }
+}
+
+/// See [`CLIC::exit_critical`].
+#[inline]
+unsafe fn exit_critical(state: CriticalState) {
+    crate::csr::mstatus::clear_mie();
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    crate::csr::mintthresh::write(state.threshold);
+    if state.mie {
+        crate::csr::mstatus::set_mie();
+    }
+}
+
+/// Drop guard that restores the previous value of the `mintthresh` register.
+///
+/// It is returned by [`CLIC::raise_threshold_to`] and [`CeilingLock::lock`]. As `mintthresh` is HART-local,
+/// the guard cannot be sent to other threads.
+#[must_use = "the previous threshold is restored as soon as the guard is dropped"]
+pub struct ThresholdGuard {
+    prev: usize,
+    _marker: core::marker::PhantomData<*const ()>,
+}
+
+impl ThresholdGuard {
+    /// Saves the current threshold and sets it to `thresh`.
+    #[inline]
+    fn new(thresh: usize) -> Self {
+        let prev = crate::csr::mintthresh::swap(thresh);
+        Self {
+            prev,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the threshold that is restored when the guard is dropped.
+    #[inline]
+    pub const fn previous(&self) -> usize {
+        self.prev
+    }
+}
+
+impl Drop for ThresholdGuard {
+    #[inline]
+    fn drop(&mut self) {
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        crate::csr::mintthresh::write(self.prev);
+    }
+}
+
+/// Priority-ceiling lock of a resource shared among interrupt handlers.
+///
+/// `CEIL` is the ceiling of the resource in the raw encoding of the `mintthresh` register, i.e.,
+/// the highest `clicintctl` value among the interrupts that access the resource. While the lock
+/// is held, these interrupts are masked, so the resource can be accessed without disabling
+/// interrupts globally. Interrupts above the ceiling are still taken.
+///
+/// # Nesting
+///
+/// Locks can be nested. Acquiring a lock with a ceiling lower than or equal to the current
+/// threshold leaves the threshold unchanged, so an inner lock never unmasks interrupts masked by
+/// an outer lock. Each guard restores the threshold found when it was acquired, so guards must be
+/// dropped in the reverse order of acquisition, which is the case for guards bound to nested scopes.
+/// Interrupt handlers that acquire locks restore the threshold before returning, so they do not
+/// interfere with the locks of the interrupted code.
+///
+/// # Example
+///
+/// ```no_run
+/// use riscv_peripheral::clic::CeilingLock;
+///
+/// static UART_LOCK: CeilingLock<0xBF> = CeilingLock::new();
+///
+/// {
+///     let _guard = UART_LOCK.lock(); // mintthresh is raised to 0xBF
+///     // access the shared resource
+/// } // the previous threshold is restored
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CeilingLock<const CEIL: usize>;
+
+impl<const CEIL: usize> CeilingLock<CEIL> {
+    /// Creates a new priority-ceiling lock. It can be used in `const` contexts.
+    #[inline]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns the ceiling of the lock.
+    #[inline]
+    pub const fn ceiling(&self) -> usize {
+        CEIL
+    }
+
+    /// Raises the `mintthresh` register to the ceiling of the lock, unless it is already higher.
+    /// It returns a [`ThresholdGuard`] that restores the previous threshold when dropped.
+    #[inline]
+    pub fn lock(&self) -> ThresholdGuard {
+        ThresholdGuard::new(CEIL.max(crate::csr::mintthresh::read()))
     }
 }
 
 #[cfg(test)]
 pub(crate) mod test {
-    use super::{InterruptNumber, PriorityNumber};
+    use super::{Clic, InterruptNumber, PriorityNumber, Reg, RW};
+
+    /// CLIC with all the `clicintctl` bits implemented.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub(crate) struct Clic8;
+
+    unsafe impl Clic for Clic8 {
+        const BASE: usize = 0;
+    }
+
+    /// CLIC with only the 4 most significant `clicintctl` bits implemented.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub(crate) struct Clic4;
+
+    unsafe impl Clic for Clic4 {
+        const BASE: usize = 0;
+        const CLICINTCTLBITS: u8 = 4;
+    }
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     #[repr(u16)]
@@ -204,6 +1060,12 @@ pub(crate) mod test {
 
         assert_eq!(Interrupt::from_number(0), Err(0));
         assert_eq!(Interrupt::from_number(5), Err(5));
+
+        assert_eq!(Interrupt::try_from_number_opt(4), Some(Interrupt::I4));
+        assert_eq!(Interrupt::try_from_number_opt(0), None);
+        assert_eq!(Interrupt::try_from_number_opt(5), None);
+        assert_eq!(Interrupt::from_number_saturating(2), Interrupt::I2);
+        assert_eq!(Interrupt::from_number_saturating(5), Interrupt::I4);
     }
 
     #[test]
@@ -219,15 +1081,361 @@ pub(crate) mod test {
         assert_eq!(Priority::from_number(3), Ok(Priority::P3));
 
         assert_eq!(Priority::from_number(4), Err(4));
+
+        assert_eq!(Priority::try_from_number_opt(3), Some(Priority::P3));
+        assert_eq!(Priority::try_from_number_opt(4), None);
+        assert_eq!(Priority::from_number_saturating(0), Priority::P0);
+        assert_eq!(Priority::from_number_saturating(u8::MAX), Priority::P3);
     }
 
     #[allow(dead_code)]
     #[test]
     fn check_clic() {
-        crate::clic_codegen!(base 0x1000);
+        crate::clic_codegen!(base 0x1000, interrupts Interrupt,);
 
         let interrupts = CLIC::interrupts();
 
         assert_eq!(interrupts.address(), 0x0000_2000);
+
+        // addresses are available in const contexts
+        const INTERRUPT3_ADDR: usize = CLIC::interrupt_addr(3);
+        assert_eq!(INTERRUPT3_ADDR, 0x0000_200C);
+        assert_eq!(CLIC::CLICCFG_ADDR, 0x0000_1000);
+        assert_eq!(CLIC::INTERRUPTS_ADDR, interrupts.address());
+        assert_eq!(
+            CLIC::interrupt_addr(1),
+            interrupts.register_address(Interrupt::I1, super::interrupt::IrqField::Pending)
+        );
+    }
+
+    #[test]
+    fn check_multi_core() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct MultiClic;
+
+        unsafe impl Clic for MultiClic {
+            const BASE: usize = 0x0C00_0000;
+            const STRIDE: usize = 0x0001_0000;
+        }
+
+        type Multi = super::CLIC<MultiClic>;
+
+        assert_eq!(Multi::base(0), 0x0C00_0000);
+        assert_eq!(Multi::base(1), 0x0C01_0000);
+        assert_eq!(
+            Multi::interrupts_of(0).address(),
+            Multi::interrupts().address()
+        );
+        assert_eq!(Multi::interrupts_of(1).address(), 0x0C01_1000);
+        assert_eq!(Multi::interrupts_of(3).address(), 0x0C03_1000);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "CLIC STRIDE is not defined")]
+    fn check_multi_core_no_stride() {
+        super::CLIC::<Clic8>::interrupts_of(1);
+    }
+
+    #[allow(dead_code)]
+    #[test]
+    fn check_interrupt_controller() {
+        use crate::InterruptController;
+
+        crate::clic_codegen!(base 0x1000,);
+
+        crate::csr::mstatus::clear_mie();
+        assert!(!<CLIC as InterruptController>::is_enabled());
+        unsafe { crate::csr::mstatus::set_mie() };
+        assert!(<CLIC as InterruptController>::is_enabled());
+        crate::csr::mstatus::clear_mie();
+
+        crate::csr::mintstatus::set(0);
+        assert!(!<CLIC as InterruptController>::is_interrupting());
+        crate::csr::mintstatus::set(0x80 << 24);
+        assert!(<CLIC as InterruptController>::is_interrupting());
+        crate::csr::mintstatus::set(0);
+    }
+
+    #[test]
+    fn check_global_csrs() {
+        type Clic = super::CLIC<Clic8>;
+
+        Clic::disable();
+        assert!(!Clic::is_enabled());
+        Clic::enable();
+        assert!(Clic::is_enabled());
+        assert!(crate::csr::mstatus::mie());
+        Clic::disable();
+        assert!(!Clic::is_enabled());
+        assert!(!crate::csr::mstatus::mie());
+
+        Clic::set_threshold(0x7F);
+        assert_eq!(Clic::get_threshold(), 0x7F);
+        assert_eq!(crate::csr::mintthresh::read(), 0x7F);
+        Clic::set_threshold(0);
+        assert_eq!(Clic::get_threshold(), 0);
+    }
+
+    #[test]
+    fn check_source_and_global() {
+        let mut raw_reg = [0u32; 32];
+        // SAFETY: valid memory address
+        let interrupts =
+            unsafe { super::interrupt::INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+        crate::csr::mstatus::clear_mie();
+
+        let state = unsafe { super::enable_source_and_global(interrupts, Interrupt::I2) };
+        assert_eq!(
+            state,
+            super::EnableState {
+                source: false,
+                global: false
+            }
+        );
+        assert!(interrupts.is_enabled(Interrupt::I2));
+        assert!(crate::csr::mstatus::mie());
+
+        let disabled = super::disable_source_and_global(interrupts, Interrupt::I2);
+        assert_eq!(
+            disabled,
+            super::EnableState {
+                source: true,
+                global: true
+            }
+        );
+        assert!(!interrupts.is_enabled(Interrupt::I2));
+        assert!(!crate::csr::mstatus::mie());
+
+        unsafe { super::restore_source_and_global(interrupts, Interrupt::I2, disabled) };
+        assert!(interrupts.is_enabled(Interrupt::I2));
+        assert!(crate::csr::mstatus::mie());
+
+        unsafe { super::restore_source_and_global(interrupts, Interrupt::I2, state) };
+        assert!(!interrupts.is_enabled(Interrupt::I2));
+        assert!(!crate::csr::mstatus::mie());
+    }
+
+    #[test]
+    fn check_with_threshold() {
+        type Clic = super::CLIC<Clic8>;
+
+        Clic::set_threshold(0x3F);
+        let res = Clic::with_threshold(0xBF, || {
+            assert_eq!(Clic::get_threshold(), 0xBF);
+            42
+        });
+        assert_eq!(res, 42);
+        assert_eq!(Clic::get_threshold(), 0x3F);
+
+        // the threshold must be restored even if the closure panics
+        let res =
+            std::panic::catch_unwind(|| Clic::with_threshold(0xFF, || panic!("closure panicked")));
+        assert!(res.is_err());
+        assert_eq!(Clic::get_threshold(), 0x3F);
+    }
+
+    #[test]
+    fn check_critical() {
+        type Clic = super::CLIC<Clic8>;
+
+        unsafe { crate::csr::mstatus::set_mie() };
+        Clic::set_threshold(0x3F);
+        let state = Clic::enter_critical();
+        assert_eq!(
+            state,
+            super::CriticalState {
+                threshold: 0x3F,
+                mie: true
+            }
+        );
+        assert!(!crate::csr::mstatus::mie());
+        Clic::set_threshold(0xFF);
+        unsafe { Clic::exit_critical(state) };
+        assert_eq!(Clic::get_threshold(), 0x3F);
+        assert!(crate::csr::mstatus::mie());
+
+        // nested critical sections keep interrupts disabled until the outermost one exits
+        let res = Clic::with_critical(|| {
+            Clic::set_threshold(0x7F);
+            Clic::with_critical(|| assert!(!crate::csr::mstatus::mie()));
+            assert!(!crate::csr::mstatus::mie());
+            assert_eq!(Clic::get_threshold(), 0x7F);
+            42
+        });
+        assert_eq!(res, 42);
+        assert_eq!(Clic::get_threshold(), 0x3F);
+        assert!(crate::csr::mstatus::mie());
+
+        // the state must be restored even if the closure panics
+        let res = std::panic::catch_unwind(|| {
+            Clic::with_critical(|| {
+                Clic::set_threshold(0xFF);
+                panic!("closure panicked")
+            })
+        });
+        assert!(res.is_err());
+        assert_eq!(Clic::get_threshold(), 0x3F);
+        assert!(crate::csr::mstatus::mie());
+    }
+
+    #[test]
+    fn check_swap_threshold() {
+        type Clic = super::CLIC<Clic8>;
+
+        Clic::set_threshold(0x3F);
+        assert_eq!(Clic::swap_threshold(0xBF), 0x3F);
+        assert_eq!(Clic::get_threshold(), 0xBF);
+        assert_eq!(Clic::swap_threshold(0x3F), 0xBF);
+        assert_eq!(Clic::get_threshold(), 0x3F);
+    }
+
+    #[test]
+    fn check_level_encoding() {
+        use super::{encode_threshold, next_level_up};
+
+        // 2 level bits: levels 0x3F, 0x7F, 0xBF, 0xFF
+        assert_eq!(next_level_up(0x00, 2), Some(0x3F));
+        assert_eq!(next_level_up(0x3F, 2), Some(0x7F));
+        assert_eq!(next_level_up(0x40, 2), Some(0x7F));
+        assert_eq!(next_level_up(0xBF, 2), Some(0xFF));
+        assert_eq!(next_level_up(0xFF, 2), None);
+        assert_eq!(encode_threshold(0, 0, 2), 0x00);
+        assert_eq!(encode_threshold(1, 3, 2), 0b0100_0011);
+        assert_eq!(encode_threshold(3, 0x3F, 2), 0xFF);
+        assert_eq!(encode_threshold(4, 0x40, 2), 0x00); // out of range bits are discarded
+
+        // 4 level bits: levels 0x0F, 0x1F, ..., 0xFF
+        assert_eq!(next_level_up(0x0F, 4), Some(0x1F));
+        assert_eq!(next_level_up(0x10, 4), Some(0x1F));
+        assert_eq!(next_level_up(0xEF, 4), Some(0xFF));
+        assert_eq!(next_level_up(0xFF, 4), None);
+        assert_eq!(encode_threshold(0xA, 0x5, 4), 0xA5);
+
+        // 8 level bits: every value is a level
+        assert_eq!(next_level_up(0x00, 8), Some(0x01));
+        assert_eq!(next_level_up(0x7F, 8), Some(0x80));
+        assert_eq!(next_level_up(0xFF, 8), None);
+        assert_eq!(encode_threshold(0x80, 0xFF, 8), 0x80);
+        assert_eq!(next_level_up(0x10, 9), Some(0x11));
+
+        // no level bits: all the interrupts have level 0xFF
+        assert_eq!(next_level_up(0x00, 0), Some(0xFF));
+        assert_eq!(next_level_up(0xFF, 0), None);
+        assert_eq!(encode_threshold(1, 0x12, 0), 0x12);
+    }
+
+    #[test]
+    fn check_interrupt_status() {
+        type Clic = super::CLIC<Clic8>;
+
+        crate::csr::mintstatus::set(0xBF00_7F01);
+        let status = Clic::interrupt_status();
+        assert_eq!(status.mil, 0xBF);
+        assert_eq!(status.sil, 0x7F);
+        assert_eq!(status.uil, 0x01);
+        assert!(Clic::is_interrupting());
+
+        crate::csr::mintstatus::set(0);
+        assert_eq!(Clic::interrupt_status(), super::InterruptStatus::default());
+        assert!(!Clic::is_interrupting());
+    }
+
+    #[test]
+    fn check_vectored() {
+        // nvbits = 0, nlbits = 4
+        let mut raw_cliccfg = 0x08u8;
+        // SAFETY: valid memory address
+        let cliccfg = unsafe { Reg::<u8, RW>::new(&mut raw_cliccfg) };
+
+        assert!(!super::is_vectored(cliccfg));
+        super::set_vectored(cliccfg, true);
+        assert!(super::is_vectored(cliccfg));
+        assert_eq!(cliccfg.read(), 0x09);
+        super::set_vectored(cliccfg, false);
+        assert!(!super::is_vectored(cliccfg));
+        assert_eq!(cliccfg.read(), 0x08);
+    }
+
+    #[test]
+    fn check_next_interrupt_handler() {
+        type Clic = super::CLIC<Clic8>;
+        use crate::csr::{mnxti, mstatus};
+
+        // no pending interrupt: interrupts are left disabled
+        mnxti::set_next(None);
+        assert!(unsafe { Clic::next_interrupt_handler() }.is_none());
+        assert!(!mstatus::mie());
+
+        // pending interrupt: it is claimed with interrupts enabled until the token is dropped
+        mnxti::set_next(Some(Interrupt::I3 as u16));
+        let (number, token) = unsafe { Clic::next_interrupt_handler() }.unwrap();
+        assert_eq!(number, 3);
+        assert_eq!(
+            token.entry(),
+            mnxti::MTVT + 3 * core::mem::size_of::<usize>()
+        );
+        assert!(mstatus::mie());
+        token.complete();
+        assert!(!mstatus::mie());
+
+        // dispatch loop
+        let mut dispatched = 0;
+        mnxti::set_next(Some(Interrupt::I1 as u16));
+        while let Some((number, token)) = unsafe { Clic::next_interrupt_handler() } {
+            assert_eq!(number, 1);
+            dispatched += 1;
+            drop(token);
+            assert!(!mstatus::mie());
+        }
+        assert_eq!(dispatched, 1);
+    }
+
+    #[test]
+    fn check_raise_threshold_to() {
+        type Clic = super::CLIC<Clic8>;
+
+        Clic::set_threshold(1);
+        {
+            let guard = Clic::raise_threshold_to(Priority::P3);
+            assert_eq!(guard.previous(), 1);
+            assert_eq!(Clic::get_threshold(), 3);
+            {
+                // a lower level does not lower the threshold
+                let _inner = Clic::raise_threshold_to(Priority::P2);
+                assert_eq!(Clic::get_threshold(), 3);
+            }
+            assert_eq!(Clic::get_threshold(), 3);
+        }
+        assert_eq!(Clic::get_threshold(), 1);
+    }
+
+    #[test]
+    fn check_ceiling_lock() {
+        use super::CeilingLock;
+
+        static LOW: CeilingLock<0x3F> = CeilingLock::new();
+        static HIGH: CeilingLock<0xBF> = CeilingLock::new();
+
+        crate::csr::mintthresh::write(0);
+        {
+            let outer = LOW.lock();
+            assert_eq!(outer.previous(), 0);
+            assert_eq!(crate::csr::mintthresh::read(), 0x3F);
+            {
+                let inner = HIGH.lock();
+                assert_eq!(inner.previous(), 0x3F);
+                assert_eq!(crate::csr::mintthresh::read(), 0xBF);
+                {
+                    // a lower ceiling does not lower the threshold
+                    let _innermost = LOW.lock();
+                    assert_eq!(crate::csr::mintthresh::read(), 0xBF);
+                }
+                assert_eq!(crate::csr::mintthresh::read(), 0xBF);
+            }
+            assert_eq!(crate::csr::mintthresh::read(), 0x3F);
+        }
+        assert_eq!(crate::csr::mintthresh::read(), 0);
+        assert_eq!(HIGH.ceiling(), 0xBF);
     }
 }
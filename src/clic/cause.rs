@@ -0,0 +1,123 @@
+//! CLIC-aware decoding of the `mcause` register.
+//!
+//! In CLIC mode, `mcause` has the following layout (`XLEN` is the width of the register):
+//!
+//! | Bits         | Field       | Description                                        |
+//! |--------------|-------------|----------------------------------------------------|
+//! | `XLEN - 1`   | `interrupt` | `1` if the trap was caused by an interrupt         |
+//! | `30`         | `minhv`     | Hardware vectoring in progress                     |
+//! | `29:28`      | `mpp`       | Previous privilege mode                            |
+//! | `27`         | `mpie`      | Previous interrupt enable                          |
+//! | `23:16`      | `mpil`      | Previous interrupt level                           |
+//! | `11:0`       | `exccode`   | Exception code (the interrupt ID for interrupts)   |
+//!
+//! # Vector fetch faults
+//!
+//! With selective hardware vectoring, the HART sets `minhv` while it fetches the entry of the
+//! vector table, and clears it once the fetch succeeds. If the fetch faults, the HART takes an
+//! exception with `minhv` still set and `mepc` pointing to the faulting entry of the vector table
+//! (not to the interrupted code). Use [`is_vector_fetch_fault`] to detect this condition in the
+//! exception handler. After fixing the cause of the fault (e.g., mapping the vector table),
+//! executing `mret` with `minhv` set fetches the entry again and resumes the vectoring.
+
+/// Decoded value of the `mcause` register in CLIC mode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Cause {
+    /// `true` if the trap was caused by an interrupt.
+    pub is_interrupt: bool,
+    /// Exception code (`exccode` field). For interrupts, it is the interrupt ID.
+    pub exception_code: u16,
+    /// Interrupt level of the interrupted code (`mpil` field).
+    pub previous_interrupt_level: u8,
+    /// `true` if the HART was fetching an entry of the vector table (`minhv` field).
+    pub vectoring_in_progress: bool,
+    /// ID of the CLIC interrupt that caused the trap, or [`None`] for exceptions.
+    pub interrupt_id: Option<u16>,
+}
+
+impl Cause {
+    /// Bit of the `interrupt` field.
+    const INTERRUPT: usize = 1 << (usize::BITS - 1);
+    /// Bit of the `minhv` field.
+    const MINHV: usize = 1 << 30;
+    /// Offset of the `mpil` field.
+    const MPIL_OFFSET: usize = 16;
+    /// Mask of the `exccode` field.
+    const EXCCODE_MASK: usize = 0xFFF;
+
+    /// Decodes a raw `mcause` value using the CLIC field layout.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        let is_interrupt = bits & Self::INTERRUPT != 0;
+        let exception_code = (bits & Self::EXCCODE_MASK) as u16;
+        Self {
+            is_interrupt,
+            exception_code,
+            previous_interrupt_level: (bits >> Self::MPIL_OFFSET) as u8,
+            vectoring_in_progress: bits & Self::MINHV != 0,
+            interrupt_id: match is_interrupt {
+                true => Some(exception_code),
+                false => None,
+            },
+        }
+    }
+
+    /// Returns `true` if the trap is an exception raised while fetching an entry of the vector table.
+    ///
+    /// See the [module-level documentation](self) for details.
+    #[inline]
+    pub const fn is_vector_fetch_fault(self) -> bool {
+        !self.is_interrupt && self.vectoring_in_progress
+    }
+}
+
+/// Returns `true` if a raw `mcause` value corresponds to an exception raised while fetching
+/// an entry of the vector table.
+///
+/// See the [module-level documentation](self) for details.
+#[inline]
+pub const fn is_vector_fetch_fault(mcause: usize) -> bool {
+    Cause::from_bits(mcause).is_vector_fetch_fault()
+}
+
+/// Reads and decodes the `mcause` register of the current HART.
+#[inline]
+pub fn read() -> Cause {
+    Cause::from_bits(crate::csr::mcause::read())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cause() {
+        // interrupt 37 with minhv = 1, mpp = 3, mpie = 1 and mpil = 0x7F
+        let bits = Cause::INTERRUPT | 1 << 30 | 3 << 28 | 1 << 27 | 0x7F << 16 | 37;
+        let cause = Cause::from_bits(bits);
+        assert!(cause.is_interrupt);
+        assert_eq!(cause.exception_code, 37);
+        assert_eq!(cause.previous_interrupt_level, 0x7F);
+        assert_eq!(cause.interrupt_id, Some(37));
+        assert!(cause.vectoring_in_progress);
+        assert!(!cause.is_vector_fetch_fault());
+
+        // illegal instruction exception at level 0xFF
+        let cause = Cause::from_bits(0xFF << 16 | 2);
+        assert!(!cause.is_interrupt);
+        assert_eq!(cause.exception_code, 2);
+        assert_eq!(cause.previous_interrupt_level, 0xFF);
+        assert_eq!(cause.interrupt_id, None);
+        assert!(!cause.vectoring_in_progress);
+        assert!(!is_vector_fetch_fault(0xFF << 16 | 2));
+
+        // instruction access fault while fetching a vector table entry
+        let cause = Cause::from_bits(Cause::MINHV | 1);
+        assert!(cause.is_vector_fetch_fault());
+        assert!(is_vector_fetch_fault(Cause::MINHV | 1));
+
+        // highest interrupt ID
+        crate::csr::mcause::set(Cause::INTERRUPT | 4095);
+        assert_eq!(read().interrupt_id, Some(4095));
+    }
+}
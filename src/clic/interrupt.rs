@@ -1,20 +1,329 @@
 //! Interrupt register control for a CLIC
 
 use crate::{
-    clic::{InterruptNumber, PriorityNumber}, //this interruptnumber should maybe be a general thing...
-    common::{Reg, RW},
+    clic::{Clic, InterruptNumber, PriorityNumber}, //this interruptnumber should maybe be a general thing...
+    common::{Reg, UnmaskToken, RW},
+    error::InvalidInterruptNumber,
 };
+
+/// Error returned by [`INTERRUPTS::try_set_priority`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SetPriorityError {
+    /// The interrupt source number exceeds `MAX_INTERRUPT_NUMBER`.
+    InvalidSource(u16),
+    /// The priority level uses `clicintctl` bits that are not implemented by the CLIC.
+    UnsupportedPriority(u8),
+    /// The CLIC does not implement the `clicintctl` registers.
+    UnimplementedCtl,
+}
+
+/// Error returned by [`INTERRUPTS::set_mode_range`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SetModeError {
+    /// The interrupt source number exceeds `MAX_INTERRUPT_NUMBER`.
+    InvalidSource(u16),
+    /// The CLIC does not implement the `clicintattr` registers.
+    UnimplementedAttr,
+}
+
+/// Error returned when accessing a register that is not implemented by the CLIC.
+/// See the field-presence model of [`Clic`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct UnimplementedField(pub IrqField);
+
+/// Polarity of an interrupt input line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Polarity {
+    /// Active-high level or rising edge.
+    Positive,
+    /// Active-low level or falling edge.
+    Negative,
+}
+
+/// Edge-triggered interrupt modes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Edge {
+    /// Triggered on rising edges.
+    Rising,
+    /// Triggered on falling edges.
+    Falling,
+}
+
+/// Level-triggered interrupt modes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Level {
+    /// Triggered while the input line is high.
+    High,
+    /// Triggered while the input line is low.
+    Low,
+}
+
+/// Trigger configuration of an interrupt source.
+///
+/// It is encoded in the `trig` field (bits 2:1) of the `clicintattr` register:
+///
+/// | `trig` | Trigger                        |
+/// |--------|--------------------------------|
+/// | `0b00` | [`Level::High`] (positive level) |
+/// | `0b01` | [`Edge::Rising`] (positive edge) |
+/// | `0b10` | [`Level::Low`] (negative level)  |
+/// | `0b11` | [`Edge::Falling`] (negative edge) |
+///
+/// Bit 0 of `trig` selects edge (1) or level (0) triggering, and bit 1 selects the polarity.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trigger {
+    /// Level-triggered interrupt.
+    Level(Level),
+    /// Edge-triggered interrupt.
+    Edge(Edge),
+}
+
+impl Trigger {
+    /// Mask of the `trig` field in the `clicintattr` register.
+    const MASK: u8 = 0b110;
+
+    /// Returns the polarity of the trigger.
+    #[inline]
+    pub const fn polarity(self) -> Polarity {
+        match self {
+            Self::Level(Level::High) | Self::Edge(Edge::Rising) => Polarity::Positive,
+            Self::Level(Level::Low) | Self::Edge(Edge::Falling) => Polarity::Negative,
+        }
+    }
+
+    /// Returns `true` if the trigger is edge-sensitive.
+    #[inline]
+    pub const fn is_edge(self) -> bool {
+        matches!(self, Self::Edge(_))
+    }
+
+    /// Returns the value of the `trig` field (i.e., bits 2:1 of `clicintattr`).
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        let polarity = match self.polarity() {
+            Polarity::Positive => 0,
+            Polarity::Negative => 1,
+        };
+        (polarity << 1) | self.is_edge() as u8
+    }
+
+    /// Creates a trigger configuration from the value of the `trig` field.
+    /// Only the two least significant bits of `bits` are considered.
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Self::Level(Level::High),
+            0b01 => Self::Edge(Edge::Rising),
+            0b10 => Self::Level(Level::Low),
+            _ => Self::Edge(Edge::Falling),
+        }
+    }
+}
+
+impl From<Edge> for Trigger {
+    #[inline]
+    fn from(edge: Edge) -> Self {
+        Self::Edge(edge)
+    }
+}
+
+impl From<Level> for Trigger {
+    #[inline]
+    fn from(level: Level) -> Self {
+        Self::Level(level)
+    }
+}
+
+/// Privilege mode of an interrupt source.
+///
+/// It is encoded in the `mode` field (bits 7:6) of the `clicintattr` register.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mode {
+    /// User mode (`0b00`).
+    User = 0b00,
+    /// Supervisor mode (`0b01`).
+    Supervisor = 0b01,
+    /// Machine mode (`0b11`).
+    Machine = 0b11,
+}
+
+impl Mode {
+    /// Mask of the `mode` field in the `clicintattr` register.
+    const MASK: u8 = 0b1100_0000;
+
+    /// Decodes the privilege mode from the `mode` field, or returns [`None`] if it is reserved.
+    #[inline]
+    const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b00 => Some(Self::User),
+            0b01 => Some(Self::Supervisor),
+            0b11 => Some(Self::Machine),
+            _ => None,
+        }
+    }
+}
+
+/// Byte-wide field of the 32-bit configuration word of a CLIC interrupt source.
+///
+/// Each interrupt source `i` owns the word at `BASE + 0x1000 + 4 * i`, laid out as follows:
+///
+/// | Field     | Register      | Byte offset |
+/// |-----------|---------------|-------------|
+/// | `Pending` | `clicintip`   | `+0`        |
+/// | `Enable`  | `clicintie`   | `+1`        |
+/// | `Attr`    | `clicintattr` | `+2`        |
+/// | `Ctl`     | `clicintctl`  | `+3`        |
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IrqField {
+    /// Interrupt pending register (`clicintip`).
+    Pending = 0,
+    /// Interrupt enable register (`clicintie`).
+    Enable = 1,
+    /// Interrupt attribute register (`clicintattr`).
+    Attr = 2,
+    /// Interrupt control register (`clicintctl`).
+    Ctl = 3,
+}
+
+impl IrqField {
+    /// Returns the byte offset of the field within the configuration word of an interrupt source.
+    #[inline]
+    pub const fn offset(self) -> usize {
+        self as usize
+    }
+}
+
+/// Configuration of a CLIC interrupt source, to be written with [`INTERRUPTS::configure`].
+///
+/// The default configuration is a disabled, non-vectored, level-triggered (active-high)
+/// machine mode interrupt with the maximum priority.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct IrqConfig {
+    /// Raw `clicintctl` value (i.e., the interrupt level and priority).
+    pub priority: u8,
+    /// Trigger configuration.
+    pub trigger: Trigger,
+    /// Privilege mode.
+    pub mode: Mode,
+    /// Selective hardware vectoring.
+    pub vectored: bool,
+    /// Interrupt enable.
+    pub enabled: bool,
+}
+
+impl Default for IrqConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IrqConfig {
+    /// Creates the default interrupt configuration.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            priority: u8::MAX,
+            trigger: Trigger::Level(Level::High),
+            mode: Mode::Machine,
+            vectored: false,
+            enabled: false,
+        }
+    }
+
+    /// Sets the priority of the interrupt source.
+    #[inline]
+    pub fn priority<P: PriorityNumber>(mut self, priority: P) -> Self {
+        self.priority = priority.number();
+        self
+    }
+
+    /// Sets the trigger configuration of the interrupt source.
+    #[inline]
+    pub fn trigger<T: Into<Trigger>>(mut self, trigger: T) -> Self {
+        self.trigger = trigger.into();
+        self
+    }
+
+    /// Sets the privilege mode of the interrupt source.
+    #[inline]
+    pub const fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Enables or disables selective hardware vectoring for the interrupt source.
+    #[inline]
+    pub const fn vectored(mut self, vectored: bool) -> Self {
+        self.vectored = vectored;
+        self
+    }
+
+    /// Enables or disables the interrupt source.
+    #[inline]
+    pub const fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Returns the 32-bit register word of the configuration.
+    /// The pending bit of the word is always cleared.
+    #[inline]
+    pub const fn word(self) -> u32 {
+        let attr = ((self.mode as u8) << 6) | (self.trigger.bits() << 1) | self.vectored as u8;
+        ((self.priority as u32) << 24) | ((attr as u32) << 16) | ((self.enabled as u32) << 8)
+    }
+}
+
 /// In a CLIC, all properties of an interrupt are controlled via a single
 /// word-wide register block.
+///
+/// # Note
+///
+/// The CLIC is a HART-local interrupt controller, and the same base address may map to
+/// the registers of a different CLIC instance in each HART. Thus, this proxy is neither
+/// [`Send`] nor [`Sync`]. Use [`crate::clic::CLIC::interrupts`] in each HART instead:
+///
+/// ```compile_fail
+/// # use riscv_peripheral::clic::{interrupt::INTERRUPTS, Clic};
+/// # #[derive(Clone, Copy)]
+/// # struct MyClic;
+/// # unsafe impl Clic for MyClic { const BASE: usize = 0x1000_0000; }
+/// fn is_send<T: Send>() {}
+///
+/// is_send::<INTERRUPTS<MyClic>>(); // fails to compile
+/// ```
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct INTERRUPTS {
+pub struct INTERRUPTS<C: Clic> {
     ptr: *mut u32,
+    _marker: core::marker::PhantomData<C>,
 }
 
-impl INTERRUPTS {
+impl<C: Clic> INTERRUPTS<C> {
+    /// Mask of the `clicintctl` bits that are not implemented by the CLIC.
+    /// If the CLIC does not implement `clicintctl`, no bit is implemented.
+    const UNIMPLEMENTED_CTL_MASK: u8 = match C::HAS_CTL {
+        true => (0xFF_u16 >> C::CLICINTCTLBITS) as u8,
+        false => 0xFF,
+    };
+
+    /// Creates a new interrupts configuration register proxy from a base address.
+    ///
+    /// # Safety
+    ///
+    /// The base address must point to a valid CLIC interrupt configuration block
+    /// (i.e., the `clicintip` register of interrupt 0) of a CLIC described by `C`.
     #[inline]
-    pub(crate) const unsafe fn new(address: usize) -> Self {
-        Self { ptr: address as _ }
+    pub const unsafe fn new(address: usize) -> Self {
+        Self {
+            ptr: address as _,
+            _marker: core::marker::PhantomData,
+        }
     }
 
     #[cfg(test)]
@@ -23,14 +332,57 @@ impl INTERRUPTS {
         self.ptr as _
     }
 
+    /// Returns the 32-bit configuration word of the interrupt source number `source`.
+    #[inline(always)]
+    fn word_reg(self, source: usize) -> Reg<u32, RW> {
+        // SAFETY: valid interrupt number
+        unsafe { Reg::new(self.ptr.add(source)) }
+    }
+
+    /// Returns the byte-wide register `field` of the interrupt source number `source`.
+    ///
+    /// The address is computed with byte-pointer arithmetic, so it is valid for any pointer width.
+    /// The CLIC specification fixes the byte offset of each field within the configuration word
+    /// (i.e., the little-endian byte order of RISC-V), as documented in [`IrqField`].
+    #[inline(always)]
+    fn byte_reg(self, source: usize, field: IrqField) -> Reg<u8, RW> {
+        // SAFETY: valid interrupt number and field offset within the configuration word
+        unsafe { Reg::new((self.ptr as *mut u8).add(4 * source + field.offset())) }
+    }
+
+    /// Returns `true` if the CLIC implements the register `field` of the interrupt sources.
+    /// `clicintip` and `clicintie` are always implemented. See the field-presence model of [`Clic`].
+    #[inline]
+    pub const fn implements(field: IrqField) -> bool {
+        match field {
+            IrqField::Pending | IrqField::Enable => true,
+            IrqField::Attr => C::HAS_ATTR,
+            IrqField::Ctl => C::HAS_CTL,
+        }
+    }
+
+    /// Returns an [`UnimplementedField`] error if the CLIC does not implement the register `field`.
+    #[inline]
+    const fn check_field(field: IrqField) -> Result<(), UnimplementedField> {
+        match Self::implements(field) {
+            true => Ok(()),
+            false => Err(UnimplementedField(field)),
+        }
+    }
+
+    /// Returns the absolute address of the register `field` of an interrupt source.
+    ///
+    /// This is useful for passing the address of a register to other bus masters
+    /// (e.g., DMA or trace engines) or for debugging. See [`IrqField`] for the layout.
+    #[inline]
+    pub fn register_address<I: InterruptNumber>(self, source: I, field: IrqField) -> usize {
+        self.byte_reg(source.number() as usize, field).get_ptr() as usize
+    }
+
     /// Checks if an interrupt source is enabled.
     #[inline]
     pub fn is_enabled<I: InterruptNumber>(self, source: I) -> bool {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-
-        let reg: Reg<u8, RW> =
-            unsafe { Reg::new((self.ptr.offset(offset) as usize + 1) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Enable);
         reg.read() == 1
     }
 
@@ -41,57 +393,461 @@ impl INTERRUPTS {
     /// * Enabling an interrupt source can break mask-based critical sections.
     #[inline]
     pub unsafe fn enable<I: InterruptNumber>(self, source: I) {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> =
-            unsafe { Reg::new((self.ptr.offset(offset) as usize + 1) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Enable);
         reg.write(1);
     }
 
+    /// Enables an interrupt source, checking that the interrupt source does not exceed
+    /// `I::MAX_INTERRUPT_NUMBER`.
+    ///
+    /// Instead of being `unsafe`, it requires an [`UnmaskToken`] to acknowledge that
+    /// enabling an interrupt source can break mask-based critical sections.
+    #[inline]
+    pub fn try_enable<I: InterruptNumber>(
+        self,
+        source: I,
+        _token: UnmaskToken,
+    ) -> Result<(), InvalidInterruptNumber> {
+        check_source(source)?;
+        // SAFETY: the token holder accepts the critical section hazard
+        unsafe { self.enable(source) };
+        Ok(())
+    }
+
     /// Disables an interrupts source.
+    #[inline]
     pub fn disable<I: InterruptNumber>(self, source: I) {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> =
-            unsafe { Reg::new((self.ptr.offset(offset) as usize + 1) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Enable);
         reg.write(0);
     }
 
     /// Returns the configured priority of an interrupt source
+    ///
+    /// # Note
+    ///
+    /// The CLIC only implements the `C::CLICINTCTLBITS` most significant bits of `clicintctl`,
+    /// and the remaining bits read as 1. These bits are masked off, so the returned value
+    /// only contains the implemented bits. If the CLIC does not implement `clicintctl`, it returns 0.
     #[inline]
     pub fn get_priority<I: InterruptNumber>(self, source: I) -> u8 {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> =
-            unsafe { Reg::new((self.ptr.offset(offset) as usize + 3) as *mut u8) };
-        reg.read()
+        if !C::HAS_CTL {
+            return 0;
+        }
+        let reg = self.byte_reg(source.number() as usize, IrqField::Ctl);
+        reg.read() & !Self::UNIMPLEMENTED_CTL_MASK
     }
-    /// Sets the priority of an interrupt source
+    /// Sets the priority of an interrupt source.
+    /// If the CLIC does not implement `clicintctl`, it does nothing.
     ///
     /// # Safety
     ///
     /// * Changing/setting the priority of an interrupt may break mask-based critical sections.
     #[inline]
     pub unsafe fn set_priority<I: InterruptNumber, P: PriorityNumber>(self, source: I, prio: P) {
-        let source = source.number() as usize;
-        let offset = (source) as _;
+        if !C::HAS_CTL {
+            return;
+        }
         let prio = prio.number();
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> =
-            unsafe { Reg::new((self.ptr.offset(offset) as usize + 3) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Ctl);
         reg.write(prio);
     }
 
+    /// Sets the priority of an interrupt source, checking that both the interrupt source
+    /// and the priority level are supported by the CLIC.
+    ///
+    /// Instead of being `unsafe`, it requires an [`UnmaskToken`] to acknowledge that
+    /// changing the priority of an interrupt may break mask-based critical sections.
+    ///
+    /// # Note
+    ///
+    /// The CLIC only implements the `C::CLICINTCTLBITS` most significant bits of `clicintctl`.
+    /// Priority levels using any other bit are rejected, as the hardware would silently ignore it.
+    #[inline]
+    pub fn try_set_priority<I: InterruptNumber, P: PriorityNumber>(
+        self,
+        source: I,
+        prio: P,
+        _token: UnmaskToken,
+    ) -> Result<(), SetPriorityError> {
+        if let Err(InvalidInterruptNumber(n)) = check_source(source) {
+            return Err(SetPriorityError::InvalidSource(n));
+        }
+        if Self::check_field(IrqField::Ctl).is_err() {
+            return Err(SetPriorityError::UnimplementedCtl);
+        }
+        if prio.number() & Self::UNIMPLEMENTED_CTL_MASK != 0 {
+            return Err(SetPriorityError::UnsupportedPriority(prio.number()));
+        }
+        // SAFETY: the token holder accepts the critical section hazard
+        unsafe { self.set_priority(source, prio) };
+        Ok(())
+    }
+
+    /// Reads the whole 32-bit register word of an interrupt source with a single access.
+    ///
+    /// The word contains, from the least to the most significant byte,
+    /// the `clicintip`, `clicintie`, `clicintattr`, and `clicintctl` registers.
+    #[inline]
+    pub fn read_raw<I: InterruptNumber>(self, source: I) -> u32 {
+        let reg = self.word_reg(source.number() as usize);
+        reg.read()
+    }
+
+    /// Writes the whole 32-bit register word of an interrupt source with a single access.
+    /// Check [`INTERRUPTS::read_raw`] for the layout of the word.
+    ///
+    /// # Safety
+    ///
+    /// * Writing the word may enable or pend the interrupt and break mask-based critical sections.
+    #[inline]
+    pub unsafe fn write_raw<I: InterruptNumber>(self, source: I, word: u32) {
+        let reg = self.word_reg(source.number() as usize);
+        reg.write(word);
+    }
+
+    /// Copies the register words of interrupt sources `0..=I::MAX_INTERRUPT_NUMBER` into `buf`,
+    /// e.g., before entering a low-power state that loses the CLIC state.
+    /// It returns the number of copied words.
+    ///
+    /// # Note
+    ///
+    /// `buf` should be at least `I::MAX_INTERRUPT_NUMBER + 1` words long to save the whole configuration.
+    /// If it is shorter, only the first `buf.len()` words are copied.
+    #[inline]
+    pub fn save_into<I: InterruptNumber>(self, buf: &mut [u32]) -> usize {
+        let n = buf.len().min(I::MAX_INTERRUPT_NUMBER as usize + 1);
+        for (i, word) in buf[..n].iter_mut().enumerate() {
+            let reg = self.word_reg(i);
+            *word = reg.read();
+        }
+        n
+    }
+
+    /// Restores the register words of interrupt sources `0..=I::MAX_INTERRUPT_NUMBER` from `buf`,
+    /// previously filled by [`INTERRUPTS::save_into`]. It returns the number of restored words.
+    ///
+    /// # Note
+    ///
+    /// If `buf` is shorter than `I::MAX_INTERRUPT_NUMBER + 1` words, only the first `buf.len()`
+    /// interrupt sources are restored. Words beyond `I::MAX_INTERRUPT_NUMBER` are ignored.
+    ///
+    /// # Safety
+    ///
+    /// * Restoring the configuration may enable or pend interrupts and break mask-based critical sections.
+    #[inline]
+    pub unsafe fn restore_from<I: InterruptNumber>(self, buf: &[u32]) -> usize {
+        let n = buf.len().min(I::MAX_INTERRUPT_NUMBER as usize + 1);
+        for (i, word) in buf[..n].iter().enumerate() {
+            let reg = self.word_reg(i);
+            reg.write(*word);
+        }
+        n
+    }
+
+    /// Configures an interrupt source with a single write of its 32-bit register word.
+    ///
+    /// # Note
+    ///
+    /// The pending bit of the interrupt source is cleared.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling an interrupt source or changing its configuration may break mask-based critical sections.
+    #[inline]
+    pub unsafe fn configure<I: InterruptNumber>(self, source: I, config: IrqConfig) {
+        self.write_raw(source, config.word());
+    }
+
+    /// Scans the interrupt sources and returns the pending and enabled source that wins the arbitration.
+    /// If no interrupt source is pending and enabled, it returns [`None`].
+    ///
+    /// # Note
+    ///
+    /// The arbitration follows the rules of the CLIC specification:
+    ///
+    /// 1. The source with the highest `clicintctl` value (i.e., the highest level and then the
+    ///    highest priority, as the level bits are the most significant ones) wins.
+    /// 2. Ties are broken in favor of the source with the **highest** interrupt number.
+    ///
+    /// The tie-break deliberately differs from the lowest-number rule of other interrupt
+    /// controllers (e.g., the PLIC): the CLIC specification selects the source with the highest
+    /// interrupt number, and this method must agree with the source the hardware would take.
+    ///
+    /// Unimplemented `clicintctl` bits are masked off before comparing sources, and privilege
+    /// modes are not taken into account. Sources rejected by `I::from_number` are skipped.
+    #[inline]
+    pub fn highest_pending<I: InterruptNumber>(self) -> Option<I> {
+        (0..=I::MAX_INTERRUPT_NUMBER)
+            .filter_map(I::try_from_number_opt)
+            .filter_map(|source| {
+                let word = self.read_raw(source);
+                let (ip, ie) = (word & 0x1 != 0, word & 0x100 != 0);
+                let ctl = (word >> 24) as u8 & !Self::UNIMPLEMENTED_CTL_MASK;
+                match ip && ie {
+                    true => Some((ctl, source.number(), source)),
+                    false => None,
+                }
+            })
+            .max_by_key(|(ctl, number, _)| (*ctl, *number))
+            .map(|(_, _, source)| source)
+    }
+
+    /// Writes `value` to the register `field` of the interrupt source number `source`
+    /// and returns the value read back. The original value is restored afterwards.
+    #[inline]
+    fn probe(self, source: usize, field: IrqField, value: u8) -> u8 {
+        let reg = self.byte_reg(source, field);
+        let prev = reg.read();
+        reg.write(value);
+        let res = reg.read();
+        reg.write(prev);
+        res
+    }
+
+    /// Probes the number of `clicintctl` bits implemented by the CLIC for an interrupt source.
+    ///
+    /// It writes all zeros to the `clicintctl` register of `source` and counts the bits that
+    /// read back as zero, as unimplemented bits are hardwired to one. The original value of
+    /// the register is restored afterwards.
+    ///
+    /// # Note
+    ///
+    /// This method is intended to be used during initialization, before interrupts are enabled.
+    /// While probing, the level and priority of `source` temporarily change. `source` must be
+    /// implemented by the CLIC (see [`INTERRUPTS::num_interrupts`]).
+    /// If the CLIC does not implement `clicintctl`, it returns 0 without probing.
+    #[inline]
+    pub fn implemented_ctl_bits<I: InterruptNumber>(self, source: I) -> u8 {
+        if !C::HAS_CTL {
+            return 0;
+        }
+        probe_ctl_bits(|value| self.probe(source.number() as usize, IrqField::Ctl, value))
+    }
+
+    /// Probes the number of interrupt sources implemented by the CLIC.
+    ///
+    /// It writes all ones to the `clicintctl` register of the interrupt sources in ascending order,
+    /// starting from 0, until one reads back as zero, as the registers of unimplemented sources
+    /// are hardwired to zero. The original value of each register is restored afterwards.
+    ///
+    /// # Note
+    ///
+    /// This method is intended to be used during initialization, before interrupts are enabled.
+    /// While probing, the level and priority of the sources temporarily change. It accesses
+    /// the registers of up to [`MAX_INTERRUPT_NUMBER`](crate::clic::MAX_INTERRUPT_NUMBER) + 1
+    /// sources, so the whole interrupt configuration block must be accessible.
+    /// If the CLIC does not implement `clicintctl`, the `clicintie` registers are probed instead,
+    /// temporarily enabling the sources.
+    #[inline]
+    pub fn num_interrupts(self) -> u16 {
+        let field = match C::HAS_CTL {
+            true => IrqField::Ctl,
+            false => IrqField::Enable,
+        };
+        probe_num_interrupts(|source| self.probe(source, field, 0xFF))
+    }
+
+    /// Disables all the interrupt sources from 0 to `I::MAX_INTERRUPT_NUMBER`.
+    #[inline]
+    pub fn disable_all<I: InterruptNumber>(self) {
+        for source in 0..=I::MAX_INTERRUPT_NUMBER as usize {
+            let reg = self.byte_reg(source, IrqField::Enable);
+            reg.write(0);
+        }
+    }
+
+    /// Enables all the interrupt sources from 0 to `I::MAX_INTERRUPT_NUMBER`, e.g., for self-tests.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling interrupt sources can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn enable_all<I: InterruptNumber>(self) {
+        for source in 0..=I::MAX_INTERRUPT_NUMBER as usize {
+            let reg = self.byte_reg(source, IrqField::Enable);
+            reg.write(1);
+        }
+    }
+
+    /// Clears the pending status of all the interrupt sources from 0 to `I::MAX_INTERRUPT_NUMBER`.
+    ///
+    /// # Safety
+    ///
+    /// * Unpending interrupts is side-effectful
+    #[inline]
+    pub unsafe fn clear_all_pending<I: InterruptNumber>(self) {
+        for source in 0..=I::MAX_INTERRUPT_NUMBER as usize {
+            let reg = self.byte_reg(source, IrqField::Pending);
+            reg.write(0);
+        }
+    }
+
+    /// Enables and pends an interrupt source, e.g., to trigger it by software.
+    ///
+    /// # Note
+    ///
+    /// The interrupt enable byte is written before the interrupt pending byte.
+    /// Both writes are volatile, so they are not reordered by the compiler.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling and pending interrupts may break mask-based critical sections.
+    #[inline]
+    pub unsafe fn trigger<I: InterruptNumber>(self, source: I) {
+        self.enable(source);
+        self.pend(source);
+    }
+
+    /// Sets the priority of an interrupt source and then enables it.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling an interrupt source can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn arm<I: InterruptNumber, P: PriorityNumber>(self, source: I, prio: P) {
+        self.set_priority(source, prio);
+        self.enable(source);
+    }
+
+    /// Disables an interrupt source and then clears its pending status.
+    #[inline]
+    pub fn disarm<I: InterruptNumber>(self, source: I) {
+        self.disable(source);
+        // SAFETY: the interrupt source is already disabled
+        unsafe { self.unpend(source) };
+    }
+
+    /// Returns the trigger configuration of an interrupt source.
+    /// If the CLIC does not implement `clicintattr`, it returns [`Level::High`].
+    #[inline]
+    pub fn get_trigger<I: InterruptNumber>(self, source: I) -> Trigger {
+        if !C::HAS_ATTR {
+            return Trigger::Level(Level::High);
+        }
+        let reg = self.byte_reg(source.number() as usize, IrqField::Attr);
+        Trigger::from_bits((reg.read() & Trigger::MASK) >> 1)
+    }
+
+    /// Sets the trigger configuration of an interrupt source.
+    /// The remaining fields of the `clicintattr` register are left unchanged.
+    /// `trigger` can be an [`Edge`] (e.g., [`Edge::Falling`]), a [`Level`] (e.g., [`Level::Low`]), or a [`Trigger`].
+    /// If the CLIC does not implement `clicintattr`, it does nothing.
+    ///
+    /// # Safety
+    ///
+    /// * Changing the trigger configuration may pend the interrupt and break mask-based critical sections.
+    #[inline]
+    pub unsafe fn set_trigger<I: InterruptNumber, T: Into<Trigger>>(self, source: I, trigger: T) {
+        if !C::HAS_ATTR {
+            return;
+        }
+        let reg = self.byte_reg(source.number() as usize, IrqField::Attr);
+        let bits = trigger.into().bits() << 1;
+        reg.modify(|attr| *attr = (*attr & !Trigger::MASK) | bits);
+    }
+
+    /// Sets the trigger configuration of an interrupt source.
+    /// Unlike [`INTERRUPTS::set_trigger`], it returns an error if the CLIC does not implement `clicintattr`.
+    ///
+    /// # Safety
+    ///
+    /// * Changing the trigger configuration may pend the interrupt and break mask-based critical sections.
+    #[inline]
+    pub unsafe fn try_set_trigger<I: InterruptNumber, T: Into<Trigger>>(
+        self,
+        source: I,
+        trigger: T,
+    ) -> Result<(), UnimplementedField> {
+        Self::check_field(IrqField::Attr)?;
+        self.set_trigger(source, trigger);
+        Ok(())
+    }
+
+    /// Returns the privilege mode of an interrupt source,
+    /// or [`None`] if the `mode` field holds the reserved value `0b10`.
+    /// If the CLIC does not implement `clicintattr`, it returns [`Mode::Machine`].
+    #[inline]
+    pub fn get_mode<I: InterruptNumber>(self, source: I) -> Option<Mode> {
+        if !C::HAS_ATTR {
+            return Some(Mode::Machine);
+        }
+        let reg = self.byte_reg(source.number() as usize, IrqField::Attr);
+        Mode::from_bits((reg.read() & Mode::MASK) >> 6)
+    }
+
+    /// Sets the privilege mode of an interrupt source.
+    /// The remaining fields of the `clicintattr` register are left unchanged.
+    ///
+    /// # Note
+    ///
+    /// The CLIC may not support all the privilege modes. Unsupported modes are
+    /// mapped by the hardware according to the `nmbits` field of `cliccfg`.
+    /// If the CLIC does not implement `clicintattr`, it does nothing.
+    ///
+    /// # Safety
+    ///
+    /// * Routing an interrupt source to another privilege mode may break critical sections of that mode.
+    #[inline]
+    pub unsafe fn set_mode<I: InterruptNumber>(self, source: I, mode: Mode) {
+        if !C::HAS_ATTR {
+            return;
+        }
+        let reg = self.byte_reg(source.number() as usize, IrqField::Attr);
+        let bits = (mode as u8) << 6;
+        reg.modify(|attr| *attr = (*attr & !Mode::MASK) | bits);
+    }
+
+    /// Sets the privilege mode of an interrupt source.
+    /// Unlike [`INTERRUPTS::set_mode`], it returns an error if the CLIC does not implement `clicintattr`.
+    ///
+    /// # Safety
+    ///
+    /// * Routing an interrupt source to another privilege mode may break critical sections of that mode.
+    #[inline]
+    pub unsafe fn try_set_mode<I: InterruptNumber>(
+        self,
+        source: I,
+        mode: Mode,
+    ) -> Result<(), UnimplementedField> {
+        Self::check_field(IrqField::Attr)?;
+        self.set_mode(source, mode);
+        Ok(())
+    }
+
+    /// Sets the privilege mode of several interrupt sources, e.g., to route a group of interrupt
+    /// sources to supervisor mode while the rest remain in machine mode.
+    ///
+    /// All the interrupt sources are checked against `I::MAX_INTERRUPT_NUMBER` before modifying
+    /// any register. If an interrupt source is invalid, it returns an error and no mode is changed.
+    /// If the CLIC does not implement `clicintattr`, it returns an error, as no mode can be applied.
+    /// See [`INTERRUPTS::set_mode`] for more details.
+    ///
+    /// # Safety
+    ///
+    /// * Routing an interrupt source to another privilege mode may break critical sections of that mode.
+    #[inline]
+    pub unsafe fn set_mode_range<I: InterruptNumber>(
+        self,
+        modes: &[(I, Mode)],
+    ) -> Result<(), SetModeError> {
+        if Self::check_field(IrqField::Attr).is_err() {
+            return Err(SetModeError::UnimplementedAttr);
+        }
+        for &(source, _) in modes {
+            if let Err(InvalidInterruptNumber(n)) = check_source(source) {
+                return Err(SetModeError::InvalidSource(n));
+            }
+        }
+        for &(source, mode) in modes {
+            self.set_mode(source, mode);
+        }
+        Ok(())
+    }
+
     /// Retuns the pending status of an interrupt
     #[inline]
     pub fn is_pending<I: InterruptNumber>(self, source: I) -> bool {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> = unsafe { Reg::new(self.ptr.offset(offset) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Pending);
         reg.read() == 1
     }
 
@@ -102,13 +858,27 @@ impl INTERRUPTS {
     /// * Pending interrupts may break mask-based critical sections.
     #[inline]
     pub unsafe fn pend<I: InterruptNumber>(self, source: I) {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> = unsafe { Reg::new(self.ptr.offset(offset) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Pending);
         reg.write(1);
     }
 
+    /// Sets an interrupt as pending, checking that the interrupt source does not exceed
+    /// `I::MAX_INTERRUPT_NUMBER`.
+    ///
+    /// Instead of being `unsafe`, it requires an [`UnmaskToken`] to acknowledge that
+    /// pending an interrupt may break mask-based critical sections.
+    #[inline]
+    pub fn try_pend<I: InterruptNumber>(
+        self,
+        source: I,
+        _token: UnmaskToken,
+    ) -> Result<(), InvalidInterruptNumber> {
+        check_source(source)?;
+        // SAFETY: the token holder accepts the critical section hazard
+        unsafe { self.pend(source) };
+        Ok(())
+    }
+
     /// Sets an interrupt as not pending
     ///
     /// # Safety
@@ -116,26 +886,142 @@ impl INTERRUPTS {
     /// * Unpending interrupts is side-effectful
     #[inline]
     pub unsafe fn unpend<I: InterruptNumber>(self, source: I) {
-        let source = source.number() as usize;
-        let offset = (source) as _;
-        // SAFETY: valid interrupt number
-        let reg: Reg<u8, RW> = unsafe { Reg::new(self.ptr.offset(offset) as *mut u8) };
+        let reg = self.byte_reg(source.number() as usize, IrqField::Pending);
         reg.write(0);
     }
+
+    /// Acknowledges an interrupt source: it reads its pending bit and, if set, clears it.
+    /// It returns `true` if the interrupt source was pending.
+    ///
+    /// # Note
+    ///
+    /// * For edge-triggered sources, the hardware already clears the pending bit when the interrupt
+    ///   is taken with selective hardware vectoring or claimed via `mnxti`. Use this method when
+    ///   the pending bit is not cleared automatically (e.g., when polling a source).
+    /// * For level-triggered sources, the pending bit follows the interrupt line, and the CLIC may
+    ///   ignore writes to it. Deassert the interrupt at the device before acknowledging it.
+    ///   Otherwise, the pending bit may be set again right after being cleared.
+    /// * The read and the clear are two separate accesses. An edge arriving in between is lost,
+    ///   as it is merged with the edge being acknowledged.
+    ///
+    /// # Safety
+    ///
+    /// * Unpending interrupts is side-effectful
+    #[inline]
+    pub unsafe fn acknowledge<I: InterruptNumber>(self, source: I) -> bool {
+        let reg = self.byte_reg(source.number() as usize, IrqField::Pending);
+        let pending = reg.read() == 1;
+        if pending {
+            reg.write(0);
+        }
+        pending
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<C: Clic> defmt::Format for INTERRUPTS<C> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "INTERRUPTS({=usize:#x})", self.ptr as usize)
+    }
+}
+
+/// Checks that an interrupt source does not exceed `I::MAX_INTERRUPT_NUMBER`.
+#[inline]
+fn check_source<I: InterruptNumber>(source: I) -> Result<(), InvalidInterruptNumber> {
+    match source.number() {
+        n if n > I::MAX_INTERRUPT_NUMBER => Err(InvalidInterruptNumber(n)),
+        _ => Ok(()),
+    }
+}
+
+/// Counts the implemented `clicintctl` bits from the value read back after writing `value` with `probe`.
+#[inline]
+fn probe_ctl_bits(mut probe: impl FnMut(u8) -> u8) -> u8 {
+    probe(0x00).leading_zeros() as u8
+}
+
+/// Counts the consecutive interrupt sources whose `clicintctl` register reads back as non-zero
+/// after writing all ones to it with `probe`.
+#[inline]
+fn probe_num_interrupts(mut probe: impl FnMut(usize) -> u8) -> u16 {
+    (0..=crate::clic::MAX_INTERRUPT_NUMBER)
+        .find(|&source| probe(source as usize) == 0)
+        .unwrap_or(crate::clic::MAX_INTERRUPT_NUMBER + 1)
 }
 
 #[cfg(test)]
 mod test {
     use crate::clic::test::Priority;
 
-    use super::super::test::Interrupt;
+    use super::super::test::{Clic4, Clic8, Interrupt};
     use super::*;
 
+    /// Interrupt source that reports a number beyond its `MAX_INTERRUPT_NUMBER`.
+    #[derive(Clone, Copy)]
+    struct Raw(u16);
+
+    unsafe impl InterruptNumber for Raw {
+        const MAX_INTERRUPT_NUMBER: u16 = 3;
+
+        fn number(self) -> u16 {
+            self.0
+        }
+
+        fn from_number(value: u16) -> Result<Self, u16> {
+            Ok(Self(value))
+        }
+    }
+
+    #[test]
+    fn test_probe() {
+        // mock CLIC with 4 `clicintctl` bits and 10 interrupt sources
+        const CTL_BITS: u8 = 4;
+        const NUM_INTERRUPTS: usize = 10;
+        let mut ctl = [0xFFu8; NUM_INTERRUPTS];
+        let mut probe = |source: usize, value: u8| match ctl.get_mut(source) {
+            Some(reg) => {
+                let prev = *reg;
+                *reg = value | (0xFF >> CTL_BITS); // unimplemented bits are hardwired to one
+                let res = *reg;
+                *reg = prev;
+                res
+            }
+            None => 0, // unimplemented sources are hardwired to zero
+        };
+
+        assert_eq!(probe_ctl_bits(|value| probe(3, value)), CTL_BITS);
+        assert_eq!(probe_num_interrupts(|source| probe(source, 0xFF)), 10);
+        assert_eq!(probe_ctl_bits(|_| 0xFF), 0);
+        assert_eq!(probe_num_interrupts(|_| 0xFF), 4096);
+
+        // on memory, all the bits are implemented and the original value is restored
+        let mut raw_reg = [0u32; 32];
+        raw_reg[2] = 0x7F00_0000;
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+        assert_eq!(interrupts.implemented_ctl_bits(Interrupt::I2), 8);
+        assert_eq!(raw_reg[2], 0x7F00_0000);
+    }
+
+    #[test]
+    fn test_acknowledge() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        unsafe { interrupts.enable(Interrupt::I2) };
+        unsafe { interrupts.pend(Interrupt::I2) };
+        assert!(unsafe { interrupts.acknowledge(Interrupt::I2) });
+        assert!(!interrupts.is_pending(Interrupt::I2));
+        // the rest of the configuration word is left untouched
+        assert!(interrupts.is_enabled(Interrupt::I2));
+        assert!(!unsafe { interrupts.acknowledge(Interrupt::I2) });
+        assert_eq!(raw_reg[2], 0x0000_0100);
+    }
+
     #[test]
     fn test_enable() {
         let mut raw_reg = [0u32; 32];
 
-        let interrupts = unsafe { INTERRUPTS::new(raw_reg.as_mut_ptr() as _) };
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
 
         unsafe { interrupts.enable(Interrupt::I1) };
         unsafe { interrupts.enable(Interrupt::I2) };
@@ -152,7 +1038,7 @@ mod test {
     #[test]
     fn test_priorities() {
         let mut raw_reg = [0u32; 32];
-        let interrupts = unsafe { INTERRUPTS::new(raw_reg.as_mut_ptr() as _) };
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
 
         unsafe { interrupts.set_priority(Interrupt::I1, Priority::P0) };
         unsafe { interrupts.set_priority(Interrupt::I2, Priority::P1) };
@@ -165,11 +1051,464 @@ mod test {
         assert_eq!(interrupts.get_priority(Interrupt::I4), 3);
     }
 
+    #[test]
+    fn test_try_set_priority() {
+        // SAFETY: no critical sections in the test
+        let token = unsafe { UnmaskToken::new() };
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+        assert_eq!(
+            interrupts.try_set_priority(Interrupt::I1, Priority::P3, token),
+            Ok(())
+        );
+        assert_eq!(interrupts.get_priority(Interrupt::I1), 3);
+
+        // only the 4 most significant bits of clicintctl are implemented
+        let interrupts = unsafe { INTERRUPTS::<Clic4>::new(raw_reg.as_mut_ptr() as _) };
+        assert_eq!(
+            interrupts.try_set_priority(Interrupt::I2, Priority::P3, token),
+            Err(SetPriorityError::UnsupportedPriority(3))
+        );
+        assert_eq!(
+            interrupts.try_set_priority(Interrupt::I2, Priority::P0, token),
+            Ok(())
+        );
+        assert_eq!(interrupts.get_priority(Interrupt::I2), 0);
+    }
+
+    #[test]
+    fn test_try_enable_pend() {
+        // SAFETY: no critical sections in the test
+        let token = unsafe { UnmaskToken::new() };
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        assert_eq!(interrupts.try_enable(Raw(0), token), Ok(()));
+        assert!(interrupts.is_enabled(Raw(0)));
+        assert_eq!(interrupts.try_pend(Raw(3), token), Ok(()));
+        assert!(interrupts.is_pending(Raw(3)));
+
+        assert_eq!(
+            interrupts.try_enable(Raw(4), token),
+            Err(InvalidInterruptNumber(4))
+        );
+        assert_eq!(
+            interrupts.try_pend(Raw(4), token),
+            Err(InvalidInterruptNumber(4))
+        );
+        assert_eq!(
+            interrupts.try_set_priority(Raw(4), Priority::P1, token),
+            Err(SetPriorityError::InvalidSource(4))
+        );
+        assert_eq!(raw_reg[4], 0);
+    }
+
+    #[test]
+    fn test_interrupt_zero() {
+        /// Local interrupt with ID 0.
+        #[derive(Clone, Copy)]
+        struct Local0;
+
+        unsafe impl InterruptNumber for Local0 {
+            const MAX_INTERRUPT_NUMBER: u16 = 0;
+
+            fn number(self) -> u16 {
+                0
+            }
+
+            fn from_number(number: u16) -> Result<Self, u16> {
+                match number {
+                    0 => Ok(Local0),
+                    _ => Err(number),
+                }
+            }
+        }
+
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        // interrupt 0 is a valid source that uses the first configuration word
+        assert_eq!(
+            interrupts.register_address(Local0, IrqField::Pending),
+            raw_reg.as_ptr() as usize
+        );
+        unsafe { interrupts.enable(Local0) };
+        unsafe { interrupts.pend(Local0) };
+        assert!(interrupts.is_enabled(Local0));
+        assert!(interrupts.is_pending(Local0));
+        assert!(!interrupts.is_pending(Interrupt::I1));
+        assert_eq!(raw_reg[0], 0x0000_0101);
+    }
+
+    #[test]
+    fn test_register_address() {
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(0x1000) };
+
+        assert_eq!(
+            interrupts.register_address(Interrupt::I1, IrqField::Pending),
+            0x1004
+        );
+        assert_eq!(
+            interrupts.register_address(Interrupt::I2, IrqField::Enable),
+            0x1009
+        );
+        assert_eq!(
+            interrupts.register_address(Interrupt::I3, IrqField::Attr),
+            0x100E
+        );
+        assert_eq!(
+            interrupts.register_address(Interrupt::I4, IrqField::Ctl),
+            0x1013
+        );
+    }
+
+    #[test]
+    fn test_raw() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        unsafe { interrupts.write_raw(Interrupt::I2, 0x03_02_01_01) };
+        assert!(interrupts.is_pending(Interrupt::I2));
+        assert!(interrupts.is_enabled(Interrupt::I2));
+        assert_eq!(interrupts.get_trigger(Interrupt::I2), Edge::Rising.into());
+        assert_eq!(interrupts.get_priority(Interrupt::I2), 3);
+
+        interrupts.disable(Interrupt::I2);
+        assert_eq!(interrupts.read_raw(Interrupt::I2), 0x03_02_00_01);
+        assert_eq!(interrupts.read_raw(Interrupt::I1), 0);
+    }
+
+    #[test]
+    fn test_save_restore() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        unsafe { interrupts.arm(Interrupt::I1, Priority::P1) };
+        unsafe { interrupts.set_trigger(Interrupt::I4, Edge::Falling) };
+
+        let mut buf = [0u32; 8];
+        // Interrupt::MAX_INTERRUPT_NUMBER is 4, so only 5 words are saved
+        assert_eq!(interrupts.save_into::<Interrupt>(&mut buf), 5);
+        assert_eq!(buf[1], interrupts.read_raw(Interrupt::I1));
+        assert_eq!(buf[4], interrupts.read_raw(Interrupt::I4));
+
+        // emulate a power loss
+        raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+        assert!(!interrupts.is_enabled(Interrupt::I1));
+
+        assert_eq!(unsafe { interrupts.restore_from::<Interrupt>(&buf) }, 5);
+        assert!(interrupts.is_enabled(Interrupt::I1));
+        assert_eq!(interrupts.get_priority(Interrupt::I1), 1);
+        assert_eq!(interrupts.get_trigger(Interrupt::I4), Edge::Falling.into());
+
+        // short buffers are partially copied
+        let mut short = [0u32; 2];
+        assert_eq!(interrupts.save_into::<Interrupt>(&mut short), 2);
+        assert_eq!(short, buf[..2]);
+    }
+
+    #[test]
+    fn test_configure() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        assert_eq!(IrqConfig::default().word(), 0xFF_C0_00_00);
+
+        let config = IrqConfig::new()
+            .priority(Priority::P2)
+            .trigger(Edge::Falling)
+            .mode(Mode::Supervisor)
+            .vectored(true)
+            .enabled(true);
+        unsafe { interrupts.pend(Interrupt::I3) };
+        unsafe { interrupts.configure(Interrupt::I3, config) };
+
+        assert_eq!(interrupts.read_raw(Interrupt::I3), 0x02_47_01_00);
+        assert!(interrupts.is_enabled(Interrupt::I3));
+        assert!(!interrupts.is_pending(Interrupt::I3));
+        assert_eq!(interrupts.get_priority(Interrupt::I3), 2);
+        assert_eq!(interrupts.get_trigger(Interrupt::I3), Edge::Falling.into());
+    }
+
+    #[test]
+    fn test_highest_pending() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic4>::new(raw_reg.as_mut_ptr() as _) };
+        assert_eq!(interrupts.highest_pending::<Interrupt>(), None);
+
+        // pending but disabled sources do not compete
+        unsafe { interrupts.write_raw(Interrupt::I4, 0xF0_00_00_01) };
+        assert_eq!(interrupts.highest_pending::<Interrupt>(), None);
+
+        // enabled but not pending sources do not compete
+        unsafe { interrupts.write_raw(Interrupt::I3, 0xF0_00_01_00) };
+        assert_eq!(interrupts.highest_pending::<Interrupt>(), None);
+
+        unsafe { interrupts.write_raw(Interrupt::I1, 0x10_00_01_01) };
+        assert_eq!(
+            interrupts.highest_pending::<Interrupt>(),
+            Some(Interrupt::I1)
+        );
+        unsafe { interrupts.write_raw(Interrupt::I2, 0x20_00_01_01) };
+        assert_eq!(
+            interrupts.highest_pending::<Interrupt>(),
+            Some(Interrupt::I2)
+        );
+
+        // unimplemented bits are ignored, so I1 and I2 tie: the highest interrupt number wins
+        unsafe { interrupts.write_raw(Interrupt::I1, 0x2F_00_01_01) };
+        assert_eq!(
+            interrupts.highest_pending::<Interrupt>(),
+            Some(Interrupt::I2)
+        );
+        unsafe { interrupts.write_raw(Interrupt::I3, 0x20_00_01_01) };
+        assert_eq!(
+            interrupts.highest_pending::<Interrupt>(),
+            Some(Interrupt::I3)
+        );
+    }
+
+    #[test]
+    fn test_sweeps() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        unsafe { interrupts.enable_all::<Interrupt>() };
+        // source 0 is a valid interrupt source
+        assert!(raw_reg[..=4].iter().all(|&word| word == 0x00_00_01_00));
+        // sources beyond MAX_INTERRUPT_NUMBER are not touched
+        assert_eq!(raw_reg[5], 0);
+
+        raw_reg[..5].fill(0x00_00_01_01);
+        interrupts.disable_all::<Interrupt>();
+        unsafe { interrupts.clear_all_pending::<Interrupt>() };
+        assert!(raw_reg.iter().all(|&word| word == 0));
+    }
+
+    #[test]
+    fn test_arm() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        unsafe { interrupts.arm(Interrupt::I1, Priority::P2) };
+        assert!(interrupts.is_enabled(Interrupt::I1));
+        assert!(!interrupts.is_pending(Interrupt::I1));
+        assert_eq!(interrupts.get_priority(Interrupt::I1), 2);
+
+        unsafe { interrupts.trigger(Interrupt::I3) };
+        assert!(interrupts.is_enabled(Interrupt::I3));
+        assert!(interrupts.is_pending(Interrupt::I3));
+
+        interrupts.disarm(Interrupt::I3);
+        assert!(!interrupts.is_enabled(Interrupt::I3));
+        assert!(!interrupts.is_pending(Interrupt::I3));
+        assert!(interrupts.is_enabled(Interrupt::I1));
+    }
+
+    #[test]
+    fn test_recorded_accesses() {
+        use crate::testing::{MockMmio, Record};
+
+        let mmio = MockMmio::with_log(0x20);
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(mmio.base()) };
+
+        // single byte accesses to the fields of interrupt source 1 (word at offset 4)
+        unsafe { interrupts.set_priority(Interrupt::I1, Priority::P3) };
+        unsafe { interrupts.enable(Interrupt::I1) };
+        assert_eq!(
+            mmio.records(),
+            [Record::write(7, 0x03u8), Record::write(5, 0x01u8)]
+        );
+
+        // read-modify-write of the clicintattr register
+        mmio.clear_records();
+        mmio.write::<u8>(10, 0x07);
+        unsafe { interrupts.set_mode(Interrupt::I2, Mode::Supervisor) };
+        assert_eq!(
+            mmio.records(),
+            [Record::read(10, 0x07u8), Record::write(10, 0x47u8)]
+        );
+
+        // configurations are written with a single word access
+        mmio.clear_records();
+        unsafe { interrupts.configure(Interrupt::I3, IrqConfig::new()) };
+        assert_eq!(mmio.records(), [Record::write(12, 0xFF_C0_00_00u32)]);
+    }
+
+    #[test]
+    fn test_mode() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        assert_eq!(interrupts.get_mode(Interrupt::I1), Some(Mode::User));
+        raw_reg[1] = 0x80 << 16;
+        assert_eq!(interrupts.get_mode(Interrupt::I1), None);
+
+        // shv and trigger fields must be preserved
+        for word in raw_reg.iter_mut() {
+            *word = 0xC7 << 16;
+        }
+        let modes = [
+            (Interrupt::I1, Mode::Supervisor),
+            (Interrupt::I2, Mode::Supervisor),
+            (Interrupt::I3, Mode::User),
+        ];
+        assert_eq!(unsafe { interrupts.set_mode_range(&modes) }, Ok(()));
+        assert_eq!(
+            raw_reg[..5],
+            [0xC7 << 16, 0x47 << 16, 0x47 << 16, 0x07 << 16, 0xC7 << 16,]
+        );
+        for (source, mode) in modes {
+            assert_eq!(interrupts.get_mode(source), Some(mode));
+        }
+        assert_eq!(interrupts.get_mode(Interrupt::I4), Some(Mode::Machine));
+    }
+
+    #[test]
+    fn test_field_presence() {
+        /// CLIC without `clicintattr` and `clicintctl` registers.
+        #[derive(Clone, Copy)]
+        struct ClicMinimal;
+
+        unsafe impl Clic for ClicMinimal {
+            const BASE: usize = 0;
+            const HAS_ATTR: bool = false;
+            const HAS_CTL: bool = false;
+        }
+
+        assert!(INTERRUPTS::<Clic8>::implements(IrqField::Attr));
+        assert!(INTERRUPTS::<Clic8>::implements(IrqField::Ctl));
+        assert!(INTERRUPTS::<ClicMinimal>::implements(IrqField::Pending));
+        assert!(INTERRUPTS::<ClicMinimal>::implements(IrqField::Enable));
+        assert!(!INTERRUPTS::<ClicMinimal>::implements(IrqField::Attr));
+        assert!(!INTERRUPTS::<ClicMinimal>::implements(IrqField::Ctl));
+
+        // SAFETY: no critical sections in the test
+        let token = unsafe { UnmaskToken::new() };
+        // absent registers hold garbage that must be neither read nor written
+        let mut raw_reg = [0x5A5A_0000u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<ClicMinimal>::new(raw_reg.as_mut_ptr() as _) };
+
+        assert_eq!(interrupts.get_priority(Interrupt::I1), 0);
+        assert_eq!(
+            interrupts.get_trigger(Interrupt::I1),
+            Trigger::Level(Level::High)
+        );
+        assert_eq!(interrupts.get_mode(Interrupt::I1), Some(Mode::Machine));
+        assert_eq!(interrupts.implemented_ctl_bits(Interrupt::I1), 0);
+
+        unsafe {
+            interrupts.set_priority(Interrupt::I1, Priority::P3);
+            interrupts.set_trigger(Interrupt::I1, Edge::Rising);
+            interrupts.set_mode(Interrupt::I1, Mode::User);
+            assert_eq!(
+                interrupts.try_set_trigger(Interrupt::I1, Edge::Rising),
+                Err(UnimplementedField(IrqField::Attr))
+            );
+            assert_eq!(
+                interrupts.try_set_mode(Interrupt::I1, Mode::User),
+                Err(UnimplementedField(IrqField::Attr))
+            );
+            assert_eq!(
+                interrupts.set_mode_range(&[(Interrupt::I1, Mode::User)]),
+                Err(SetModeError::UnimplementedAttr)
+            );
+        }
+        assert_eq!(
+            interrupts.try_set_priority(Interrupt::I1, Priority::P0, token),
+            Err(SetPriorityError::UnimplementedCtl)
+        );
+        assert_eq!(raw_reg[1], 0x5A5A_0000);
+
+        // clicintip and clicintie are still accessible
+        unsafe { interrupts.trigger(Interrupt::I1) };
+        assert_eq!(raw_reg[1], 0x5A5A_0101);
+
+        // the try_ variants succeed on a CLIC with all the registers
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+        unsafe {
+            assert_eq!(
+                interrupts.try_set_trigger(Interrupt::I1, Edge::Rising),
+                Ok(())
+            );
+            assert_eq!(
+                interrupts.try_set_mode(Interrupt::I1, Mode::Machine),
+                Ok(())
+            );
+        }
+        assert_eq!(
+            interrupts.get_trigger(Interrupt::I1),
+            Trigger::Edge(Edge::Rising)
+        );
+        assert_eq!(interrupts.get_mode(Interrupt::I1), Some(Mode::Machine));
+    }
+
+    #[test]
+    fn test_mode_range_invalid() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        // invalid sources are rejected before modifying any register
+        let modes = [(Raw(1), Mode::Machine), (Raw(4), Mode::Machine)];
+        assert_eq!(
+            unsafe { interrupts.set_mode_range(&modes) },
+            Err(SetModeError::InvalidSource(4))
+        );
+        assert_eq!(raw_reg, [0; 32]);
+    }
+
+    #[test]
+    fn test_trigger() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+
+        assert_eq!(interrupts.get_trigger(Interrupt::I1), Level::High.into());
+
+        // shv and mode fields must be preserved
+        raw_reg[2] = 0xC1 << 16;
+        let triggers = [
+            (Trigger::Level(Level::High), 0b00),
+            (Trigger::Edge(Edge::Rising), 0b01),
+            (Trigger::Level(Level::Low), 0b10),
+            (Trigger::Edge(Edge::Falling), 0b11),
+        ];
+        for (trigger, bits) in triggers {
+            assert_eq!(trigger.bits(), bits);
+            assert_eq!(Trigger::from_bits(bits), trigger);
+            unsafe { interrupts.set_trigger(Interrupt::I2, trigger) };
+            assert_eq!(interrupts.get_trigger(Interrupt::I2), trigger);
+            assert_eq!(raw_reg[2] >> 16, 0xC1 | (bits as u32) << 1);
+        }
+
+        assert_eq!(Trigger::from(Edge::Falling).polarity(), Polarity::Negative);
+        assert_eq!(Trigger::from(Level::High).polarity(), Polarity::Positive);
+        assert!(Trigger::from(Edge::Rising).is_edge());
+        assert!(!Trigger::from(Level::Low).is_edge());
+    }
+
+    #[test]
+    fn test_priority_normalization() {
+        let mut raw_reg = [0u32; 32];
+        let interrupts = unsafe { INTERRUPTS::<Clic4>::new(raw_reg.as_mut_ptr() as _) };
+
+        // emulate the hardware: unimplemented bits read as 1
+        raw_reg[1] = 0x3F << 24;
+        assert_eq!(interrupts.get_priority(Interrupt::I1), 0x30);
+        raw_reg[2] = 0x0F << 24;
+        assert_eq!(interrupts.get_priority(Interrupt::I2), 0);
+
+        // with all bits implemented, the value is returned as is
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
+        assert_eq!(interrupts.get_priority(Interrupt::I1), 0x3F);
+    }
+
     #[test]
     fn test_pending() {
         let mut raw_reg = [0u32; 32];
 
-        let interrupts = unsafe { INTERRUPTS::new(raw_reg.as_mut_ptr() as _) };
+        let interrupts = unsafe { INTERRUPTS::<Clic8>::new(raw_reg.as_mut_ptr() as _) };
 
         unsafe { interrupts.pend(Interrupt::I1) };
         unsafe { interrupts.pend(Interrupt::I2) };
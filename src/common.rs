@@ -2,14 +2,17 @@
 
 /// Read-only type state for `A` in [`Reg`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RO;
 
 /// Write-only type state for `A` in [`Reg`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct WO;
 
 /// Read-write type state for `A` in [`Reg`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RW;
 
 /// Generic trait for all the peripheral registers.
@@ -39,12 +42,16 @@ impl Write for RW {}
 /// However, keep in mind that read-modify-write operations may lead to **wrong** behavior.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(transparent)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Reg<T: Copy, A: Access> {
     ptr: *mut T,
     phantom: core::marker::PhantomData<A>,
 }
 
+// SAFETY: a register is a memory-mapped location shared among all the HARTs.
+// All the accesses are volatile, so it can be sent to other threads if `T` can.
 unsafe impl<T: Copy + Send, A: Access> Send for Reg<T, A> {}
+// SAFETY: see the `Send` implementation above.
 unsafe impl<T: Copy + Sync, A: Access> Sync for Reg<T, A> {}
 
 impl<T: Copy, A: Access> Reg<T, A> {
@@ -53,7 +60,7 @@ impl<T: Copy, A: Access> Reg<T, A> {
     /// # Safety
     ///
     /// The pointer must be valid and must be correctly aligned.
-    #[inline]
+    #[inline(always)]
     pub const unsafe fn new(ptr: *mut T) -> Self {
         Self {
             ptr,
@@ -62,7 +69,7 @@ impl<T: Copy, A: Access> Reg<T, A> {
     }
 
     /// Returns a pointer to the register.
-    #[inline]
+    #[inline(always)]
     pub const fn get_ptr(self) -> *mut T {
         self.ptr
     }
@@ -74,10 +81,13 @@ impl<T: Copy, A: Read> Reg<T, A> {
     /// # Note
     ///
     /// If you want to perform a read-modify-write operation, use [`Reg::modify`] instead.
-    #[inline]
+    #[inline(always)]
     pub fn read(self) -> T {
         // SAFETY: valid address and register is readable
-        unsafe { self.ptr.read_volatile() }
+        let val = unsafe { self.ptr.read_volatile() };
+        #[cfg(any(test, feature = "testing"))]
+        let val = crate::testing::record_read(self.ptr as usize, val);
+        val
     }
 }
 
@@ -87,8 +97,10 @@ impl<T: Copy, A: Write> Reg<T, A> {
     /// # Note
     ///
     /// If you want to perform a read-modify-write operation, use [`Reg::modify`] instead.
-    #[inline]
+    #[inline(always)]
     pub fn write(self, val: T) {
+        #[cfg(any(test, feature = "testing"))]
+        crate::testing::record(crate::testing::Access::Write, self.ptr as usize, &val);
         // SAFETY: valid address and register is writable
         unsafe { self.ptr.write_volatile(val) }
     }
@@ -110,6 +122,200 @@ impl<T: Copy, A: Read + Write> Reg<T, A> {
     }
 }
 
+/// Bank of `N` contiguous registers of type `T` and access level `A` (e.g., the priority registers of a PLIC).
+///
+/// # Note
+///
+/// Indexed accesses check that the index is less than `N` only in debug builds.
+/// Use [`RegBank::get`] for a checked access in all builds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RegBank<T: Copy, A: Access, const N: usize> {
+    ptr: *mut T,
+    phantom: core::marker::PhantomData<A>,
+}
+
+// SAFETY: see the `Send` implementation of `Reg`.
+unsafe impl<T: Copy + Send, A: Access, const N: usize> Send for RegBank<T, A, N> {}
+// SAFETY: see the `Send` implementation of `Reg`.
+unsafe impl<T: Copy + Sync, A: Access, const N: usize> Sync for RegBank<T, A, N> {}
+
+impl<T: Copy, A: Access, const N: usize> RegBank<T, A, N> {
+    /// Creates a new register bank from a pointer to its first register.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be valid and correctly aligned for `N` contiguous registers.
+    #[inline]
+    pub const unsafe fn new(ptr: *mut T) -> Self {
+        Self {
+            ptr,
+            phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns a pointer to the first register of the bank.
+    #[inline]
+    pub const fn get_ptr(self) -> *mut T {
+        self.ptr
+    }
+
+    /// Returns the number of registers of the bank.
+    #[inline]
+    pub const fn len(self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the bank has no registers.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        N == 0
+    }
+
+    /// Returns the register at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is out of bounds.
+    #[inline]
+    pub fn reg(self, index: usize) -> Reg<T, A> {
+        match self.get(index) {
+            Some(reg) => reg,
+            None => panic!("register index out of bounds"),
+        }
+    }
+
+    /// Returns the register at position `index`, or [`None`] if it is out of bounds.
+    #[inline]
+    pub fn get(self, index: usize) -> Option<Reg<T, A>> {
+        match index < N {
+            // SAFETY: valid index
+            true => Some(unsafe { Reg::new(self.ptr.add(index)) }),
+            false => None,
+        }
+    }
+
+    /// Returns an iterator over the registers of the bank.
+    #[inline]
+    pub fn iter(self) -> impl Iterator<Item = Reg<T, A>> {
+        (0..N).map(move |index| self.reg(index))
+    }
+}
+
+impl<T: Copy, A: Read, const N: usize> RegBank<T, A, N> {
+    /// Performs a volatile read of the register at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is out of bounds.
+    #[inline]
+    pub fn read(self, index: usize) -> T {
+        self.reg(index).read()
+    }
+}
+
+impl<T: Copy, A: Write, const N: usize> RegBank<T, A, N> {
+    /// Performs a volatile write of the register at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is out of bounds.
+    #[inline]
+    pub fn write(self, index: usize, val: T) {
+        self.reg(index).write(val)
+    }
+}
+
+/// Asserts that the base address of a peripheral is aligned to `align` bytes (i.e., the width of its registers).
+///
+/// # Note
+///
+/// This function is intended to be used in `const` contexts, turning a misaligned base
+/// address into a compilation error. The codegen macros use it to check the `BASE` of
+/// the generated peripherals:
+///
+/// ```compile_fail
+/// riscv_peripheral::clint_codegen!(base 0x0200_0004,); // MTIME requires 8-byte alignment
+/// ```
+///
+/// # Panics
+///
+/// It panics if `align` is not a power of two or if `base` is not aligned to `align` bytes.
+#[inline]
+pub const fn assert_base_alignment(base: usize, align: usize) {
+    assert!(align.is_power_of_two(), "alignment must be a power of two");
+    assert!(
+        base & (align - 1) == 0,
+        "base address is not aligned to the width of the peripheral registers"
+    );
+}
+
+/// Acknowledgment that enabling, pending, or changing the priority of an interrupt source may
+/// break mask-based and priority-based critical sections.
+///
+/// The `try_`-prefixed methods of the PLIC and CLIC (e.g., `try_enable`) validate the interrupt
+/// source and require this token instead of being `unsafe`. Thus, the two safety concerns are
+/// kept apart: the methods check the source number, and the token holder vouches for
+/// the critical sections.
+///
+/// The token is [`Copy`], so a single token obtained during initialization can be reused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnmaskToken {
+    _private: (),
+}
+
+impl UnmaskToken {
+    /// Creates a new token.
+    ///
+    /// # Safety
+    ///
+    /// Unmasking interrupt sources must not break any critical section for as long as the
+    /// token (or any copy of it) is used. For instance, this holds during initialization,
+    /// before interrupts are globally enabled, or if critical sections do not rely on
+    /// interrupt masks or priorities (e.g., they disable interrupts globally).
+    #[inline]
+    pub const unsafe fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+/// Human-readable name of an interrupt source for logging.
+///
+/// It is returned by the `display_name` method of the [`crate::plic::InterruptNumber`] and
+/// [`crate::clic::InterruptNumber`] traits. It formats as the name provided by the PAC
+/// (e.g., `UART0`) or, if there is none, as `IRQ` followed by the interrupt number (e.g., `IRQ37`).
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct InterruptName {
+    /// Interrupt number of the source.
+    pub number: u16,
+    /// Name of the source, if provided by the PAC.
+    pub name: Option<&'static str>,
+}
+
+impl core::fmt::Display for InterruptName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.name {
+            Some(name) => f.write_str(name),
+            None => write!(f, "IRQ{}", self.number),
+        }
+    }
+}
+
+impl core::fmt::Debug for InterruptName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for InterruptName {
+    fn format(&self, f: defmt::Formatter) {
+        match self.name {
+            Some(name) => defmt::write!(f, "{=str}", name),
+            None => defmt::write!(f, "IRQ{=u16}", self.number),
+        }
+    }
+}
+
 /// Macro to provide bit-wise operations to integer number registers.
 macro_rules! bitwise_reg {
     ($TYPE: ty) => {
@@ -245,6 +451,7 @@ macro_rules! peripheral {
         /// Peripheral register.
         #[derive(Clone, Copy, Debug, Eq, PartialEq)]
         #[repr(transparent)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
         pub struct $REGISTER {
             register: $crate::common::Reg<$TYPE, $crate::common::$ACCESS>,
         }
@@ -267,6 +474,7 @@ macro_rules! peripheral {
         /// Peripheral register.
         #[derive(Clone, Copy, Debug, Eq, PartialEq)]
         #[repr(transparent)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
         pub struct $REGISTER<$GENERIC> {
             register: $crate::common::Reg<$TYPE, $crate::common::$ACCESS>,
             _marker: core::marker::PhantomData<$GENERIC>,
@@ -396,7 +604,70 @@ macro_rules! unsafe_peripheral {
     };
 }
 
-pub(crate) use {peripheral, safe_peripheral, unsafe_peripheral};
+/// Macro to define newtypes that compare values of a number trait by their number.
+macro_rules! ordered_number {
+    ($NAME: ident, $TRAIT: ident, $doc: literal) => {
+        #[doc = $doc]
+        ///
+        /// Comparisons are based on the value returned by the `number` method of the wrapped value.
+        #[derive(Clone, Copy, Debug)]
+        #[repr(transparent)]
+        pub struct $NAME<T: $TRAIT>(pub T);
+
+        impl<T: $TRAIT> $NAME<T> {
+            /// Returns the wrapped value.
+            #[inline]
+            pub fn get(self) -> T {
+                self.0
+            }
+
+            /// Returns the number of the wrapped value.
+            #[inline]
+            pub fn number(self) -> u16 {
+                self.0.number() as _
+            }
+        }
+
+        impl<T: $TRAIT> From<T> for $NAME<T> {
+            #[inline]
+            fn from(value: T) -> Self {
+                Self(value)
+            }
+        }
+
+        impl<T: $TRAIT> PartialEq for $NAME<T> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.number() == other.number()
+            }
+        }
+
+        impl<T: $TRAIT> Eq for $NAME<T> {}
+
+        impl<T: $TRAIT> PartialOrd for $NAME<T> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<T: $TRAIT> Ord for $NAME<T> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.number().cmp(&other.number())
+            }
+        }
+
+        impl<T: $TRAIT> core::hash::Hash for $NAME<T> {
+            #[inline]
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.number().hash(state);
+            }
+        }
+    };
+}
+
+pub(crate) use {ordered_number, peripheral, safe_peripheral, unsafe_peripheral};
 
 mod sealed {
     use super::*;
@@ -405,3 +676,46 @@ mod sealed {
     impl Access for WO {}
     impl Access for RW {}
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_reg_bank() {
+        let mut raw_regs = [0u32; 4];
+        // SAFETY: valid memory address
+        let bank = unsafe { RegBank::<u32, RW, 4>::new(raw_regs.as_mut_ptr()) };
+        assert_eq!(bank.len(), 4);
+        assert!(!bank.is_empty());
+
+        for i in 0..bank.len() {
+            bank.write(i, i as u32 * 10);
+        }
+        assert_eq!(bank.read(3), 30);
+        assert_eq!(bank.get(2).map(Reg::read), Some(20));
+        assert!(bank.get(4).is_none());
+        assert_eq!(bank.iter().map(Reg::read).sum::<u32>(), 60);
+        assert_eq!(bank.reg(1).get_ptr(), unsafe {
+            raw_regs.as_mut_ptr().add(1)
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "register index out of bounds")]
+    fn check_reg_bank_bounds() {
+        let mut raw_regs = [0u32; 4];
+        // SAFETY: valid memory address
+        let bank = unsafe { RegBank::<u32, RO, 4>::new(raw_regs.as_mut_ptr()) };
+        bank.read(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "register index out of bounds")]
+    fn check_reg_bank_bounds_write() {
+        let mut raw_regs = [0u32; 4];
+        // SAFETY: valid memory address
+        let bank = unsafe { RegBank::<u32, RW, 4>::new(raw_regs.as_mut_ptr()) };
+        bank.write(4, 1);
+    }
+}
@@ -0,0 +1,511 @@
+//! Accessors to the control and status registers (CSRs) used by the peripherals.
+//!
+//! When testing on the host, CSRs are emulated with thread-local variables.
+
+#![allow(dead_code)] // some accessors are only used with specific features
+
+/// Machine status register.
+pub(crate) mod mstatus {
+    #[cfg(test)]
+    std::thread_local! {
+        static MIE: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+    }
+
+    /// Returns the value of the Machine Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn mie() -> bool {
+        #[cfg(not(test))]
+        return riscv::register::mstatus::read().mie();
+        #[cfg(test)]
+        return MIE.with(|mie| mie.get());
+    }
+
+    /// Sets the Machine Interrupt Enable bit.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub(crate) unsafe fn set_mie() {
+        #[cfg(not(test))]
+        riscv::register::mstatus::set_mie();
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(true));
+    }
+
+    /// Clears the Machine Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn clear_mie() {
+        #[cfg(not(test))]
+        // SAFETY: it is safe to disable interrupts
+        unsafe {
+            riscv::register::mstatus::clear_mie()
+        };
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(false));
+    }
+}
+
+/// Machine interrupt enable register.
+pub(crate) mod mie {
+    #[cfg(test)]
+    std::thread_local! {
+        static MIE: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    #[cfg(test)]
+    const MSOFT: usize = 1 << 3;
+    #[cfg(test)]
+    const MTIMER: usize = 1 << 7;
+    #[cfg(test)]
+    const MEXT: usize = 1 << 11;
+
+    /// Returns the value of the Machine Software Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn msoft() -> bool {
+        #[cfg(not(test))]
+        return riscv::register::mie::read().msoft();
+        #[cfg(test)]
+        return MIE.with(|mie| mie.get() & MSOFT != 0);
+    }
+
+    /// Sets the Machine Software Interrupt Enable bit.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub(crate) unsafe fn set_msoft() {
+        #[cfg(not(test))]
+        riscv::register::mie::set_msoft();
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(mie.get() | MSOFT));
+    }
+
+    /// Clears the Machine Software Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn clear_msoft() {
+        #[cfg(not(test))]
+        // SAFETY: it is safe to disable interrupts
+        unsafe {
+            riscv::register::mie::clear_msoft()
+        };
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(mie.get() & !MSOFT));
+    }
+
+    /// Returns the value of the Machine Timer Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn mtimer() -> bool {
+        #[cfg(not(test))]
+        return riscv::register::mie::read().mtimer();
+        #[cfg(test)]
+        return MIE.with(|mie| mie.get() & MTIMER != 0);
+    }
+
+    /// Sets the Machine Timer Interrupt Enable bit.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub(crate) unsafe fn set_mtimer() {
+        #[cfg(not(test))]
+        riscv::register::mie::set_mtimer();
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(mie.get() | MTIMER));
+    }
+
+    /// Clears the Machine Timer Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn clear_mtimer() {
+        #[cfg(not(test))]
+        // SAFETY: it is safe to disable interrupts
+        unsafe {
+            riscv::register::mie::clear_mtimer()
+        };
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(mie.get() & !MTIMER));
+    }
+
+    /// Returns the value of the Machine External Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn mext() -> bool {
+        #[cfg(not(test))]
+        return riscv::register::mie::read().mext();
+        #[cfg(test)]
+        return MIE.with(|mie| mie.get() & MEXT != 0);
+    }
+
+    /// Sets the Machine External Interrupt Enable bit.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub(crate) unsafe fn set_mext() {
+        #[cfg(not(test))]
+        riscv::register::mie::set_mext();
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(mie.get() | MEXT));
+    }
+
+    /// Clears the Machine External Interrupt Enable bit.
+    #[inline]
+    pub(crate) fn clear_mext() {
+        #[cfg(not(test))]
+        // SAFETY: it is safe to disable interrupts
+        unsafe {
+            riscv::register::mie::clear_mext()
+        };
+        #[cfg(test)]
+        MIE.with(|mie| mie.set(mie.get() & !MEXT));
+    }
+}
+
+/// Machine interrupt pending register.
+pub(crate) mod mip {
+    /// Supervisor Timer Interrupt Pending bit.
+    #[cfg(test)]
+    const STIP: usize = 1 << 5;
+
+    #[cfg(test)]
+    std::thread_local! {
+        static MIP: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the raw value of the `mip` CSR.
+    #[inline]
+    pub(crate) fn read() -> usize {
+        #[cfg(not(test))]
+        return riscv::register::mip::read().bits();
+        #[cfg(test)]
+        return MIP.with(|mip| mip.get());
+    }
+
+    /// Sets the emulated raw value of the `mip` CSR.
+    #[cfg(test)]
+    pub(crate) fn set(bits: usize) {
+        MIP.with(|mip| mip.set(bits));
+    }
+
+    /// Returns the value of the Supervisor Timer Interrupt Pending bit.
+    #[inline]
+    pub(crate) fn stimer() -> bool {
+        #[cfg(not(test))]
+        return riscv::register::mip::read().stimer();
+        #[cfg(test)]
+        return MIP.with(|mip| mip.get() & STIP != 0);
+    }
+
+    /// Sets the Supervisor Timer Interrupt Pending bit.
+    ///
+    /// # Safety
+    ///
+    /// Pending interrupts may break mask-based critical sections.
+    #[inline]
+    pub(crate) unsafe fn set_stimer() {
+        #[cfg(not(test))]
+        riscv::register::mip::set_stimer();
+        #[cfg(test)]
+        MIP.with(|mip| mip.set(mip.get() | STIP));
+    }
+
+    /// Clears the Supervisor Timer Interrupt Pending bit.
+    #[inline]
+    pub(crate) fn clear_stimer() {
+        #[cfg(not(test))]
+        // SAFETY: it is safe to unpend the supervisor timer interrupt
+        unsafe {
+            riscv::register::mip::clear_stimer()
+        };
+        #[cfg(test)]
+        MIP.with(|mip| mip.set(mip.get() & !STIP));
+    }
+}
+
+/// HART ID register.
+pub(crate) mod mhartid {
+    #[cfg(test)]
+    std::thread_local! {
+        static MHARTID: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the ID of the current HART.
+    #[inline]
+    pub(crate) fn read() -> usize {
+        #[cfg(not(test))]
+        return riscv::register::mhartid::read();
+        #[cfg(test)]
+        return MHARTID.with(|id| id.get());
+    }
+
+    /// Sets the emulated ID of the current HART.
+    #[cfg(test)]
+    pub(crate) fn set(hart_id: usize) {
+        MHARTID.with(|id| id.set(hart_id));
+    }
+}
+
+/// Machine interrupt level threshold register of the CLIC.
+pub(crate) mod mintthresh {
+    #[cfg(test)]
+    std::thread_local! {
+        static MINTTHRESH: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the value of the `mintthresh` register.
+    #[inline]
+    pub(crate) fn read() -> usize {
+        #[cfg(not(test))]
+        {
+            let r: usize;
+            // SAFETY: reading `mintthresh` has no side effects
+            unsafe { core::arch::asm!("csrrs {0}, 0x347, x0", out(reg) r) };
+            r
+        }
+        #[cfg(test)]
+        return MINTTHRESH.with(|t| t.get());
+    }
+
+    /// Writes a value to the `mintthresh` register.
+    ///
+    /// # Note
+    ///
+    /// Lowering the threshold may cause a pending interrupt to be taken immediately.
+    #[inline]
+    pub(crate) fn write(thresh: usize) {
+        #[cfg(not(test))]
+        // SAFETY: `mintthresh` is a valid CSR of the CLIC
+        unsafe {
+            core::arch::asm!("csrrw x0, 0x347, {0}", in(reg) thresh)
+        };
+        #[cfg(test)]
+        MINTTHRESH.with(|t| t.set(thresh));
+    }
+
+    /// Writes a value to the `mintthresh` register and returns its previous value
+    /// with a single `csrrw` instruction.
+    ///
+    /// # Note
+    ///
+    /// Lowering the threshold may cause a pending interrupt to be taken immediately.
+    #[inline]
+    pub(crate) fn swap(thresh: usize) -> usize {
+        #[cfg(not(test))]
+        {
+            let r: usize;
+            // SAFETY: `mintthresh` is a valid CSR of the CLIC
+            unsafe { core::arch::asm!("csrrw {0}, 0x347, {1}", out(reg) r, in(reg) thresh) };
+            r
+        }
+        #[cfg(test)]
+        return MINTTHRESH.with(|t| t.replace(thresh));
+    }
+}
+
+/// Waits for an interrupt.
+///
+/// When testing on the host, it calls the hook set with [`set_wfi_hook`] (if any).
+#[inline]
+pub(crate) fn wfi() {
+    #[cfg(not(test))]
+    // SAFETY: `wfi` has no side effects besides stalling the HART
+    unsafe {
+        core::arch::asm!("wfi", options(nomem, nostack))
+    };
+    #[cfg(test)]
+    if let Some(hook) = WFI_HOOK.with(|hook| hook.get()) {
+        hook();
+    }
+}
+
+/// Waits for interrupts until `condition` returns `true`, and returns with `mstatus.MIE` set.
+///
+/// `condition` is evaluated and `wfi` is executed with `mstatus.MIE` cleared, so an interrupt
+/// that makes `condition` true cannot be taken between both. The HART still wakes up, as
+/// `wfi` ignores `mstatus.MIE`. Pending interrupts are taken right after each `wfi`.
+///
+/// # Safety
+///
+/// Enabling interrupts may break critical sections.
+#[inline]
+pub(crate) unsafe fn wait_until(mut condition: impl FnMut() -> bool) {
+    loop {
+        mstatus::clear_mie();
+        if condition() {
+            break;
+        }
+        wfi();
+        mstatus::set_mie();
+    }
+    mstatus::set_mie();
+}
+
+#[cfg(test)]
+std::thread_local! {
+    static WFI_HOOK: core::cell::Cell<Option<fn()>> = const { core::cell::Cell::new(None) };
+}
+
+/// Sets a function to be called every time [`wfi`] is executed in the current thread.
+#[cfg(test)]
+pub(crate) fn set_wfi_hook(hook: Option<fn()>) {
+    WFI_HOOK.with(|h| h.set(hook));
+}
+
+/// Machine interrupt status register of the CLIC.
+pub(crate) mod mintstatus {
+    #[cfg(test)]
+    std::thread_local! {
+        static MINTSTATUS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the value of the `mintstatus` register.
+    #[inline]
+    pub(crate) fn read() -> usize {
+        #[cfg(not(test))]
+        {
+            let r: usize;
+            // SAFETY: reading `mintstatus` has no side effects
+            unsafe { core::arch::asm!("csrrs {0}, 0xFB1, x0", out(reg) r) };
+            r
+        }
+        #[cfg(test)]
+        return MINTSTATUS.with(|s| s.get());
+    }
+
+    /// Sets the emulated value of the `mintstatus` register.
+    #[cfg(test)]
+    pub(crate) fn set(value: usize) {
+        MINTSTATUS.with(|s| s.set(value));
+    }
+}
+
+/// Machine trap vector table base address register of the CLIC.
+pub(crate) mod mtvt {
+    #[cfg(test)]
+    std::thread_local! {
+        static MTVT: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the value of the `mtvt` register.
+    #[inline]
+    pub(crate) fn read() -> usize {
+        #[cfg(not(test))]
+        {
+            let r: usize;
+            // SAFETY: reading `mtvt` has no side effects
+            unsafe { core::arch::asm!("csrrs {0}, 0x307, x0", out(reg) r) };
+            r
+        }
+        #[cfg(test)]
+        return MTVT.with(|t| t.get());
+    }
+
+    /// Writes the base address of the vector table to the `mtvt` register.
+    ///
+    /// # Safety
+    ///
+    /// The address must point to a valid vector table.
+    #[inline]
+    pub(crate) unsafe fn write(addr: usize) {
+        #[cfg(not(test))]
+        core::arch::asm!("csrrw x0, 0x307, {0}", in(reg) addr);
+        #[cfg(test)]
+        MTVT.with(|t| t.set(addr));
+    }
+}
+
+/// Machine trap cause register.
+pub(crate) mod mcause {
+    #[cfg(test)]
+    std::thread_local! {
+        static MCAUSE: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the value of the `mcause` register.
+    #[inline]
+    pub(crate) fn read() -> usize {
+        #[cfg(not(test))]
+        return riscv::register::mcause::read().bits();
+        #[cfg(test)]
+        return MCAUSE.with(|c| c.get());
+    }
+
+    /// Sets the emulated value of the `mcause` register.
+    #[cfg(test)]
+    pub(crate) fn set(value: usize) {
+        MCAUSE.with(|c| c.set(value));
+    }
+}
+
+/// Machine next interrupt handler address and interrupt enable register of the CLIC.
+pub(crate) mod mnxti {
+    #[cfg(test)]
+    std::thread_local! {
+        static NEXT: core::cell::Cell<Option<u16>> = const { core::cell::Cell::new(None) };
+    }
+
+    /// Emulated base address of the vector table when testing on the host.
+    #[cfg(test)]
+    pub(crate) const MTVT: usize = 0x8000_0000;
+
+    /// Reads `mnxti` with `csrrsi rd, mnxti, 8` (encoding `0x34546073 | rd << 7`).
+    ///
+    /// It sets `mstatus.MIE` and, if there is a pending interrupt with a level higher than both
+    /// `mcause.MPIL` and `mintthresh`, it claims it (updating `mintstatus.MIL` and `mcause.EXCCODE`)
+    /// and returns the address of its entry in the vector table. Otherwise, it returns 0.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break critical sections.
+    #[inline]
+    pub(crate) unsafe fn read_set_mie() -> usize {
+        #[cfg(not(test))]
+        {
+            let r: usize;
+            core::arch::asm!("csrrsi {0}, 0x345, 8", out(reg) r);
+            r
+        }
+        #[cfg(test)]
+        {
+            super::mstatus::set_mie();
+            match NEXT.with(|n| n.take()) {
+                Some(id) => {
+                    super::mcause::set(id as usize);
+                    MTVT + id as usize * core::mem::size_of::<usize>()
+                }
+                None => 0,
+            }
+        }
+    }
+
+    /// Sets the emulated next interrupt to be claimed by [`read_set_mie`].
+    #[cfg(test)]
+    pub(crate) fn set_next(id: Option<u16>) {
+        NEXT.with(|n| n.set(id));
+    }
+}
+
+/// Cycle counter for `rdcycle`.
+pub(crate) mod cycle {
+    #[cfg(test)]
+    std::thread_local! {
+        static CYCLE: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Returns the value of the `cycle` counter.
+    ///
+    /// When testing on the host, the counter is incremented by one on every read.
+    #[inline]
+    pub(crate) fn read64() -> u64 {
+        #[cfg(not(test))]
+        return riscv::register::cycle::read64();
+        #[cfg(test)]
+        return CYCLE.with(|c| {
+            let value = c.get();
+            c.set(value + 1);
+            value
+        });
+    }
+}
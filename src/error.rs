@@ -0,0 +1,118 @@
+//! Error types for invalid numbers of interrupt sources, priority levels, contexts, and HARTs,
+//! as well as for timeouts and timer periods.
+
+use core::fmt;
+
+/// Error returned when a number does not correspond to a valid HART ID.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvalidHartId(pub u16);
+
+/// Error returned when a number does not correspond to a valid interrupt source.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvalidInterruptNumber(pub u16);
+
+/// Error returned when a number does not correspond to a valid priority level.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvalidPriorityNumber(pub u8);
+
+/// Error returned when a number does not correspond to a valid PLIC context.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvalidContextNumber(pub u16);
+
+/// Error returned when a timer period (in microseconds) rounds to zero ticks at the timer frequency.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvalidPeriod(pub u64);
+
+/// Error returned when a condition is not met before a timeout expires.
+/// It contains the number of elapsed `MTIME` ticks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TimeoutError(pub u64);
+
+impl fmt::Display for InvalidHartId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid HART ID number: {}", self.0)
+    }
+}
+
+impl fmt::Display for InvalidInterruptNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid interrupt number: {}", self.0)
+    }
+}
+
+impl fmt::Display for InvalidPriorityNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid priority number: {}", self.0)
+    }
+}
+
+impl fmt::Display for InvalidContextNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid context number: {}", self.0)
+    }
+}
+
+impl fmt::Display for InvalidPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "period of {} us rounds to zero ticks", self.0)
+    }
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {} ticks", self.0)
+    }
+}
+
+// `core::error::Error` requires Rust 1.81, above the MSRV of the crate
+#[cfg(feature = "core-error")]
+impl core::error::Error for InvalidHartId {}
+#[cfg(feature = "core-error")]
+impl core::error::Error for InvalidInterruptNumber {}
+#[cfg(feature = "core-error")]
+impl core::error::Error for InvalidPriorityNumber {}
+#[cfg(feature = "core-error")]
+impl core::error::Error for InvalidContextNumber {}
+#[cfg(feature = "core-error")]
+impl core::error::Error for InvalidPeriod {}
+#[cfg(feature = "core-error")]
+impl core::error::Error for TimeoutError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn check_display() {
+        assert_eq!(InvalidHartId(5).to_string(), "invalid HART ID number: 5");
+        assert_eq!(
+            InvalidInterruptNumber(1024).to_string(),
+            "invalid interrupt number: 1024"
+        );
+        assert_eq!(
+            InvalidPriorityNumber(8).to_string(),
+            "invalid priority number: 8"
+        );
+        assert_eq!(
+            InvalidContextNumber(3).to_string(),
+            "invalid context number: 3"
+        );
+        assert_eq!(
+            InvalidPeriod(10).to_string(),
+            "period of 10 us rounds to zero ticks"
+        );
+        assert_eq!(TimeoutError(100).to_string(), "timed out after 100 ticks");
+    }
+
+    #[test]
+    fn check_map_keys() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let sorted: BTreeSet<_> = [3, 1, 2, 1].into_iter().map(InvalidContextNumber).collect();
+        assert!(sorted.into_iter().map(|e| e.0).eq([1, 2, 3]));
+        let hashed: HashSet<_> = [5, 5, 7].into_iter().map(InvalidHartId).collect();
+        assert_eq!(hashed.len(), 2);
+        assert!(hashed.contains(&InvalidHartId(7)));
+    }
+}
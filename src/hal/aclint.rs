@@ -1,11 +1,22 @@
 //! Delay trait implementation for (A)CLINT peripherals
 
-use crate::aclint::mtimer::MTIME;
+use crate::aclint::mtimer::{MTIME, MTIMECMP};
 pub use crate::hal::delay::DelayNs;
 
 /// Delay implementation for (A)CLINT peripherals.
+///
+/// # Note
+///
+/// [`MTIME`] is a lightweight `Copy` proxy to the `MTIME` register.
+/// Thus, several `Delay` instances can share the same `MTIME` register without moving it.
+/// Use [`Delay::get_mtime`] and [`Delay::get_freq`] to reuse the underlying register and frequency.
+///
+/// A `Delay` can also be bound to the `MTIMECMP` register of a HART (see [`Delay::new_on`]).
+/// Blocking delays always busy-poll `MTIME`, but with the `async` feature, asynchronous delays
+/// of a bound `Delay` wait for a machine timer interrupt of that HART instead.
 pub struct Delay {
     mtime: MTIME,
+    mtimecmp: Option<MTIMECMP>,
     freq: usize,
 }
 
@@ -13,7 +24,32 @@ impl Delay {
     /// Creates a new `Delay` instance.
     #[inline]
     pub const fn new(mtime: MTIME, freq: usize) -> Self {
-        Self { mtime, freq }
+        Self {
+            mtime,
+            mtimecmp: None,
+            freq,
+        }
+    }
+
+    /// Creates a new `Delay` instance bound to the `MTIMECMP` register of a HART.
+    ///
+    /// # Note
+    ///
+    /// On multi-HART systems, `mtimecmp` must belong to the HART that runs the delays.
+    /// Otherwise, interrupt-driven asynchronous delays would program the timer of another HART.
+    #[inline]
+    pub const fn new_on(mtime: MTIME, mtimecmp: MTIMECMP, freq: usize) -> Self {
+        Self {
+            mtime,
+            mtimecmp: Some(mtimecmp),
+            freq,
+        }
+    }
+
+    /// Creates a new `Delay` instance from a reference to an `MTIME` register.
+    #[inline]
+    pub const fn from_ref(mtime: &MTIME, freq: usize) -> Self {
+        Self::new(*mtime, freq)
     }
 
     /// Returns the frequency of the `MTIME` register.
@@ -33,14 +69,37 @@ impl Delay {
     pub const fn get_mtime(&self) -> MTIME {
         self.mtime
     }
+
+    /// Returns the `MTIMECMP` register the `Delay` is bound to, if any.
+    #[inline]
+    pub const fn get_mtimecmp(&self) -> Option<MTIMECMP> {
+        self.mtimecmp
+    }
+
+    /// Busy-waits for a [`fugit`] duration with `u32` or `u64` ticks.
+    /// The duration is converted to `MTIME` ticks using the current frequency of the `Delay`.
+    /// The conversion rounds up, so the delay never takes less than requested.
+    #[cfg(feature = "fugit")]
+    #[inline]
+    pub fn delay_for<T, const NOM: u32, const DENOM: u32>(
+        &mut self,
+        duration: fugit::Duration<T, NOM, DENOM>,
+    ) where
+        fugit::Duration<T, NOM, DENOM>: Into<fugit::Duration<u64, NOM, DENOM>>,
+    {
+        let duration: fugit::Duration<u64, NOM, DENOM> = duration.into();
+        let freq = self.freq as u64;
+        let n_ticks = crate::aclint::time::ratio_to_ticks(duration.ticks(), NOM, DENOM, freq);
+        let t0 = self.mtime.read();
+        while self.mtime.read().wrapping_sub(t0) < n_ticks {}
+    }
 }
 
 impl DelayNs for Delay {
     #[inline]
     fn delay_ns(&mut self, ns: u32) {
         let t0 = self.mtime.read();
-        let ns_64: u64 = ns.into();
-        let n_ticks = ns_64 * self.freq as u64 / 1_000_000_000;
+        let n_ticks = crate::aclint::time::ns_to_ticks(ns.into(), self.freq as u64);
         while self.mtime.read().wrapping_sub(t0) < n_ticks {}
     }
 }
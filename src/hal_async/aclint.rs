@@ -1,4 +1,14 @@
 //! Asynchronous delay implementation for the (A)CLINT peripheral.
+//!
+//! [`Delay`] busy-polls the `MTIME` register. With the `async` feature, [`InterruptDelay`]
+//! and the [`Delay`]s bound to the `MTIMECMP` register of a HART (see [`Delay::new_on`])
+//! wait for a machine timer interrupt instead, so the executor can put the HART to sleep.
+//!
+//! # Note
+//!
+//! [`InterruptDelay`] programs the `MTIMECMP` register of HART 0. On multi-HART systems, the
+//! interrupt-driven path requires a [`Delay`] bound to the `MTIMECMP` register of the current HART
+//! (e.g., via [`crate::aclint::CLINT::delay_on`]).
 
 use crate::aclint::mtimer::MTIME;
 pub use crate::hal::aclint::Delay;
@@ -35,22 +45,185 @@ impl Future for DelayAsync {
     }
 }
 
+/// Waits for `n_ticks` ticks of the `MTIME` register of `delay`.
+///
+/// With the `async` feature, if `delay` is bound to a `MTIMECMP` register, it waits for
+/// a machine timer interrupt. Otherwise, it busy-polls the `MTIME` register.
+#[inline]
+async fn wait_ticks(delay: &Delay, n_ticks: u64) {
+    #[cfg(feature = "async")]
+    if let Some(mtimecmp) = delay.get_mtimecmp() {
+        let mtime = delay.get_mtime();
+        let deadline = mtime.read().saturating_add(n_ticks);
+        return crate::aclint::timer::TimerFuture::new_on(mtimecmp, mtime, deadline).await;
+    }
+    DelayAsync::new(delay.get_mtime(), n_ticks).await;
+}
+
 impl DelayNs for Delay {
     #[inline]
     async fn delay_ns(&mut self, ns: u32) {
-        let n_ticks = ns as u64 * self.get_freq() as u64 / 1_000_000_000;
-        DelayAsync::new(self.get_mtime(), n_ticks).await;
+        let n_ticks = crate::aclint::time::ns_to_ticks(ns as u64, self.get_freq() as u64);
+        wait_ticks(self, n_ticks).await;
+    }
+
+    #[inline]
+    async fn delay_us(&mut self, us: u32) {
+        let n_ticks = crate::aclint::time::us_to_ticks(us as u64, self.get_freq() as u64);
+        wait_ticks(self, n_ticks).await;
+    }
+
+    #[inline]
+    async fn delay_ms(&mut self, ms: u32) {
+        let n_ticks = crate::aclint::time::ms_to_ticks(ms as u64, self.get_freq() as u64);
+        wait_ticks(self, n_ticks).await;
+    }
+}
+
+/// Interrupt-driven asynchronous delay implementation for (A)CLINT peripherals.
+///
+/// Instead of busy-polling the `MTIME` register, it programs the `MTIMECMP` register of HART 0
+/// and waits for a machine timer interrupt (see the [`crate::aclint::timer`] module).
+/// For other HARTs, use a [`Delay`] bound to their `MTIMECMP` register instead.
+///
+/// # Note
+///
+/// For delays to complete, you **must**:
+///
+/// * Enable machine timer interrupts (e.g., via the `mtimer_enable` method of the `CLINT`).
+/// * Call [`on_timer_interrupt`] from the machine timer interrupt handler.
+#[cfg(feature = "async")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InterruptDelay {
+    mtimer: crate::aclint::mtimer::MTIMER,
+    freq: usize,
+}
+
+#[cfg(feature = "async")]
+impl InterruptDelay {
+    /// Creates a new `InterruptDelay` instance.
+    #[inline]
+    pub const fn new(mtimer: crate::aclint::mtimer::MTIMER, freq: usize) -> Self {
+        Self { mtimer, freq }
+    }
+
+    /// Returns the frequency of the `MTIME` register.
+    #[inline]
+    pub const fn get_freq(&self) -> usize {
+        self.freq
+    }
+
+    /// Sets the frequency of the `MTIME` register.
+    #[inline]
+    pub fn set_freq(&mut self, freq: usize) {
+        self.freq = freq;
+    }
+
+    /// Returns the `MTIMER` peripheral.
+    #[inline]
+    pub const fn get_mtimer(&self) -> crate::aclint::mtimer::MTIMER {
+        self.mtimer
+    }
+}
+
+#[cfg(feature = "async")]
+impl DelayNs for InterruptDelay {
+    #[inline]
+    async fn delay_ns(&mut self, ns: u32) {
+        let n_ticks = crate::aclint::time::ns_to_ticks(ns as u64, self.freq as u64);
+        self.mtimer.sleep(n_ticks).await;
     }
 
     #[inline]
     async fn delay_us(&mut self, us: u32) {
-        let n_ticks = us as u64 * self.get_freq() as u64 / 1_000_000;
-        DelayAsync::new(self.get_mtime(), n_ticks).await;
+        let n_ticks = crate::aclint::time::us_to_ticks(us as u64, self.freq as u64);
+        self.mtimer.sleep(n_ticks).await;
     }
 
     #[inline]
     async fn delay_ms(&mut self, ms: u32) {
-        let n_ticks = ms as u64 * self.get_freq() as u64 / 1_000;
-        DelayAsync::new(self.get_mtime(), n_ticks).await;
+        let n_ticks = crate::aclint::time::ms_to_ticks(ms as u64, self.freq as u64);
+        self.mtimer.sleep(n_ticks).await;
+    }
+}
+
+/// Wakes the pending [`InterruptDelay`] delays whose deadline has been reached.
+///
+/// # Note
+///
+/// This function **must** be called from the machine timer interrupt handler.
+#[cfg(feature = "async")]
+#[inline]
+pub fn on_timer_interrupt() {
+    crate::aclint::timer::TimerFuture::on_interrupt();
+}
+
+#[cfg(all(test, feature = "async"))]
+mod test {
+    use super::*;
+    use crate::aclint::{
+        mtimer::MTIMER,
+        timer::test::{waker, QUEUE_LOCK},
+    };
+
+    #[test]
+    fn check_interrupt_delay() {
+        let _lock = QUEUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                &mut raw_mtimecmp as *mut u64 as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+        let waker = waker();
+        let mut cx = Context::from_waker(&waker);
+        unsafe { crate::csr::mstatus::set_mie() };
+
+        let mut delay = InterruptDelay::new(mtimer, 1_000_000);
+        assert_eq!(delay.get_freq(), 1_000_000);
+        assert_eq!(delay.get_mtimer(), mtimer);
+
+        // 1 ms at 1 MHz: the deadline is programmed in MTIMECMP
+        mtimer.mtime.write(500);
+        {
+            let mut future = core::pin::pin!(delay.delay_ms(1));
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+            assert_eq!(mtimer.mtimecmp0.read(), 1_500);
+            // the queue is accessed with interrupts disabled, and they are enabled again afterwards
+            assert!(crate::csr::mstatus::mie());
+
+            mtimer.mtime.write(1_499);
+            on_timer_interrupt();
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+            assert_eq!(mtimer.mtimecmp0.read(), 1_500);
+
+            mtimer.mtime.write(1_500);
+            on_timer_interrupt();
+            assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(()));
+        }
+
+        // sub-tick delays round up to one tick
+        {
+            let mut future = core::pin::pin!(delay.delay_ns(1));
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+            assert_eq!(mtimer.mtimecmp0.read(), 1_501);
+            mtimer.mtime.write(1_501);
+            on_timer_interrupt();
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(()));
+            assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
+        }
+
+        // dropping a pending delay clears its deadline
+        delay.set_freq(1_000);
+        {
+            let mut future = core::pin::pin!(delay.delay_us(1_000));
+            assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+            assert_eq!(mtimer.mtimecmp0.read(), 1_502);
+        }
+        assert_eq!(mtimer.mtimecmp0.read(), u64::MAX);
     }
 }
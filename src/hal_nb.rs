@@ -0,0 +1,66 @@
+//! Non-blocking polling functions for drivers based on the `nb` crate.
+//!
+//! These functions return [`nb::Error::WouldBlock`] until a condition is met.
+//! Thus, `nb`-based drivers can use the `MTIME` counter as a timeout source,
+//! and block on it with [`nb::block!`] if needed.
+
+pub use nb; // re-export nb to allow users to use the same version
+
+use crate::aclint::mtimer::MTIMER;
+use core::convert::Infallible;
+
+impl MTIMER {
+    /// Returns [`nb::Error::WouldBlock`] until `MTIME` reaches `deadline`.
+    #[inline]
+    pub fn poll_deadline(&self, deadline: u64) -> nb::Result<(), Infallible> {
+        match self.mtime.read() >= deadline {
+            true => Ok(()),
+            false => Err(nb::Error::WouldBlock),
+        }
+    }
+
+    /// Returns [`nb::Error::WouldBlock`] until `ticks` ticks of `MTIME` elapse since `start`.
+    ///
+    /// # Note
+    ///
+    /// Elapsed ticks are computed with wrapping arithmetic, so the timeout is correct
+    /// even if `MTIME` wraps around while polling.
+    #[inline]
+    pub fn poll_elapsed(&self, start: u64, ticks: u64) -> nb::Result<(), Infallible> {
+        match self.mtime.read().wrapping_sub(start) >= ticks {
+            true => Ok(()),
+            false => Err(nb::Error::WouldBlock),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_poll() {
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let mtimer = unsafe {
+            MTIMER::new(
+                &mut raw_mtimecmp as *mut u64 as _,
+                &mut raw_mtime as *mut u64 as _,
+            )
+        };
+
+        mtimer.mtime.write(99);
+        assert_eq!(mtimer.poll_deadline(100), Err(nb::Error::WouldBlock));
+        mtimer.mtime.write(100);
+        assert_eq!(mtimer.poll_deadline(100), Ok(()));
+        assert_eq!(nb::block!(mtimer.poll_deadline(50)), Ok(()));
+
+        mtimer.mtime.write(5);
+        assert_eq!(
+            mtimer.poll_elapsed(u64::MAX - 4, 20),
+            Err(nb::Error::WouldBlock)
+        );
+        assert_eq!(mtimer.poll_elapsed(u64::MAX - 4, 10), Ok(()));
+    }
+}
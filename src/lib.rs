@@ -3,13 +3,67 @@
 #![deny(missing_docs)]
 #![no_std]
 
+#[cfg(any(test, feature = "testing"))]
+extern crate std;
+
 pub use riscv; // re-export riscv crate to allow macros to use it
 
 pub mod aclint; // ACLINT and CLINT peripherals
 pub mod clic; // CLIC peripheral
 pub mod common; // common definitions for all peripherals
+mod csr; // CSR accessors (emulated when testing on the host)
+pub mod error; // error types
 pub mod hal; // trait implementations for embedded-hal
 #[cfg(feature = "hal-async")]
 pub mod hal_async; // async trait implementations for embedded-hal
+#[cfg(feature = "nb")]
+pub mod hal_nb; // non-blocking polling functions for nb-based drivers
 pub mod macros; // macros for easing the definition of peripherals in PACs
+pub mod mapping; // mapping of interrupt sources between the CLIC and the PLIC
 pub mod plic; // PLIC peripheral
+#[cfg(feature = "rtic")]
+pub mod rtic; // RTIC monotonic timer implementations
+mod sync; // atomics for internal shared state (optionally from portable-atomic)
+#[cfg(any(test, feature = "testing"))]
+pub mod testing; // utilities for testing peripherals on the host
+
+/// Common interface of the interrupt controllers generated by [`clint_codegen!`], [`plic_codegen!`], and [`clic_codegen!`].
+///
+/// It allows writing code that is generic over the interrupt controller of the target.
+/// The generated types also provide these methods as inherent methods for direct use.
+pub trait InterruptController {
+    /// Returns `true` if the interrupt controller is interrupting the current HART.
+    fn is_interrupting() -> bool;
+
+    /// Returns `true` if the interrupts of the interrupt controller are enabled in the current HART.
+    fn is_enabled() -> bool;
+
+    /// Enables the interrupts of the interrupt controller in the current HART.
+    ///
+    /// # Safety
+    ///
+    /// Enabling interrupts may break mask-based critical sections.
+    unsafe fn enable();
+
+    /// Disables the interrupts of the interrupt controller in the current HART.
+    fn disable();
+}
+
+/// Claim side of an interrupt controller: a source of pending interrupts of type `I`.
+///
+/// Together with [`InterruptSink`], it allows device drivers and frameworks to work with any
+/// interrupt controller that follows a claim/complete protocol (e.g., a PLIC context).
+/// Every interrupt returned by [`InterruptSource::claim`] must eventually be passed to
+/// [`InterruptSink::complete`] so that the interrupt controller can signal it again.
+pub trait InterruptSource<I> {
+    /// Claims the highest priority pending interrupt, if any.
+    fn claim(&self) -> Option<I>;
+}
+
+/// Completion side of an interrupt controller: a sink of handled interrupts of type `I`.
+///
+/// See [`InterruptSource`] for details.
+pub trait InterruptSink<I> {
+    /// Signals that the handling of a previously claimed interrupt is complete.
+    fn complete(&self, source: I);
+}
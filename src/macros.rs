@@ -6,9 +6,14 @@
 /// This macro expects 4 different argument types:
 ///
 /// - Base address (**MANDATORY**): base address of the CLINT peripheral of the target.
-/// - Frequency (**OPTIONAL**): clock frequency (in Hz) of the `MTIME` register. It enables the `delay` method of the `CLINT` struct.
+///   Alternatively, ACLINT targets with independent `MSWI` and `MTIMER` devices can provide the
+///   base address of each device with `mswi_base` and `mtimer_base`. By default, `MTIME` is placed at
+///   [`crate::aclint::ACLINT_MTIME_OFFSET`] from `mtimer_base`, unless `mtime_base` is also provided.
+/// - Frequency (**OPTIONAL**): clock frequency (in Hz) of the `MTIME` register. It enables the `delay` and `now` methods of the `CLINT` struct.
 /// - Per-HART mtimecmp registers (**OPTIONAL**): a list of `mtimecmp` registers for easing access to per-HART mtimecmp regs.
 /// - Per-HART msip registers (**OPTIONAL**): a list of `msip` registers for easing access to per-HART msip regs.
+/// - HART ID type (**OPTIONAL**): the type implementing [`crate::aclint::HartIdNumber`] in the target.
+///   It enables the `mtimecmp` and `msip` methods of the `CLINT` struct to access the registers of any HART.
 ///
 /// Check the examples below for more details about the usage and syntax of this macro.
 ///
@@ -67,6 +72,57 @@
 /// let msip1 = CLINT::msip1(); // msip register for HART 1
 /// let msip2 = CLINT::msip2(); // msip register for HART 2
 /// ```
+///
+/// ## Base address and HART ID type
+///
+/// ```
+/// use riscv_peripheral::clint_codegen;
+///
+/// /// HART IDs for the target CLINT peripheral
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// #[repr(u16)]
+/// pub enum HartId { H0 = 0, H1 = 1, H2 = 2 }
+///
+/// // Implement `HartIdNumber` for `HartId`
+/// unsafe impl riscv_peripheral::aclint::HartIdNumber for HartId {
+///   const MAX_HART_ID_NUMBER: u16 = 2;
+///   fn number(self) -> u16 { self as _ }
+///   fn from_number(number: u16) -> Result<Self, u16> {
+///     if number > Self::MAX_HART_ID_NUMBER {
+///        Err(number)
+///     } else {
+///        // SAFETY: valid context number
+///        Ok(unsafe { core::mem::transmute(number) })
+///     }
+///   }
+/// }
+///
+/// clint_codegen!(base 0x0200_0000, harts HartId,); // do not forget the ending comma!
+///
+/// let mtimecmp2 = CLINT::mtimecmp(HartId::H2); // mtimecmp register for HART 2
+/// let msip2 = CLINT::msip(HartId::H2); // msip register for HART 2
+/// ```
+///
+/// ## ACLINT with independent device base addresses
+///
+/// ```
+/// use riscv_peripheral::clint_codegen;
+///
+/// clint_codegen!(mswi_base 0x0200_0000, mtimer_base 0x0300_0000, freq 10_000_000,);
+///
+/// assert_eq!(CLINT::mswi().msip0.get_ptr() as usize, 0x0200_0000);
+/// assert_eq!(CLINT::mtimer().mtimecmp0.get_ptr() as usize, 0x0300_0000);
+/// assert_eq!(CLINT::mtime().get_ptr() as usize, 0x0300_7FF8); // default MTIME offset
+/// ```
+///
+/// ```
+/// use riscv_peripheral::clint_codegen;
+///
+/// clint_codegen!(mswi_base 0x0200_0000, mtimer_base 0x0300_0000, mtime_base 0x0400_0000,);
+///
+/// assert_eq!(CLINT::mtimer().mtimecmp0.get_ptr() as usize, 0x0300_0000);
+/// assert_eq!(CLINT::mtime().get_ptr() as usize, 0x0400_0000);
+/// ```
 #[macro_export]
 macro_rules! clint_codegen {
     () => {
@@ -74,20 +130,76 @@ macro_rules! clint_codegen {
         use CLINT as _; // assert that the CLINT struct is defined
     };
     (base $addr:literal, $($tail:tt)*) => {
+        // `MTIME` and `MTIMECMP` are 64-bit registers
+        const _: () = $crate::common::assert_base_alignment($addr, 8);
+
+        $crate::clint_codegen!(@clint { const BASE: usize = $addr; }, $($tail)*);
+    };
+    (mswi_base $mswi:literal, mtimer_base $mtimer:literal, mtime_base $mtime:literal, $($tail:tt)*) => {
+        // `MSIP` registers are 32-bit registers, and `MTIME` and `MTIMECMP` are 64-bit registers
+        const _: () = $crate::common::assert_base_alignment($mswi, 4);
+        const _: () = $crate::common::assert_base_alignment($mtimer, 8);
+        const _: () = $crate::common::assert_base_alignment($mtime, 8);
+
+        $crate::clint_codegen!(@clint {
+            const BASE: usize = $mswi;
+            const MSWI_BASE: usize = $mswi;
+            const MTIMER_BASE: usize = $mtimer;
+            const MTIME_BASE: usize = $mtime;
+        }, $($tail)*);
+    };
+    (mswi_base $mswi:literal, mtimer_base $mtimer:literal, $($tail:tt)*) => {
+        // `MSIP` registers are 32-bit registers, and `MTIME` and `MTIMECMP` are 64-bit registers
+        const _: () = $crate::common::assert_base_alignment($mswi, 4);
+        const _: () = $crate::common::assert_base_alignment($mtimer, 8);
+
+        $crate::clint_codegen!(@clint {
+            const BASE: usize = $mswi;
+            const MSWI_BASE: usize = $mswi;
+            const MTIMER_BASE: usize = $mtimer;
+            const MTIME_BASE: usize = $mtimer + $crate::aclint::ACLINT_MTIME_OFFSET;
+        }, $($tail)*);
+    };
+    (@clint { $($consts:tt)* }, $($tail:tt)*) => {
         /// CLINT peripheral
         #[allow(clippy::upper_case_acronyms)]
         #[derive(Clone, Copy, Debug, Eq, PartialEq)]
         pub struct CLINT;
 
         unsafe impl $crate::aclint::Clint for CLINT {
-            const BASE: usize = $addr;
+            $($consts)*
         }
 
         impl CLINT {
+            /// Address of the `MSWI` device (i.e., `MSIP` of HART 0).
+            pub const MSWI_ADDR: usize = $crate::aclint::CLINT::<CLINT>::MSWI_ADDR;
+            /// Address of the `MTIMER` device (i.e., `MTIMECMP` of HART 0).
+            pub const MTIMER_ADDR: usize = $crate::aclint::CLINT::<CLINT>::MTIMER_ADDR;
+            /// Address of the `MTIME` register.
+            pub const MTIME_ADDR: usize = $crate::aclint::CLINT::<CLINT>::MTIME_ADDR;
+
+            /// Returns the address of the `MSIP` register of the HART with index `hart_index`.
+            #[inline]
+            pub const fn msip_addr(hart_index: u16) -> usize {
+                $crate::aclint::CLINT::<CLINT>::msip_addr(hart_index)
+            }
+
+            /// Returns the address of the `MTIMECMP` register of the HART with index `hart_index`.
+            #[inline]
+            pub const fn mtimecmp_addr(hart_index: u16) -> usize {
+                $crate::aclint::CLINT::<CLINT>::mtimecmp_addr(hart_index)
+            }
+
             /// Returns `true` if a machine timer **OR** software interrupt is pending.
             #[inline]
             pub fn is_interrupting() -> bool {
-                Self::mswi_is_interrupting() || Self::mtimer_is_interrupting()
+                Self::interrupting_sources().any()
+            }
+
+            /// Returns which machine timer and software interrupts are pending, reading `mip` once.
+            #[inline]
+            pub fn interrupting_sources() -> $crate::aclint::InterruptSources {
+                $crate::aclint::CLINT::<CLINT>::interrupting_sources()
             }
 
             /// Returns `true` if machine timer **OR** software interrupts are enabled.
@@ -113,6 +225,24 @@ macro_rules! clint_codegen {
                 Self::mtimer_disable();
             }
 
+            /// Waits for interrupts until `condition` returns `true`, evaluating it with global
+            /// machine interrupts disabled. Global machine interrupts are enabled when this method returns.
+            ///
+            /// # Safety
+            ///
+            /// Enabling interrupts may break critical sections.
+            #[inline]
+            pub unsafe fn wait_for_interrupt<F: FnMut() -> bool>(condition: F) {
+                $crate::aclint::CLINT::<CLINT>::wait_for_interrupt(condition);
+            }
+
+            /// Runs `f` with machine timer **AND** software interrupts disabled.
+            /// The previous state of both interrupt sources is restored afterwards, even if `f` returns early or panics.
+            #[inline]
+            pub fn with_interrupts_disabled<F: FnOnce() -> R, R>(f: F) -> R {
+                $crate::aclint::CLINT::<CLINT>::with_interrupts_disabled(f)
+            }
+
             /// Returns `true` if a machine software interrupt is pending.
             #[inline]
             pub fn mswi_is_interrupting() -> bool {
@@ -142,12 +272,30 @@ macro_rules! clint_codegen {
                 unsafe { $crate::riscv::register::mie::clear_msoft() };
             }
 
+            /// Returns `true` if machine software interrupts can be delivered to the current HART,
+            /// i.e., if both the `msoft` bit of the `mie` CSR and the `MIE` bit of the `mstatus` CSR are set.
+            #[inline]
+            pub fn mswi_deliverable() -> bool {
+                $crate::aclint::CLINT::<CLINT>::mswi_deliverable()
+            }
+
             /// Returns the `MSWI` peripheral.
             #[inline]
             pub const fn mswi() -> $crate::aclint::mswi::MSWI {
                 $crate::aclint::CLINT::<CLINT>::mswi()
             }
 
+            /// Sends an inter-processor interrupt to the HART which ID is `hart_id`.
+            /// It pends a machine software interrupt in the `msip` register of the target HART.
+            ///
+            /// # Safety
+            ///
+            /// Pending a machine software interrupt may break mask-based critical sections.
+            #[inline]
+            pub unsafe fn send_ipi<H: $crate::aclint::HartIdNumber>(hart_id: H) {
+                Self::mswi().msip(hart_id).pend();
+            }
+
             /// Returns `true` if a machine timer interrupt is pending.
             #[inline]
             pub fn mtimer_is_interrupting() -> bool {
@@ -179,6 +327,14 @@ macro_rules! clint_codegen {
                 unsafe { $crate::riscv::register::mie::clear_mtimer() };
             }
 
+            /// Returns `true` if the `MTIMECMP` register of the HART which ID is `hart_id` will deliver
+            /// a machine timer interrupt to the current HART.
+            /// `hart_id` must be the ID of the current HART.
+            #[inline]
+            pub fn mtimer_deliverable<H: $crate::aclint::HartIdNumber>(hart_id: H) -> bool {
+                $crate::aclint::CLINT::<CLINT>::mtimer_deliverable(hart_id)
+            }
+
             /// Returns the `MTIMER` peripheral.
             #[inline]
             pub const fn mtimer() -> $crate::aclint::mtimer::MTIMER {
@@ -190,6 +346,40 @@ macro_rules! clint_codegen {
             pub const fn mtime() -> $crate::aclint::mtimer::MTIME {
                 Self::mtimer().mtime
             }
+
+            /// Delay implementation for CLINT peripherals whose `MTIME` frequency (in Hz) is only known at runtime.
+            /// You can later update the frequency with the `set_freq` method of the returned `Delay`.
+            ///
+            /// # Note
+            ///
+            /// You must export the `riscv_peripheral::hal::delay::DelayNs` trait in order to use delay methods.
+            /// You must export the `riscv_peripheral::hal_async::delay::DelayNs` trait in order to use async delay methods.
+            #[inline]
+            pub const fn delay_with_freq(freq: usize) -> $crate::hal::aclint::Delay {
+                $crate::hal::aclint::Delay::new(Self::mtime(), freq)
+            }
+        }
+
+        impl $crate::InterruptController for CLINT {
+            #[inline]
+            fn is_interrupting() -> bool {
+                Self::is_interrupting()
+            }
+
+            #[inline]
+            fn is_enabled() -> bool {
+                Self::is_enabled()
+            }
+
+            #[inline]
+            unsafe fn enable() {
+                Self::enable();
+            }
+
+            #[inline]
+            fn disable() {
+                Self::disable();
+            }
         }
         $crate::clint_codegen!($($tail)*);
     };
@@ -211,6 +401,39 @@ macro_rules! clint_codegen {
             pub const fn delay() -> $crate::hal::aclint::Delay {
                 $crate::hal::aclint::Delay::new(Self::mtime(), Self::freq())
             }
+
+            /// Delay implementation for CLINT peripherals bound to the `mtimecmp` register of a given HART.
+            ///
+            /// # Note
+            ///
+            /// With the `async` feature, asynchronous delays program the `mtimecmp` register of `hart_id`
+            /// and wait for a machine timer interrupt. Thus, `hart_id` must be the current HART.
+            #[inline]
+            pub fn delay_on<H: $crate::aclint::HartIdNumber>(hart_id: H) -> $crate::hal::aclint::Delay {
+                $crate::aclint::CLINT::<CLINT>::delay_on(hart_id, Self::freq())
+            }
+
+            /// Returns the current value of the `MTIME` register as an `Instant`.
+            #[inline]
+            pub fn now() -> $crate::aclint::time::Instant<$freq> {
+                Self::mtime().now()
+            }
+        }
+        $crate::clint_codegen!($($tail)*);
+    };
+    (harts $hart:ty, $($tail:tt)*) => {
+        impl CLINT {
+            /// Returns the `mtimecmp` register for a given HART.
+            #[inline]
+            pub fn mtimecmp(hart_id: $hart) -> $crate::aclint::mtimer::MTIMECMP {
+                Self::mtimer().mtimecmp(hart_id)
+            }
+
+            /// Returns the `msip` register for a given HART.
+            #[inline]
+            pub fn msip(hart_id: $hart) -> $crate::aclint::mswi::MSIP {
+                Self::mswi().msip(hart_id)
+            }
         }
         $crate::clint_codegen!($($tail)*);
     };
@@ -245,6 +468,95 @@ macro_rules! clint_codegen {
 }
 
 /// Macro to create interfaces to PLIC peripherals in PACs.
+/// The resulting struct will be named `PLIC`, and will provide safe access to the PLIC registers.
+///
+/// This macro expects 6 different argument types:
+///
+/// - Base address (**MANDATORY**): base address of the PLIC peripheral of the target.
+/// - Per-context proxies (**OPTIONAL**): a list of context proxies for easing access to per-context registers.
+/// - Per-context threshold setters (**OPTIONAL**): a list of functions for setting the threshold of a context.
+/// - Per-context interrupt enablers (**OPTIONAL**): a list of functions for enabling interrupt sources in a context.
+/// - Interrupt type (**OPTIONAL**): the type implementing [`crate::plic::InterruptNumber`] in the target.
+///   Compilation fails if its `MAX_INTERRUPT_NUMBER` exceeds the PLIC interrupt sources region.
+/// - Number of contexts (**OPTIONAL**): the number of PLIC contexts of the target, exposed as
+///   `PLIC::NUM_CONTEXTS`, optionally followed by the type implementing [`crate::plic::ContextNumber`].
+///   Compilation fails if its `MAX_CONTEXT_NUMBER` is not less than the number of contexts.
+///
+/// # Example
+///
+/// ```
+/// use riscv_peripheral::plic_codegen;
+///
+/// /// PLIC contexts of the target
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// #[repr(u16)]
+/// pub enum Context { C0 = 0, C1 = 1 }
+///
+/// // Implement `ContextNumber` for `Context`
+/// unsafe impl riscv_peripheral::plic::ContextNumber for Context {
+///   const MAX_CONTEXT_NUMBER: u16 = 1;
+///   fn number(self) -> u16 { self as _ }
+///   fn from_number(number: u16) -> Result<Self, u16> {
+///     if number > Self::MAX_CONTEXT_NUMBER {
+///        Err(number)
+///     } else {
+///        // SAFETY: valid context number
+///        Ok(unsafe { core::mem::transmute(number) })
+///     }
+///   }
+/// }
+///
+/// plic_codegen!(
+///     base 0x0C00_0000,
+///     ctxs [ctx0 = (Context::C0, "`C0`"), ctx1 = (Context::C1, "`C1`")],
+///     ctx_thresholds [ctx0_set_threshold = (Context::C0, "`C0`"), ctx1_set_threshold = (Context::C1, "`C1`")],
+///     ctx_enables [ctx0_enable = (Context::C0, "`C0`"), ctx1_enable = (Context::C1, "`C1`")],
+///     num_contexts 2, contexts Context, // do not forget the ending comma!
+/// );
+///
+/// let priorities = PLIC::priorities(); // Priorities registers
+/// let pendings = PLIC::pendings(); // Pendings registers
+///
+/// let ctx0 = PLIC::ctx0(); // Context proxy for context 0
+/// let ctx1 = PLIC::ctx1(); // Context proxy for context 1
+/// assert_eq!(PLIC::NUM_CONTEXTS, 2);
+/// ```
+///
+/// The context type must fit the contexts of the target:
+///
+/// ```
+/// # #[derive(Clone, Copy)]
+/// # pub enum Context { C0 = 0, C1 = 1 }
+/// # unsafe impl riscv_peripheral::plic::ContextNumber for Context {
+/// #   const MAX_CONTEXT_NUMBER: u16 = 1;
+/// #   fn number(self) -> u16 { self as _ }
+/// #   fn from_number(number: u16) -> Result<Self, u16> { Err(number) }
+/// # }
+/// riscv_peripheral::plic_codegen!(base 0x0C00_0000, num_contexts 2, contexts Context,);
+/// ```
+///
+/// A context type with more contexts than the target fails to compile:
+///
+/// ```compile_fail
+/// # #[derive(Clone, Copy)]
+/// # pub enum Context { C0 = 0, C1 = 1 }
+/// # unsafe impl riscv_peripheral::plic::ContextNumber for Context {
+/// #   const MAX_CONTEXT_NUMBER: u16 = 1;
+/// #   fn number(self) -> u16 { self as _ }
+/// #   fn from_number(number: u16) -> Result<Self, u16> { Err(number) }
+/// # }
+/// riscv_peripheral::plic_codegen!(base 0x0C00_0000, num_contexts 1, contexts Context,);
+/// ```
+///
+/// The target must have at least one context:
+///
+/// ```
+/// riscv_peripheral::plic_codegen!(base 0x0C00_0000, num_contexts 1,); // compiles
+/// ```
+///
+/// ```compile_fail
+/// riscv_peripheral::plic_codegen!(base 0x0C00_0000, num_contexts 0,); // fails to compile
+/// ```
 #[macro_export]
 macro_rules! plic_codegen {
     () => {
@@ -261,7 +573,39 @@ macro_rules! plic_codegen {
             const BASE: usize = $addr;
         }
 
+        // PLIC registers are 32-bit wide
+        const _: () = $crate::common::assert_base_alignment($addr, 4);
+
         impl PLIC {
+            /// Address of the interrupt priorities block.
+            pub const PRIORITIES_ADDR: usize = $crate::plic::PLIC::<PLIC>::PRIORITIES_ADDR;
+            /// Address of the interrupt pending bits block.
+            pub const PENDINGS_ADDR: usize = $crate::plic::PLIC::<PLIC>::PENDINGS_ADDR;
+
+            /// Returns the address of the priority register of the interrupt source number `source`.
+            #[inline]
+            pub const fn priority_addr(source: u16) -> usize {
+                $crate::plic::PLIC::<PLIC>::priority_addr(source)
+            }
+
+            /// Returns the address of the interrupt enable bits block of the context number `context`.
+            #[inline]
+            pub const fn enables_addr(context: u16) -> usize {
+                $crate::plic::PLIC::<PLIC>::enables_addr(context)
+            }
+
+            /// Returns the address of the threshold register of the context number `context`.
+            #[inline]
+            pub const fn threshold_addr(context: u16) -> usize {
+                $crate::plic::PLIC::<PLIC>::threshold_addr(context)
+            }
+
+            /// Returns the address of the claim/complete register of the context number `context`.
+            #[inline]
+            pub const fn claim_addr(context: u16) -> usize {
+                $crate::plic::PLIC::<PLIC>::claim_addr(context)
+            }
+
             /// Returns `true` if a machine external interrupt is pending.
             #[inline]
             pub fn is_interrupting() -> bool {
@@ -291,6 +635,13 @@ macro_rules! plic_codegen {
                 unsafe { $crate::riscv::register::mie::clear_mext() };
             }
 
+            /// Returns `true` if machine external interrupts can be delivered to the current HART,
+            /// i.e., if both the `mext` bit of the `mie` CSR and the `MIE` bit of the `mstatus` CSR are set.
+            #[inline]
+            pub fn mext_deliverable() -> bool {
+                $crate::plic::PLIC::<PLIC>::mext_deliverable()
+            }
+
             /// Returns the priorities register of the PLIC.
             #[inline]
             pub fn priorities() -> $crate::plic::priorities::PRIORITIES {
@@ -303,11 +654,78 @@ macro_rules! plic_codegen {
                 $crate::plic::PLIC::<PLIC>::pendings()
             }
 
+            /// Returns the priority level assigned to a given interrupt source.
+            #[inline]
+            pub fn get_priority<I: $crate::plic::InterruptNumber, P: $crate::plic::PriorityNumber>(
+                source: I,
+            ) -> P {
+                Self::priorities().get_priority(source)
+            }
+
+            /// Sets the priority level of a given interrupt source.
+            ///
+            /// # Safety
+            ///
+            /// Changing the priority level can break priority-based critical sections.
+            #[inline]
+            pub unsafe fn set_priority<I: $crate::plic::InterruptNumber, P: $crate::plic::PriorityNumber>(
+                source: I,
+                priority: P,
+            ) {
+                Self::priorities().set_priority(source, priority);
+            }
+
+            /// Configures a set of interrupt sources for a context from a declarative table.
+            /// See [`PLIC::apply_config`]($crate::plic::PLIC::apply_config) for more details.
+            ///
+            /// # Safety
+            ///
+            /// Enabling interrupt sources and changing their priority levels can break
+            /// mask-based and priority-based critical sections.
+            #[inline]
+            pub unsafe fn apply_config<I: $crate::plic::InterruptNumber, P: $crate::plic::PriorityNumber>(
+                ctx: $crate::plic::CTX<Self>,
+                config: &[(I, u8, bool)],
+            ) -> Result<(), $crate::error::InvalidPriorityNumber> {
+                $crate::plic::PLIC::<PLIC>::apply_config::<I, P>(ctx, config)
+            }
+
             /// Returns the context proxy of a given PLIC context.
             #[inline]
             pub fn ctx<C: $crate::plic::ContextNumber>(context: C) -> $crate::plic::CTX<Self> {
                 $crate::plic::PLIC::<PLIC>::ctx(context)
             }
+
+            /// Returns the context proxy of the PLIC context that targets a given HART and privilege mode.
+            #[inline]
+            pub fn ctx_for<C: $crate::plic::ContextInfo>(
+                hart_id: u16,
+                mode: $crate::plic::Mode,
+            ) -> Option<$crate::plic::CTX<Self>> {
+                $crate::plic::PLIC::<PLIC>::ctx_for::<C>(hart_id, mode)
+            }
+        }
+
+        impl $crate::InterruptController for PLIC {
+            #[inline]
+            fn is_interrupting() -> bool {
+                Self::is_interrupting()
+            }
+
+            #[inline]
+            fn is_enabled() -> bool {
+                Self::is_enabled()
+            }
+
+            #[inline]
+            unsafe fn enable() {
+                Self::enable();
+            }
+
+            #[inline]
+            fn disable() {
+                Self::disable();
+            }
         }
         $crate::plic_codegen!($($tail)*);
     };
@@ -325,16 +743,79 @@ macro_rules! plic_codegen {
         }
         $crate::plic_codegen!($($tail)*);
     };
+    (ctx_thresholds [$($fn:ident = ($ctx:expr , $sctx:expr)),+], $($tail:tt)*) => {
+        impl PLIC {
+            $(
+                #[doc = "Sets the priority threshold level of context "]
+                #[doc = $sctx]
+                #[doc = "."]
+                ///
+                /// # Safety
+                ///
+                /// Changing the priority threshold can break priority-based critical sections.
+                #[inline]
+                pub unsafe fn $fn<P: $crate::plic::PriorityNumber>(threshold: P) {
+                    Self::ctx($ctx).threshold().set_threshold(threshold);
+                }
+            )*
+        }
+        $crate::plic_codegen!($($tail)*);
+    };
+    (interrupts $int:ty, $($tail:tt)*) => {
+        const _: () = $crate::plic::assert_max_interrupt::<$int>();
+        $crate::plic_codegen!($($tail)*);
+    };
+    (num_contexts $n:literal, contexts $ctx:ty, $($tail:tt)*) => {
+        const _: () = $crate::plic::assert_num_contexts::<$ctx>($n);
+        $crate::plic_codegen!(num_contexts $n, $($tail)*);
+    };
+    (num_contexts $n:literal, $($tail:tt)*) => {
+        const _: () = $crate::plic::assert_num_contexts_range($n);
+
+        impl PLIC {
+            /// Number of PLIC contexts of the target.
+            pub const NUM_CONTEXTS: u16 = $n;
+        }
+        $crate::plic_codegen!($($tail)*);
+    };
+    (ctx_enables [$($fn:ident = ($ctx:expr , $sctx:expr)),+], $($tail:tt)*) => {
+        impl PLIC {
+            $(
+                #[doc = "Enables an interrupt source for context "]
+                #[doc = $sctx]
+                #[doc = "."]
+                ///
+                /// # Safety
+                ///
+                /// Enabling an interrupt source can break mask-based critical sections.
+                #[inline]
+                pub unsafe fn $fn<I: $crate::plic::InterruptNumber>(source: I) {
+                    Self::ctx($ctx).enables().enable(source);
+                }
+            )*
+        }
+        $crate::plic_codegen!($($tail)*);
+    };
 }
 /// Macro to generate interface to interrupt configuration registers of the CLIC
+///
+/// This macro expects 2 different argument types:
+///
+/// - Base address (**MANDATORY**): base address of the CLIC peripheral of the target.
+/// - Interrupt type (**OPTIONAL**): the type implementing [`crate::clic::InterruptNumber`] in the target.
+///   Compilation fails if its `MAX_INTERRUPT_NUMBER` exceeds the CLIC interrupt control registers region.
 #[macro_export]
 macro_rules! clic_codegen {
     () => {
         #[allow(unused_imports)]
-        use CLIC as _; // assert that the PLIC struct is defined
+        use CLIC as _; // assert that the CLIC struct is defined
     };
     (base $addr:literal) => {
-        /// PLIC peripheral
+        $crate::clic_codegen!(base $addr,);
+    };
+    (base $addr:literal, $($tail:tt)*) => {
+        /// CLIC peripheral
+        #[allow(clippy::upper_case_acronyms)]
         #[derive(Clone, Copy, Debug, Eq, PartialEq)]
         pub struct CLIC;
 
@@ -342,7 +823,33 @@ macro_rules! clic_codegen {
             const BASE: usize = $addr;
         }
 
+        // CLIC interrupt configuration registers are 32-bit wide
+        const _: () = $crate::common::assert_base_alignment($addr, 4);
+
         impl CLIC {
+            /// Address of the `cliccfg` register.
+            pub const CLICCFG_ADDR: usize = $crate::clic::CLIC::<CLIC>::CLICCFG_ADDR;
+            /// Address of the interrupt control register block.
+            pub const INTERRUPTS_ADDR: usize = $crate::clic::CLIC::<CLIC>::INTERRUPTS_ADDR;
+
+            /// Returns the address of the 32-bit configuration word of the interrupt source number `source`.
+            #[inline]
+            pub const fn interrupt_addr(source: u16) -> usize {
+                $crate::clic::CLIC::<CLIC>::interrupt_addr(source)
+            }
+
+            /// Returns `true` if the HART is handling a CLIC interrupt.
+            #[inline]
+            pub fn is_interrupting() -> bool {
+                $crate::clic::CLIC::<CLIC>::is_interrupting()
+            }
+
+            /// Returns `true` if the Machine Mode Interrupt Enable bit of the `mstatus` CSR is set.
+            #[inline]
+            pub fn is_enabled() -> bool {
+                $crate::clic::CLIC::<CLIC>::is_enabled()
+            }
+
             /// Sets the Machine Mode Interrupt Enable bit of the `mstatus` CSR.
             /// When set, CLIC interrupts are effectively enabled.
             ///
@@ -375,11 +882,431 @@ macro_rules! clic_codegen {
             pub fn get_threshold() -> usize {
                 $crate::clic::CLIC::<CLIC>::get_threshold()
             }
+            /// Sets the current global interrupt threshold and returns the previous one
+            /// with a single `csrrw` instruction.
+            ///
+            /// # Safety
+            /// Changing the threshold is side-effectful and may cause an interrupt to be
+            /// inadvertently taken
+            #[inline]
+            pub unsafe fn swap_threshold(thresh: usize) -> usize {
+                $crate::clic::CLIC::<CLIC>::swap_threshold(thresh)
+            }
+
+            /// Waits for interrupts until `condition` returns `true`, evaluating it with global
+            /// machine interrupts disabled. Global machine interrupts are enabled when this method returns.
+            ///
+            /// # Safety
+            ///
+            /// Enabling interrupts may break critical sections.
+            #[inline]
+            pub unsafe fn wait_for_interrupt<F: FnMut() -> bool>(condition: F) {
+                $crate::clic::CLIC::<CLIC>::wait_for_interrupt(condition);
+            }
+
+            /// Runs `f` with the global interrupt threshold set to `thresh`.
+            /// The previous threshold is restored afterwards, even if `f` returns early or panics.
+            #[inline]
+            pub fn with_threshold<F: FnOnce() -> R, R>(thresh: usize, f: F) -> R {
+                $crate::clic::CLIC::<CLIC>::with_threshold(thresh, f)
+            }
+
+            /// Disables global machine interrupts and returns the previous global interrupt threshold
+            /// and enable state, which can be restored with [`CLIC::exit_critical`].
+            #[inline]
+            pub fn enter_critical() -> $crate::clic::CriticalState {
+                $crate::clic::CLIC::<CLIC>::enter_critical()
+            }
+
+            /// Restores the global interrupt threshold and then the global interrupt enable state.
+            ///
+            /// # Safety
+            ///
+            /// Enabling interrupts may break critical sections.
+            #[inline]
+            pub unsafe fn exit_critical(state: $crate::clic::CriticalState) {
+                $crate::clic::CLIC::<CLIC>::exit_critical(state);
+            }
+
+            /// Runs `f` with global machine interrupts disabled.
+            /// The previous threshold and enable state are restored afterwards, even if `f` panics.
+            #[inline]
+            pub fn with_critical<F: FnOnce() -> R, R>(f: F) -> R {
+                $crate::clic::CLIC::<CLIC>::with_critical(f)
+            }
             /// Returns the interrupt control register block of the CLIC
             #[inline]
-            pub fn interrupts() -> $crate::clic::interrupt::INTERRUPTS {
+            pub fn interrupts() -> $crate::clic::interrupt::INTERRUPTS<Self> {
                 $crate::clic::CLIC::<CLIC>::interrupts()
             }
         }
+
+        impl $crate::InterruptController for CLIC {
+            #[inline]
+            fn is_interrupting() -> bool {
+                Self::is_interrupting()
+            }
+
+            #[inline]
+            fn is_enabled() -> bool {
+                Self::is_enabled()
+            }
+
+            #[inline]
+            unsafe fn enable() {
+                Self::enable();
+            }
+
+            #[inline]
+            fn disable() {
+                Self::disable();
+            }
+        }
+        $crate::clic_codegen!($($tail)*);
+    };
+    (interrupts $int:ty, $($tail:tt)*) => {
+        const _: () = $crate::clic::assert_max_interrupt::<$int>();
+        $crate::clic_codegen!($($tail)*);
+    };
+}
+
+/// Macro to generate the vector table of the CLIC for the selective hardware vectoring mode.
+///
+/// The resulting table is a `static` [`crate::clic::VectorTable`] named `__VECTOR_TABLE`
+/// with one entry per interrupt source `0..=MAX_INTERRUPT_NUMBER`, placed in the
+/// `.vector_table.clic` section. The `mtvt` CSR must point to this table. Use
+/// [`crate::clic::CLIC::init_vectored`] to set `mtvt` and enable vectoring in one call.
+///
+/// This macro expects 3 different argument types:
+///
+/// - Interrupt type (**MANDATORY**): the fieldless enum implementing [`crate::clic::InterruptNumber`] in the target.
+/// - Default handler (**MANDATORY**): the handler of the interrupt sources without a dedicated handler.
+/// - Handlers (**OPTIONAL**): a list of `source => handler` entries.
+///
+/// Handlers must be `extern "C" fn()` or `unsafe extern "C" fn()` functions.
+/// Compilation fails if an entry is not a variant of the interrupt type,
+/// or if an interrupt source is assigned more than one handler.
+///
+/// # Note
+///
+/// In selective hardware vectoring mode, the HART jumps directly to the entry of the table,
+/// without going through the common trap handler. A plain `extern "C"` function neither saves
+/// the caller-saved registers of the interrupted code nor returns with `mret`. Thus, each entry
+/// must be an interrupt-ABI trampoline (e.g., written in assembly or generated by `riscv-rt`)
+/// that saves the caller-saved registers, calls the actual handler, restores the registers,
+/// and returns with `mret`. The `extern "C" fn()` type only describes the address of the entry.
+///
+/// # Example
+///
+/// ```
+/// use riscv_peripheral::clic_vector_table;
+///
+/// /// Interrupts of the target CLIC
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// #[repr(u16)]
+/// pub enum Interrupt { I1 = 1, UART0 = 2 }
+///
+/// unsafe impl riscv_peripheral::clic::InterruptNumber for Interrupt {
+///   const MAX_INTERRUPT_NUMBER: u16 = 2;
+///   fn number(self) -> u16 { self as _ }
+///   fn from_number(number: u16) -> Result<Self, u16> {
+///     match number {
+///       1 => Ok(Self::I1),
+///       2 => Ok(Self::UART0),
+///       _ => Err(number),
+///     }
+///   }
+/// }
+///
+/// // interrupt-ABI trampolines that wrap the actual handlers and return with `mret`
+/// # #[cfg(any())]
+/// extern "C" {
+///     fn default_trampoline();
+///     fn uart0_trampoline();
+/// }
+/// # unsafe extern "C" fn default_trampoline() {}
+/// # unsafe extern "C" fn uart0_trampoline() {}
+///
+/// clic_vector_table!(
+///     interrupts Interrupt,
+///     default default_trampoline,
+///     handlers [Interrupt::UART0 => uart0_trampoline], // do not forget the ending comma!
+/// );
+///
+/// assert_eq!(__VECTOR_TABLE.len(), 3);
+/// assert_eq!(__VECTOR_TABLE.0[2] as usize, uart0_trampoline as usize);
+/// ```
+///
+/// Assigning more than one handler to the same interrupt source fails to compile:
+///
+/// ```compile_fail
+/// # use riscv_peripheral::clic_vector_table;
+/// # #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// # #[repr(u16)]
+/// # pub enum Interrupt { I1 = 1, UART0 = 2 }
+/// # unsafe impl riscv_peripheral::clic::InterruptNumber for Interrupt {
+/// #   const MAX_INTERRUPT_NUMBER: u16 = 2;
+/// #   fn number(self) -> u16 { self as _ }
+/// #   fn from_number(number: u16) -> Result<Self, u16> { Err(number) }
+/// # }
+/// # extern "C" fn default_handler() {}
+/// # extern "C" fn uart0_handler() {}
+/// clic_vector_table!(
+///     interrupts Interrupt,
+///     default default_handler,
+///     handlers [Interrupt::UART0 => uart0_handler, Interrupt::UART0 => default_handler],
+/// );
+/// ```
+#[macro_export]
+macro_rules! clic_vector_table {
+    (interrupts $int:ty, default $default:path, $(handlers [$($source:expr => $handler:path),* $(,)?],)?) => {
+        const _: () = $crate::clic::assert_max_interrupt::<$int>();
+
+        /// Vector table of the CLIC
+        #[no_mangle]
+        #[link_section = ".vector_table.clic"]
+        pub static __VECTOR_TABLE: $crate::clic::VectorTable<
+            { <$int as $crate::clic::InterruptNumber>::MAX_INTERRUPT_NUMBER as usize + 1 },
+        > = {
+            const N: usize = <$int as $crate::clic::InterruptNumber>::MAX_INTERRUPT_NUMBER as usize + 1;
+            #[allow(unused_mut)]
+            let mut table = [$default as unsafe extern "C" fn(); N];
+            #[allow(unused_mut, unused_variables)]
+            let mut assigned = [false; N];
+            $($(
+                let source: $int = $source;
+                let index = source as usize;
+                if assigned[index] {
+                    panic!("interrupt source assigned to more than one handler");
+                }
+                assigned[index] = true;
+                table[index] = $handler as unsafe extern "C" fn();
+            )*)?
+            $crate::clic::VectorTable(table)
+        };
+    };
+}
+
+/// Macro to generate enums of interrupt sources, priority levels, contexts, or HART IDs in PACs.
+///
+/// The resulting enum implements the number trait given as first argument, and
+/// [`core::convert::TryFrom`] for the corresponding raw number type with the same error
+/// as the `try_from_number` method of the trait. Thus, it interoperates with generic code
+/// that relies on the standard conversion traits. The highest number of the trait
+/// (e.g., `MAX_INTERRUPT_NUMBER`) is computed from the variants. For interrupt sources,
+/// the `name` method of the trait returns the name of the variant (e.g., `"UART0"`).
+///
+/// The requirements of the trait that do not follow from the enum definition are checked at
+/// compile time: priority levels must include the number 0 (i.e., never interrupt), PLIC
+/// interrupt sources must not use the reserved number 0, and the numbers of PLIC interrupt
+/// sources, PLIC contexts, and CLIC interrupt sources must not exceed the maximum allowed by
+/// the corresponding specification. Otherwise, the enum fails to compile:
+///
+/// ```compile_fail
+/// riscv_peripheral::generate_interrupt_enum!(plic::PriorityNumber,
+///     pub enum Priority { P1 = 1, P2 = 2 } // missing P0 = 0
+/// );
+/// ```
+///
+/// ```compile_fail
+/// riscv_peripheral::generate_interrupt_enum!(plic::InterruptNumber,
+///     pub enum Interrupt { I0 = 0, I1 = 1 } // 0 is reserved in the PLIC
+/// );
+/// ```
+///
+/// The supported traits are:
+///
+/// | Trait                     | Raw number type | Error type                                  |
+/// |---------------------------|-----------------|---------------------------------------------|
+/// | `aclint::HartIdNumber`    | `u16`           | [`crate::error::InvalidHartId`]             |
+/// | `clic::InterruptNumber`   | `u16`           | [`crate::error::InvalidInterruptNumber`]    |
+/// | `clic::PriorityNumber`    | `u8`            | [`crate::error::InvalidPriorityNumber`]     |
+/// | `plic::InterruptNumber`   | `u16`           | [`crate::error::InvalidInterruptNumber`]    |
+/// | `plic::PriorityNumber`    | `u8`            | [`crate::error::InvalidPriorityNumber`]     |
+/// | `plic::ContextNumber`     | `u16`           | [`crate::error::InvalidContextNumber`]      |
+///
+/// # Example
+///
+/// ```
+/// use core::convert::TryFrom;
+/// use riscv_peripheral::{generate_interrupt_enum, plic::InterruptNumber};
+///
+/// generate_interrupt_enum!(plic::InterruptNumber,
+///     /// External interrupts of the target
+///     pub enum Interrupt {
+///         /// UART interrupt
+///         UART0 = 1,
+///         GPIO = 3,
+///     }
+/// );
+///
+/// generate_interrupt_enum!(plic::PriorityNumber,
+///     /// Priority levels of the target
+///     pub enum Priority { P0 = 0, P1 = 1, P2 = 2 }
+/// );
+///
+/// assert_eq!(Interrupt::MAX_INTERRUPT_NUMBER, 3);
+/// assert_eq!(Interrupt::try_from(3), Ok(Interrupt::GPIO));
+/// assert!(Interrupt::try_from(2).is_err());
+/// assert_eq!(Priority::try_from(1), Ok(Priority::P1));
+///
+/// // generic code relying on the standard conversion traits
+/// fn parse<T: TryFrom<u16>>(value: u16) -> Option<T> {
+///     T::try_from(value).ok()
+/// }
+/// assert_eq!(parse::<Interrupt>(1), Some(Interrupt::UART0));
+///
+/// // interrupt sources are named after their variants for logging
+/// assert_eq!(Interrupt::GPIO.name(), Some("GPIO"));
+/// assert_eq!(format!("{}", Interrupt::UART0.display_name()), "UART0");
+///
+/// // generated enums can iterate over their variants, skipping unused numbers
+/// generate_interrupt_enum!(plic::ContextNumber,
+///     pub enum Context { C1 = 1, C2 = 2, C5 = 5 }
+/// );
+/// assert!(Context::all().eq([Context::C1, Context::C2, Context::C5]));
+/// assert_eq!(Interrupt::VARIANTS, &[Interrupt::UART0, Interrupt::GPIO]);
+///
+/// // generated enums derive `Hash`, and `plic::Source` sorts them by number
+/// use riscv_peripheral::plic::Source;
+/// use std::collections::{BTreeSet, HashSet};
+/// let hashed: HashSet<Interrupt> = [Interrupt::GPIO, Interrupt::UART0].into_iter().collect();
+/// assert!(hashed.contains(&Interrupt::GPIO));
+/// let sorted: BTreeSet<Source<Interrupt>> = hashed.into_iter().map(Source).collect();
+/// assert_eq!(sorted.first().map(|s| s.get()), Some(Interrupt::UART0));
+/// ```
+#[macro_export]
+macro_rules! generate_interrupt_enum {
+    (aclint::HartIdNumber, $($tail:tt)*) => {
+        $crate::generate_interrupt_enum!(@impl aclint::HartIdNumber, false, u16, MAX_HART_ID_NUMBER, InvalidHartId, $($tail)*);
+    };
+    (clic::InterruptNumber, $($tail:tt)*) => {
+        $crate::generate_interrupt_enum!(@impl clic::InterruptNumber, true, u16, MAX_INTERRUPT_NUMBER, InvalidInterruptNumber, $($tail)*);
+    };
+    (clic::PriorityNumber, $($tail:tt)*) => {
+        $crate::generate_interrupt_enum!(@impl clic::PriorityNumber, false, u8, MAX_PRIORITY_NUMBER, InvalidPriorityNumber, $($tail)*);
+    };
+    (plic::InterruptNumber, $($tail:tt)*) => {
+        $crate::generate_interrupt_enum!(@impl plic::InterruptNumber, true, u16, MAX_INTERRUPT_NUMBER, InvalidInterruptNumber, $($tail)*);
+    };
+    (plic::PriorityNumber, $($tail:tt)*) => {
+        $crate::generate_interrupt_enum!(@impl plic::PriorityNumber, false, u8, MAX_PRIORITY_NUMBER, InvalidPriorityNumber, $($tail)*);
+    };
+    (plic::ContextNumber, $($tail:tt)*) => {
+        $crate::generate_interrupt_enum!(@impl plic::ContextNumber, false, u16, MAX_CONTEXT_NUMBER, InvalidContextNumber, $($tail)*);
+    };
+    (@name true, $($variant:ident),+) => {
+        #[inline]
+        fn name(self) -> Option<&'static str> {
+            match self {
+                $(Self::$variant => Some(stringify!($variant)),)+
+            }
+        }
+    };
+    (@name false, $($variant:ident),+) => {};
+    (@has_zero $repr:ident, $($value:literal),+) => {{
+        let values: &[$repr] = &[$($value),+];
+        let mut i = 0;
+        while i < values.len() && values[i] != 0 {
+            i += 1;
+        }
+        i < values.len()
+    }};
+    (@check plic::InterruptNumber, $name:ident, $repr:ident, $($value:literal),+) => {
+        const _: () = assert!(
+            !$crate::generate_interrupt_enum!(@has_zero $repr, $($value),+),
+            "PLIC interrupt number 0 is reserved"
+        );
+        const _: () = assert!(
+            <$name as $crate::plic::InterruptNumber>::MAX_INTERRUPT_NUMBER <= $crate::plic::MAX_INTERRUPT_NUMBER,
+            "PLIC interrupt numbers must be less than or equal to 1023"
+        );
+    };
+    (@check plic::ContextNumber, $name:ident, $repr:ident, $($value:literal),+) => {
+        const _: () = assert!(
+            <$name as $crate::plic::ContextNumber>::MAX_CONTEXT_NUMBER <= $crate::plic::MAX_CONTEXT_NUMBER,
+            "PLIC context numbers must be less than or equal to 15871"
+        );
+    };
+    (@check clic::InterruptNumber, $name:ident, $repr:ident, $($value:literal),+) => {
+        const _: () = $crate::clic::assert_max_interrupt::<$name>();
+    };
+    (@check $module:ident::PriorityNumber, $name:ident, $repr:ident, $($value:literal),+) => {
+        const _: () = assert!(
+            $crate::generate_interrupt_enum!(@has_zero $repr, $($value),+),
+            "priority number 0 (i.e., never interrupt) is required"
+        );
+    };
+    (@check $module:ident::$trait:ident, $name:ident, $repr:ident, $($value:literal),+) => {};
+    (@impl $module:ident::$trait:ident, $named:tt, $repr:ident, $max:ident, $error:ident,
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident = $value:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+        #[repr($repr)]
+        $vis enum $name {
+            $($(#[$vmeta])* $variant = $value),+
+        }
+
+        $crate::generate_interrupt_enum!(@check $module::$trait, $name, $repr, $($value),+);
+
+        // SAFETY: the compiler guarantees that all the variants are distinct,
+        // the highest number is computed from the variants,
+        // and the remaining requirements of the trait are checked at compile time
+        unsafe impl $crate::$module::$trait for $name {
+            const $max: $repr = {
+                let values: &[$repr] = &[$($value),+];
+                let mut max = 0;
+                let mut i = 0;
+                while i < values.len() {
+                    if values[i] > max {
+                        max = values[i];
+                    }
+                    i += 1;
+                }
+                max
+            };
+
+            #[inline]
+            fn number(self) -> $repr {
+                self as _
+            }
+
+            #[inline]
+            fn from_number(value: $repr) -> Result<Self, $repr> {
+                match value {
+                    $($value => Ok(Self::$variant),)+
+                    _ => Err(value),
+                }
+            }
+
+            $crate::generate_interrupt_enum!(@name $named, $($variant),+);
+        }
+
+        impl $name {
+            /// All the variants of the enum, in declaration order.
+            pub const VARIANTS: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Returns an iterator over all the variants of the enum, in declaration order.
+            ///
+            /// Unlike iterating over numbers with `from_number`, it skips the numbers
+            /// that do not correspond to any variant without attempting any conversion.
+            #[inline]
+            pub fn all() -> impl Iterator<Item = Self> {
+                Self::VARIANTS.iter().copied()
+            }
+        }
+
+        impl core::convert::TryFrom<$repr> for $name {
+            type Error = $crate::error::$error;
+
+            #[inline]
+            fn try_from(value: $repr) -> Result<Self, Self::Error> {
+                <Self as $crate::$module::$trait>::try_from_number(value)
+            }
+        }
     };
 }
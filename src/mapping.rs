@@ -0,0 +1,126 @@
+//! Mapping of interrupt sources between the CLIC and the PLIC.
+//!
+//! On targets with both controllers, the same device may be assigned different
+//! interrupt numbers in each controller (e.g., external interrupts of the CLIC start
+//! after the core-local ones). The [`InterruptMapping`] trait allows converting
+//! interrupt sources between the interrupt number enums of both controllers.
+
+use crate::{clic, plic};
+
+/// Trait for mapping interrupt sources between the CLIC and the PLIC of a target.
+///
+/// By default, the mapping is a fixed offset: the CLIC number of an interrupt source is
+/// its PLIC number plus [`InterruptMapping::CLIC_OFFSET`]. For irregular mappings,
+/// override [`InterruptMapping::map_to_clic`] and [`InterruptMapping::map_to_plic`].
+///
+/// # Example
+///
+/// ```
+/// use riscv_peripheral::mapping::InterruptMapping;
+/// # use riscv_peripheral::{clic, plic};
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # pub struct ClicInterrupt(u16);
+/// # unsafe impl clic::InterruptNumber for ClicInterrupt {
+/// #     const MAX_INTERRUPT_NUMBER: u16 = 31;
+/// #     fn number(self) -> u16 { self.0 }
+/// #     fn from_number(n: u16) -> Result<Self, u16> { if n <= 31 { Ok(Self(n)) } else { Err(n) } }
+/// # }
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # pub struct PlicInterrupt(u16);
+/// # unsafe impl plic::InterruptNumber for PlicInterrupt {
+/// #     const MAX_INTERRUPT_NUMBER: u16 = 15;
+/// #     fn number(self) -> u16 { self.0 }
+/// #     fn from_number(n: u16) -> Result<Self, u16> { if (1..=15).contains(&n) { Ok(Self(n)) } else { Err(n) } }
+/// # }
+///
+/// /// External interrupts start at CLIC interrupt 16.
+/// struct Mapping;
+///
+/// impl InterruptMapping for Mapping {
+///     type Clic = ClicInterrupt;
+///     type Plic = PlicInterrupt;
+///     const CLIC_OFFSET: u16 = 15;
+/// }
+///
+/// assert_eq!(Mapping::map_to_clic(PlicInterrupt(1)), Some(ClicInterrupt(16)));
+/// assert_eq!(Mapping::map_to_plic(ClicInterrupt(30)), Some(PlicInterrupt(15)));
+/// assert_eq!(Mapping::map_to_plic(ClicInterrupt(3)), None);
+/// ```
+pub trait InterruptMapping {
+    /// Interrupt number enum of the CLIC.
+    type Clic: clic::InterruptNumber;
+    /// Interrupt number enum of the PLIC.
+    type Plic: plic::InterruptNumber;
+
+    /// Offset between the CLIC and the PLIC numbers of the same interrupt source.
+    const CLIC_OFFSET: u16 = 0;
+
+    /// Converts a PLIC interrupt source to its CLIC counterpart.
+    /// If the source is not connected to the CLIC, it returns [`None`].
+    #[inline]
+    fn map_to_clic(source: Self::Plic) -> Option<Self::Clic> {
+        use clic::InterruptNumber;
+        let number = plic::InterruptNumber::number(source).checked_add(Self::CLIC_OFFSET)?;
+        Self::Clic::try_from_number_opt(number)
+    }
+
+    /// Converts a CLIC interrupt source to its PLIC counterpart.
+    /// If the source is not connected to the PLIC, it returns [`None`].
+    #[inline]
+    fn map_to_plic(source: Self::Clic) -> Option<Self::Plic> {
+        use plic::InterruptNumber;
+        let number = clic::InterruptNumber::number(source).checked_sub(Self::CLIC_OFFSET)?;
+        Self::Plic::try_from_number_opt(number)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{clic::test::Interrupt as ClicInterrupt, plic::test::Interrupt as PlicInterrupt};
+
+    struct Identity;
+
+    impl InterruptMapping for Identity {
+        type Clic = ClicInterrupt;
+        type Plic = PlicInterrupt;
+    }
+
+    struct Offset;
+
+    impl InterruptMapping for Offset {
+        type Clic = ClicInterrupt;
+        type Plic = PlicInterrupt;
+        const CLIC_OFFSET: u16 = 2;
+    }
+
+    #[test]
+    fn check_identity() {
+        assert_eq!(
+            Identity::map_to_clic(PlicInterrupt::I3),
+            Some(ClicInterrupt::I3)
+        );
+        assert_eq!(
+            Identity::map_to_plic(ClicInterrupt::I4),
+            Some(PlicInterrupt::I4)
+        );
+    }
+
+    #[test]
+    fn check_offset() {
+        assert_eq!(
+            Offset::map_to_clic(PlicInterrupt::I1),
+            Some(ClicInterrupt::I3)
+        );
+        assert_eq!(
+            Offset::map_to_clic(PlicInterrupt::I2),
+            Some(ClicInterrupt::I4)
+        );
+        assert_eq!(Offset::map_to_clic(PlicInterrupt::I3), None);
+        assert_eq!(
+            Offset::map_to_plic(ClicInterrupt::I3),
+            Some(PlicInterrupt::I1)
+        );
+        assert_eq!(Offset::map_to_plic(ClicInterrupt::I2), None);
+    }
+}
@@ -8,6 +8,108 @@ pub mod pendings;
 pub mod priorities;
 pub mod threshold;
 
+/// Highest interrupt source number allowed by the PLIC specification.
+pub const MAX_INTERRUPT_NUMBER: u16 = 1023;
+
+/// Asserts that the interrupt numbers of `I` fit in the PLIC interrupt sources region.
+///
+/// # Note
+///
+/// This function is intended to be used in `const` contexts, turning an invalid
+/// `I::MAX_INTERRUPT_NUMBER` into a compilation error:
+///
+/// ```compile_fail
+/// # use riscv_peripheral::plic::{assert_max_interrupt, InterruptNumber};
+/// # #[derive(Clone, Copy)]
+/// # struct Interrupt;
+/// unsafe impl InterruptNumber for Interrupt {
+///     const MAX_INTERRUPT_NUMBER: u16 = 1024;
+///     // ...
+/// #   fn number(self) -> u16 { 1024 }
+/// #   fn from_number(value: u16) -> Result<Self, u16> { Err(value) }
+/// }
+///
+/// const _: () = assert_max_interrupt::<Interrupt>(); // fails to compile
+/// ```
+#[inline]
+pub const fn assert_max_interrupt<I: InterruptNumber>() {
+    assert!(
+        I::MAX_INTERRUPT_NUMBER <= MAX_INTERRUPT_NUMBER,
+        "MAX_INTERRUPT_NUMBER exceeds the PLIC interrupt sources region"
+    );
+}
+
+/// Highest context number allowed by the PLIC specification.
+pub const MAX_CONTEXT_NUMBER: u16 = 15871;
+
+/// Asserts that the target has between 1 and `MAX_CONTEXT_NUMBER + 1` contexts.
+///
+/// # Note
+///
+/// This function is intended to be used in `const` contexts, turning an invalid
+/// number of contexts into a compilation error:
+///
+/// ```
+/// # use riscv_peripheral::plic::assert_num_contexts_range;
+/// const _: () = assert_num_contexts_range(1); // compiles
+/// ```
+///
+/// ```compile_fail
+/// # use riscv_peripheral::plic::assert_num_contexts_range;
+/// const _: () = assert_num_contexts_range(0); // fails to compile
+/// ```
+#[inline]
+pub const fn assert_num_contexts_range(num_contexts: u16) {
+    assert!(
+        num_contexts >= 1 && num_contexts <= MAX_CONTEXT_NUMBER + 1,
+        "the number of PLIC contexts must be between 1 and 15872"
+    );
+}
+
+/// Asserts that the target has between 1 and `MAX_CONTEXT_NUMBER + 1` contexts, and that
+/// all the context numbers of `C` are less than `num_contexts`.
+///
+/// # Note
+///
+/// This function is intended to be used in `const` contexts, turning an invalid
+/// `C::MAX_CONTEXT_NUMBER` into a compilation error:
+///
+/// ```
+/// # use riscv_peripheral::plic::{assert_num_contexts, ContextNumber};
+/// # #[derive(Clone, Copy)]
+/// # struct Context;
+/// unsafe impl ContextNumber for Context {
+///     const MAX_CONTEXT_NUMBER: u16 = 2;
+///     // ...
+/// #   fn number(self) -> u16 { 2 }
+/// #   fn from_number(value: u16) -> Result<Self, u16> { Err(value) }
+/// }
+///
+/// const _: () = assert_num_contexts::<Context>(3); // compiles
+/// ```
+///
+/// ```compile_fail
+/// # use riscv_peripheral::plic::{assert_num_contexts, ContextNumber};
+/// # #[derive(Clone, Copy)]
+/// # struct Context;
+/// unsafe impl ContextNumber for Context {
+///     const MAX_CONTEXT_NUMBER: u16 = 2;
+///     // ...
+/// #   fn number(self) -> u16 { 2 }
+/// #   fn from_number(value: u16) -> Result<Self, u16> { Err(value) }
+/// }
+///
+/// const _: () = assert_num_contexts::<Context>(2); // fails to compile
+/// ```
+#[inline]
+pub const fn assert_num_contexts<C: ContextNumber>(num_contexts: u16) {
+    assert_num_contexts_range(num_contexts);
+    assert!(
+        C::MAX_CONTEXT_NUMBER < num_contexts,
+        "MAX_CONTEXT_NUMBER exceeds the number of PLIC contexts"
+    );
+}
+
 /// Trait for enums of interrupt numbers.
 ///
 /// This trait should be implemented by a peripheral access crate (PAC)
@@ -17,6 +119,15 @@ pub mod threshold;
 /// # Note
 ///
 /// Recall that the interrupt number `0` is reserved as "no interrupt".
+/// Claiming an interrupt returns [`None`] instead of `0`, and, in debug builds,
+/// the accessors of the PLIC registers panic if `number` returns `0`.
+///
+/// # Map keys
+///
+/// To use interrupt sources as keys of hash maps (e.g., in host-side tools), PACs should
+/// derive `Hash` along with `Eq` and `PartialEq`, as [`crate::generate_interrupt_enum!`] does.
+/// Avoid deriving `Ord` on the enum: derived comparisons follow the declaration order of the
+/// variants, not their numbers. Instead, wrap the sources in [`Source`] to sort them by number.
 ///
 /// # Safety
 ///
@@ -36,6 +147,51 @@ pub unsafe trait InterruptNumber: Copy {
     /// Tries to convert a number to a valid interrupt source.
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u16) -> Result<Self, u16>;
+
+    /// Tries to convert a number to a valid interrupt source.
+    /// If the conversion fails, it returns a [`crate::error::InvalidInterruptNumber`] error.
+    #[inline]
+    fn try_from_number(value: u16) -> Result<Self, crate::error::InvalidInterruptNumber> {
+        Self::from_number(value).map_err(crate::error::InvalidInterruptNumber)
+    }
+
+    /// Tries to convert a number to a valid interrupt source.
+    /// If the conversion fails, it returns [`None`].
+    #[inline]
+    fn try_from_number_opt(value: u16) -> Option<Self> {
+        Self::from_number(value).ok()
+    }
+
+    /// Converts a number to a valid interrupt source, saturating it to `MAX_INTERRUPT_NUMBER`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the saturated number is not a valid interrupt source.
+    #[inline]
+    fn from_number_saturating(value: u16) -> Self {
+        match Self::from_number(value.min(Self::MAX_INTERRUPT_NUMBER)) {
+            Ok(v) => v,
+            Err(n) => panic!("invalid interrupt source number: {}", n),
+        }
+    }
+
+    /// Returns the name of an interrupt source (e.g., `UART0`), if any.
+    ///
+    /// By default, it returns [`None`]. PACs may override it to provide device-specific names.
+    #[inline]
+    fn name(self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns a human-readable name of an interrupt source for logging.
+    /// It formats as [`Self::name`] or, if there is none, as `IRQ` followed by the interrupt number.
+    #[inline]
+    fn display_name(self) -> crate::common::InterruptName {
+        crate::common::InterruptName {
+            number: self.number(),
+            name: self.name(),
+        }
+    }
 }
 
 /// Trait for enums of priority levels.
@@ -67,6 +223,33 @@ pub unsafe trait PriorityNumber: Copy {
     /// Tries to convert a number to a valid priority level.
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u8) -> Result<Self, u8>;
+
+    /// Tries to convert a number to a valid priority level.
+    /// If the conversion fails, it returns a [`crate::error::InvalidPriorityNumber`] error.
+    #[inline]
+    fn try_from_number(value: u8) -> Result<Self, crate::error::InvalidPriorityNumber> {
+        Self::from_number(value).map_err(crate::error::InvalidPriorityNumber)
+    }
+
+    /// Tries to convert a number to a valid priority level.
+    /// If the conversion fails, it returns [`None`].
+    #[inline]
+    fn try_from_number_opt(value: u8) -> Option<Self> {
+        Self::from_number(value).ok()
+    }
+
+    /// Converts a number to a valid priority level, saturating it to `MAX_PRIORITY_NUMBER`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the saturated number is not a valid priority level.
+    #[inline]
+    fn from_number_saturating(value: u8) -> Self {
+        match Self::from_number(value.min(Self::MAX_PRIORITY_NUMBER)) {
+            Ok(v) => v,
+            Err(n) => panic!("invalid priority level number: {}", n),
+        }
+    }
 }
 
 /// Trait for enums of PLIC contexts.
@@ -93,17 +276,134 @@ pub unsafe trait ContextNumber: Copy {
     /// Tries to convert a number to a valid context.
     /// If the conversion fails, it returns an error with the number back.
     fn from_number(value: u16) -> Result<Self, u16>;
+
+    /// Tries to convert a number to a valid context.
+    /// If the conversion fails, it returns a [`crate::error::InvalidContextNumber`] error.
+    #[inline]
+    fn try_from_number(value: u16) -> Result<Self, crate::error::InvalidContextNumber> {
+        Self::from_number(value).map_err(crate::error::InvalidContextNumber)
+    }
+
+    /// Tries to convert a number to a valid context.
+    /// If the conversion fails, it returns [`None`].
+    #[inline]
+    fn try_from_number_opt(value: u16) -> Option<Self> {
+        Self::from_number(value).ok()
+    }
+
+    /// Converts a number to a valid context, saturating it to `MAX_CONTEXT_NUMBER`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the saturated number is not a valid context.
+    #[inline]
+    fn from_number_saturating(value: u16) -> Self {
+        match Self::from_number(value.min(Self::MAX_CONTEXT_NUMBER)) {
+            Ok(v) => v,
+            Err(n) => panic!("invalid context number: {}", n),
+        }
+    }
 }
 
+/// Privilege mode targeted by a PLIC context.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mode {
+    /// User mode.
+    User,
+    /// Supervisor mode.
+    Supervisor,
+    /// Machine mode.
+    Machine,
+}
+
+/// Trait for enums of PLIC contexts that know which HART and privilege mode they target.
+///
+/// This trait can be implemented by a peripheral access crate (PAC) on top of [`ContextNumber`]
+/// to find the context of a given HART and privilege mode without hardcoding context numbers.
+///
+/// # Safety
+///
+/// * Each context must report the HART ID and privilege mode it actually targets.
+/// * No two contexts may target the same HART ID and privilege mode.
+pub unsafe trait ContextInfo: ContextNumber {
+    /// Returns the ID of the HART targeted by the context.
+    fn hart_id(self) -> u16;
+
+    /// Returns the privilege mode targeted by the context.
+    fn mode(self) -> Mode;
+
+    /// Returns `true` if the context targets the current HART, as reported by the `mhartid` CSR.
+    ///
+    /// # Note
+    ///
+    /// Usually, each HART is targeted by several contexts (e.g., one for machine mode and another
+    /// for supervisor mode). This method returns `true` for all of them. If you need to configure
+    /// the context of a specific privilege mode, check [`ContextInfo::mode`] too, or use
+    /// [`ContextInfo::context_for`].
+    #[inline]
+    fn is_current_hart(self) -> bool {
+        self.hart_id() as usize == crate::csr::mhartid::read()
+    }
+
+    /// Returns the context that targets a given HART and privilege mode.
+    /// If no context targets them, it returns [`None`].
+    #[inline]
+    fn context_for(hart_id: u16, mode: Mode) -> Option<Self> {
+        (0..=Self::MAX_CONTEXT_NUMBER)
+            .filter_map(Self::try_from_number_opt)
+            .find(|ctx| ctx.hart_id() == hart_id && ctx.mode() == mode)
+    }
+}
+
+crate::common::ordered_number!(
+    Priority,
+    PriorityNumber,
+    "Priority level of a PLIC interrupt source that can be compared with other priority levels."
+);
+
+crate::common::ordered_number!(
+    Source,
+    InterruptNumber,
+    "PLIC interrupt source that can be compared with other interrupt sources."
+);
+
 /// Trait for a PLIC peripheral.
 ///
 /// # Safety
 ///
 /// * This trait must only be implemented on a PAC of a target with a PLIC peripheral.
 /// * The PLIC peripheral base address `BASE` must be valid for the target device.
+///
+/// # Memory map
+///
+/// By default, the PLIC follows the layout of the PLIC specification.
+/// PLIC variants with a different layout can override the offsets and strides:
+///
+/// | Register    | Offset from `BASE`                         | Default value |
+/// |-------------|--------------------------------------------|---------------|
+/// | Priorities  | `PRIORITIES_OFFSET + 4 * source`           | `0x0000`      |
+/// | Pendings    | `PENDING_OFFSET`                           | `0x1000`      |
+/// | Enables     | `ENABLES_OFFSET + ENABLES_STRIDE * ctx`    | `0x2000`      |
+/// | Threshold   | `CONTEXT_BASE + CONTEXT_STRIDE * ctx`      | `0x20_0000`   |
+/// | Claim       | `CONTEXT_BASE + CONTEXT_STRIDE * ctx + 4`  | `0x20_0004`   |
+///
+/// The default strides are `0x80` for `ENABLES_STRIDE` and `0x1000` for `CONTEXT_STRIDE`.
 pub unsafe trait Plic: Copy {
     /// Base address of the PLIC peripheral.
     const BASE: usize;
+    /// Offset of the interrupt priorities block relative to `BASE`.
+    const PRIORITIES_OFFSET: usize = 0;
+    /// Offset of the interrupt pending bits block relative to `BASE`.
+    const PENDING_OFFSET: usize = 0x1000;
+    /// Offset of the interrupt enable bits block of context 0 relative to `BASE`.
+    const ENABLES_OFFSET: usize = 0x2000;
+    /// Separation between the interrupt enable bits blocks of consecutive contexts.
+    const ENABLES_STRIDE: usize = 0x80;
+    /// Offset of the threshold and claim/complete block of context 0 relative to `BASE`.
+    const CONTEXT_BASE: usize = 0x20_0000;
+    /// Separation between the threshold and claim/complete blocks of consecutive contexts.
+    const CONTEXT_STRIDE: usize = 0x1000;
 }
 
 /// Platform-Level Interrupt Controler (PLIC) peripheral.
@@ -123,25 +423,94 @@ pub struct PLIC<P: Plic> {
 }
 
 impl<P: Plic> PLIC<P> {
-    const PRIORITIES_OFFSET: usize = 0;
+    /// Address of the interrupt priorities block.
+    pub const PRIORITIES_ADDR: usize = P::BASE + P::PRIORITIES_OFFSET;
+    /// Address of the interrupt pending bits block.
+    pub const PENDINGS_ADDR: usize = P::BASE + P::PENDING_OFFSET;
 
-    const PENDINGS_OFFSET: usize = 0x1000;
+    /// Creates a new PLIC proxy. It can be used in `const` contexts.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns the address of the priority register of the interrupt source number `source`.
+    #[inline]
+    pub const fn priority_addr(source: u16) -> usize {
+        Self::PRIORITIES_ADDR + 4 * source as usize
+    }
+
+    /// Returns the address of the interrupt enable bits block of the context number `context`.
+    #[inline]
+    pub const fn enables_addr(context: u16) -> usize {
+        P::BASE + P::ENABLES_OFFSET + context as usize * P::ENABLES_STRIDE
+    }
+
+    /// Returns the address of the threshold register of the context number `context`.
+    #[inline]
+    pub const fn threshold_addr(context: u16) -> usize {
+        P::BASE
+            + P::CONTEXT_BASE
+            + context as usize * P::CONTEXT_STRIDE
+            + CTX::<P>::THRESHOLD_OFFSET
+    }
+
+    /// Returns the address of the claim/complete register of the context number `context`.
+    #[inline]
+    pub const fn claim_addr(context: u16) -> usize {
+        P::BASE + P::CONTEXT_BASE + context as usize * P::CONTEXT_STRIDE + CTX::<P>::CLAIM_OFFSET
+    }
 
     /// Returns the priorities register of the PLIC.
     /// This register allows to set the priority level of each interrupt source.
     /// The priority level of each interrupt source is shared among all the contexts.
     #[inline]
-    pub fn priorities() -> priorities::PRIORITIES {
+    pub const fn priorities() -> priorities::PRIORITIES {
         // SAFETY: valid address
-        unsafe { priorities::PRIORITIES::new(P::BASE + Self::PRIORITIES_OFFSET) }
+        unsafe { priorities::PRIORITIES::new(Self::PRIORITIES_ADDR) }
+    }
+
+    /// Returns `true` if machine external interrupts can be delivered to the current HART,
+    /// i.e., if both the `mext` bit of the `mie` CSR and the `MIE` bit of the `mstatus` CSR are set.
+    ///
+    /// # Note
+    ///
+    /// This method is intended for debugging the interrupt setup. The PLIC only delivers
+    /// interrupt sources that are enabled for the context of the HART and whose priority
+    /// is greater than the threshold of the context, which this method does not check.
+    #[inline]
+    pub fn mext_deliverable() -> bool {
+        crate::csr::mstatus::mie() && crate::csr::mie::mext()
     }
 
     /// Returns the pendings register of the PLIC.
     /// This register allows to check if a particular interrupt source is pending.
     #[inline]
-    pub fn pendings() -> pendings::PENDINGS {
+    pub const fn pendings() -> pendings::PENDINGS {
         // SAFETY: valid address
-        unsafe { pendings::PENDINGS::new(P::BASE + Self::PENDINGS_OFFSET) }
+        unsafe { pendings::PENDINGS::new(Self::PENDINGS_ADDR) }
+    }
+
+    /// Configures a set of interrupt sources for a context from a declarative table.
+    ///
+    /// For each `(source, priority, enabled)` entry of `config`, it sets the priority level of
+    /// `source` and enables or disables `source` for `ctx`. The threshold of `ctx` is left untouched.
+    ///
+    /// All the priority levels are validated against `Q::MAX_PRIORITY_NUMBER` before modifying
+    /// any register. If a priority level is not valid, it returns an error and the PLIC is not modified.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling interrupt sources and changing their priority levels can break
+    ///   mask-based and priority-based critical sections.
+    #[inline]
+    pub unsafe fn apply_config<I: InterruptNumber, Q: PriorityNumber>(
+        ctx: CTX<P>,
+        config: &[(I, u8, bool)],
+    ) -> Result<(), crate::error::InvalidPriorityNumber> {
+        apply_config::<I, Q>(Self::priorities(), ctx.enables(), config)
     }
 
     /// Returns a proxy to access to all the PLIC registers of a given context.
@@ -150,6 +519,13 @@ impl<P: Plic> PLIC<P> {
         // SAFETY: valid context number
         unsafe { CTX::new(context.number()) }
     }
+
+    /// Returns a proxy to access to all the PLIC registers of the context that targets
+    /// a given HART and privilege mode. If no context targets them, it returns [`None`].
+    #[inline]
+    pub fn ctx_for<C: ContextInfo>(hart_id: u16, mode: Mode) -> Option<CTX<P>> {
+        C::context_for(hart_id, mode).map(Self::ctx)
+    }
 }
 
 /// PLIC context proxy. It provides access to the PLIC registers of a given context.
@@ -161,14 +537,9 @@ pub struct CTX<P: Plic> {
 }
 
 impl<P: Plic> CTX<P> {
-    const ENABLES_OFFSET: usize = 0x2000;
-    const ENABLES_SEPARATION: usize = 0x80;
-
-    const THRESHOLDS_OFFSET: usize = 0x20_0000;
-    const THRESHOLDS_SEPARATION: usize = 0x1000;
+    const THRESHOLD_OFFSET: usize = 0;
 
-    const CLAIMS_OFFSET: usize = 0x20_0004;
-    const CLAIMS_SEPARATION: usize = 0x1000;
+    const CLAIM_OFFSET: usize = 4;
 
     /// Creates a new PLIC context proxy
     ///
@@ -176,7 +547,7 @@ impl<P: Plic> CTX<P> {
     ///
     /// The context number must be valid for the target device.
     #[inline]
-    pub(crate) unsafe fn new(context: u16) -> Self {
+    pub const unsafe fn new(context: u16) -> Self {
         Self {
             context: context as _,
             _marker: core::marker::PhantomData,
@@ -192,31 +563,230 @@ impl<P: Plic> CTX<P> {
     /// Returns the interrupts enable register of the context.
     #[inline]
     pub const fn enables(self) -> enables::ENABLES {
-        let addr = P::BASE + Self::ENABLES_OFFSET + self.context * Self::ENABLES_SEPARATION;
         // SAFETY: valid address
-        unsafe { enables::ENABLES::new(addr) }
+        unsafe { enables::ENABLES::new(PLIC::<P>::enables_addr(self.context())) }
+    }
+
+    /// Returns the enable bits of the interrupt sources `32 * index` to `32 * index + 31` of the context.
+    ///
+    /// Together with [`CTX::write_enable_word`], it allows saving and restoring the whole
+    /// enable configuration of the context (e.g., across a low-power mode).
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is greater than `I::MAX_INTERRUPT_NUMBER / 32`.
+    #[inline]
+    pub fn read_enable_word<I: InterruptNumber>(self, index: usize) -> u32 {
+        self.enables().read_word::<I>(index)
+    }
+
+    /// Writes the enable bits of the interrupt sources `32 * index` to `32 * index + 31` of the context.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is greater than `I::MAX_INTERRUPT_NUMBER / 32`.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling interrupt sources can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn write_enable_word<I: InterruptNumber>(self, index: usize, value: u32) {
+        self.enables().write_word::<I>(index, value)
     }
 
     /// Returns the interrupt threshold register of the context.
     #[inline]
     pub const fn threshold(self) -> threshold::THRESHOLD {
-        let addr = P::BASE + Self::THRESHOLDS_OFFSET + self.context * Self::THRESHOLDS_SEPARATION;
         // SAFETY: valid address
-        unsafe { threshold::THRESHOLD::new(addr) }
+        unsafe { threshold::THRESHOLD::new(PLIC::<P>::threshold_addr(self.context())) }
     }
 
     /// Returns the interrupt claim/complete register of the context.
     #[inline]
     pub const fn claim(self) -> claim::CLAIM {
-        let addr = P::BASE + Self::CLAIMS_OFFSET + self.context * Self::CLAIMS_SEPARATION;
         // SAFETY: valid address
-        unsafe { claim::CLAIM::new(addr) }
+        unsafe { claim::CLAIM::new(PLIC::<P>::claim_addr(self.context())) }
+    }
+
+    /// Runs `f` as the handler of the claimed interrupt `source`, whose priority is `claimed_priority`.
+    /// While `f` runs, interrupts with a priority higher than `claimed_priority` can preempt it.
+    ///
+    /// This method performs the following sequence:
+    ///
+    /// 1. Saves the current priority threshold of the context.
+    /// 2. Raises the priority threshold of the context to `claimed_priority`.
+    /// 3. Sets the `mstatus.MIE` bit to enable machine interrupts.
+    /// 4. Runs `f`.
+    /// 5. Clears the `mstatus.MIE` bit to disable machine interrupts.
+    /// 6. Completes the claimed interrupt `source`.
+    /// 7. Restores the saved priority threshold.
+    ///
+    /// Steps 5 to 7 are performed by a drop guard. Thus, they also run if `f` unwinds.
+    ///
+    /// # Safety
+    ///
+    /// * This method must only be called from the machine external interrupt handler, after claiming `source`.
+    /// * Nested traps overwrite `mepc` and `mstatus.MPIE`/`mstatus.MPP`.
+    ///   The trap handler must save them before calling this method, and restore them afterwards.
+    /// * `f` runs with machine interrupts enabled, which may break critical sections.
+    #[inline]
+    pub unsafe fn with_nested<I: InterruptNumber, F: FnOnce() -> R, R>(
+        self,
+        source: I,
+        claimed_priority: u8,
+        f: F,
+    ) -> R {
+        nested(self.threshold(), self.claim(), source, claimed_priority, f)
+    }
+
+    /// Services all the pending interrupts of the context and returns the number of serviced interrupts.
+    ///
+    /// It claims pending interrupts until the claim/complete register returns 0 (i.e., no interrupt
+    /// is pending). For each claimed interrupt source, it calls `dispatch` with the source and then
+    /// completes the very same source.
+    ///
+    /// # Note
+    ///
+    /// This method is intended to be the body of the machine external interrupt handler.
+    #[inline]
+    pub fn service<I: InterruptNumber, F: FnMut(I)>(self, dispatch: F) -> usize {
+        self.claim().service(dispatch)
+    }
+}
+
+impl<P: Plic, I: InterruptNumber> crate::InterruptSource<I> for CTX<P> {
+    #[inline]
+    fn claim(&self) -> Option<I> {
+        CTX::claim(*self).claim()
+    }
+}
+
+impl<P: Plic, I: InterruptNumber> crate::InterruptSink<I> for CTX<P> {
+    #[inline]
+    fn complete(&self, source: I) {
+        CTX::claim(*self).complete(source)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<P: Plic> defmt::Format for CTX<P> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "CTX({=u16})", self.context())
+    }
+}
+
+/// Drop guard that finishes the handling of a nested interrupt.
+struct NestedGuard<I: InterruptNumber> {
+    threshold: crate::common::Reg<u32, crate::common::RW>,
+    claim: claim::CLAIM,
+    source: I,
+    prev_threshold: u32,
+}
+
+impl<I: InterruptNumber> Drop for NestedGuard<I> {
+    #[inline]
+    fn drop(&mut self) {
+        crate::csr::mstatus::clear_mie();
+        self.claim.complete(self.source);
+        self.threshold.write(self.prev_threshold);
     }
 }
 
+/// Runs `f` as the handler of a nested interrupt. See [`CTX::with_nested`] for more details.
+///
+/// # Safety
+///
+/// See [`CTX::with_nested`].
+#[inline]
+unsafe fn nested<I: InterruptNumber, F: FnOnce() -> R, R>(
+    threshold: threshold::THRESHOLD,
+    claim: claim::CLAIM,
+    source: I,
+    claimed_priority: u8,
+    f: F,
+) -> R {
+    let threshold = threshold.get_register();
+    let _guard = NestedGuard {
+        threshold,
+        claim,
+        source,
+        prev_threshold: threshold.read(),
+    };
+    threshold.write(claimed_priority as _);
+    crate::csr::mstatus::set_mie();
+    f()
+}
+
+/// Returns the index of an interrupt source in the PLIC registers.
+///
+/// In debug builds, it panics if the interrupt number is `0`, which is reserved as "no interrupt".
+#[inline]
+fn source_index<I: InterruptNumber>(source: I) -> usize {
+    let number = source.number();
+    debug_assert_ne!(
+        number, 0,
+        "interrupt number 0 is reserved as \"no interrupt\""
+    );
+    number as _
+}
+
+/// Checks that an interrupt source is within `1..=I::MAX_INTERRUPT_NUMBER` and returns its index
+/// in the PLIC registers.
+#[inline]
+fn check_source<I: InterruptNumber>(
+    source: I,
+) -> Result<usize, crate::error::InvalidInterruptNumber> {
+    match source.number() {
+        0 => Err(crate::error::InvalidInterruptNumber(0)),
+        n if n > I::MAX_INTERRUPT_NUMBER => Err(crate::error::InvalidInterruptNumber(n)),
+        n => Ok(n as _),
+    }
+}
+
+/// Applies a table of interrupt source configurations. See [`PLIC::apply_config`] for more details.
+///
+/// # Safety
+///
+/// See [`PLIC::apply_config`].
+#[inline]
+unsafe fn apply_config<I: InterruptNumber, Q: PriorityNumber>(
+    priorities: priorities::PRIORITIES,
+    enables: enables::ENABLES,
+    config: &[(I, u8, bool)],
+) -> Result<(), crate::error::InvalidPriorityNumber> {
+    for &(_, priority, _) in config {
+        Q::try_from_number(priority)?;
+    }
+    for &(source, priority, enabled) in config {
+        priorities.set_priority(source, Q::from_number(priority).unwrap());
+        match enabled {
+            true => enables.enable(source),
+            false => enables.disable(source),
+        }
+    }
+    Ok(())
+}
+
+/// Claims, dispatches, and completes interrupts until `claim` returns [`None`].
+/// See [`CTX::service`] for more details.
+#[inline]
+fn service<I: InterruptNumber>(
+    mut claim: impl FnMut() -> Option<I>,
+    mut complete: impl FnMut(I),
+    mut dispatch: impl FnMut(I),
+) -> usize {
+    let mut serviced = 0;
+    while let Some(source) = claim() {
+        dispatch(source);
+        complete(source);
+        serviced += 1;
+    }
+    serviced
+}
+
 #[cfg(test)]
 pub(crate) mod test {
-    use super::{ContextNumber, InterruptNumber, PriorityNumber};
+    use super::{ContextInfo, ContextNumber, InterruptNumber, Mode, PriorityNumber};
 
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     #[repr(u16)]
@@ -301,6 +871,24 @@ pub(crate) mod test {
         }
     }
 
+    unsafe impl ContextInfo for Context {
+        #[inline]
+        fn hart_id(self) -> u16 {
+            match self {
+                Self::C0 | Self::C1 => 0,
+                Self::C2 => 1,
+            }
+        }
+
+        #[inline]
+        fn mode(self) -> Mode {
+            match self {
+                Self::C0 | Self::C2 => Mode::Machine,
+                Self::C1 => Mode::Supervisor,
+            }
+        }
+    }
+
     #[test]
     fn check_interrupt_enum() {
         assert_eq!(Interrupt::I1.number(), 1);
@@ -315,6 +903,49 @@ pub(crate) mod test {
 
         assert_eq!(Interrupt::from_number(0), Err(0));
         assert_eq!(Interrupt::from_number(5), Err(5));
+
+        assert_eq!(Interrupt::try_from_number_opt(4), Some(Interrupt::I4));
+        assert_eq!(Interrupt::try_from_number_opt(0), None);
+        assert_eq!(Interrupt::try_from_number_opt(5), None);
+        assert_eq!(
+            Interrupt::try_from_number(0),
+            Err(crate::error::InvalidInterruptNumber(0))
+        );
+        assert_eq!(Interrupt::from_number_saturating(2), Interrupt::I2);
+        assert_eq!(Interrupt::from_number_saturating(5), Interrupt::I4);
+    }
+
+    #[test]
+    fn check_interrupt_names() {
+        use std::format;
+
+        #[derive(Clone, Copy)]
+        struct Uart;
+
+        unsafe impl InterruptNumber for Uart {
+            const MAX_INTERRUPT_NUMBER: u16 = 37;
+
+            fn number(self) -> u16 {
+                37
+            }
+
+            fn from_number(number: u16) -> Result<Self, u16> {
+                match number {
+                    37 => Ok(Uart),
+                    _ => Err(number),
+                }
+            }
+
+            fn name(self) -> Option<&'static str> {
+                Some("UART0")
+            }
+        }
+
+        assert_eq!(Interrupt::I3.name(), None);
+        assert_eq!(format!("{}", Interrupt::I3.display_name()), "IRQ3");
+        assert_eq!(format!("{:?}", Interrupt::I3.display_name()), "IRQ3");
+        assert_eq!(format!("{}", Uart.display_name()), "UART0");
+        assert_eq!(format!("{:?}", Uart.display_name()), "UART0");
     }
 
     #[test]
@@ -330,6 +961,15 @@ pub(crate) mod test {
         assert_eq!(Priority::from_number(3), Ok(Priority::P3));
 
         assert_eq!(Priority::from_number(4), Err(4));
+
+        assert_eq!(Priority::try_from_number_opt(3), Some(Priority::P3));
+        assert_eq!(Priority::try_from_number_opt(4), None);
+        assert_eq!(
+            Priority::try_from_number(4),
+            Err(crate::error::InvalidPriorityNumber(4))
+        );
+        assert_eq!(Priority::from_number_saturating(0), Priority::P0);
+        assert_eq!(Priority::from_number_saturating(u8::MAX), Priority::P3);
     }
 
     #[test]
@@ -343,6 +983,322 @@ pub(crate) mod test {
         assert_eq!(Context::from_number(2), Ok(Context::C2));
 
         assert_eq!(Context::from_number(3), Err(3));
+
+        assert_eq!(Context::try_from_number_opt(2), Some(Context::C2));
+        assert_eq!(Context::try_from_number_opt(3), None);
+        assert_eq!(
+            Context::try_from_number(3),
+            Err(crate::error::InvalidContextNumber(3))
+        );
+        assert_eq!(Context::from_number_saturating(3), Context::C2);
+    }
+
+    #[test]
+    fn check_ordered_numbers() {
+        type Ordered = super::Priority<Priority>;
+        type Source = super::Source<Interrupt>;
+
+        assert!(Ordered::from(Priority::P1) < Ordered::from(Priority::P2));
+        assert_eq!(
+            Ordered::from(Priority::P3).max(Priority::P0.into()).get(),
+            Priority::P3
+        );
+        assert_eq!(Ordered::from(Priority::P2).number(), 2);
+
+        let mut sources =
+            [Interrupt::I3, Interrupt::I1, Interrupt::I4, Interrupt::I2].map(Source::from);
+        sources.sort();
+        assert_eq!(
+            sources.map(Source::get),
+            [Interrupt::I1, Interrupt::I2, Interrupt::I3, Interrupt::I4]
+        );
+    }
+
+    #[test]
+    fn check_context_info() {
+        assert_eq!(Context::context_for(0, Mode::Machine), Some(Context::C0));
+        assert_eq!(Context::context_for(0, Mode::Supervisor), Some(Context::C1));
+        assert_eq!(Context::context_for(1, Mode::Machine), Some(Context::C2));
+        assert_eq!(Context::context_for(1, Mode::Supervisor), None);
+        assert_eq!(Context::context_for(2, Mode::Machine), None);
+    }
+
+    #[test]
+    fn check_is_current_hart() {
+        crate::csr::mhartid::set(0);
+        assert!(Context::C0.is_current_hart());
+        assert!(Context::C1.is_current_hart());
+        assert!(!Context::C2.is_current_hart());
+
+        crate::csr::mhartid::set(1);
+        assert!(!Context::C0.is_current_hart());
+        assert!(!Context::C1.is_current_hart());
+        assert!(Context::C2.is_current_hart());
+        crate::csr::mhartid::set(0);
+    }
+
+    #[test]
+    fn check_nested() {
+        let mut raw_threshold = 1u32;
+        let mut raw_claim = 0u32;
+        // SAFETY: valid memory addresses
+        let (threshold, claim) = unsafe {
+            (
+                super::threshold::THRESHOLD::new(&mut raw_threshold as *mut _ as _),
+                super::claim::CLAIM::new(&mut raw_claim as *mut _ as _),
+            )
+        };
+
+        let res = unsafe {
+            super::nested(threshold, claim, Interrupt::I2, 2, || {
+                assert!(crate::csr::mstatus::mie());
+                assert_eq!(threshold.get_threshold::<Priority>(), Priority::P2);
+                42
+            })
+        };
+        assert_eq!(res, 42);
+        assert!(!crate::csr::mstatus::mie());
+        assert_eq!(threshold.get_threshold::<Priority>(), Priority::P1);
+        assert_eq!(unsafe { claim.get_register() }.read(), 2);
+
+        // the threshold must be restored even if the handler panics
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            super::nested(threshold, claim, Interrupt::I3, 3, || {
+                panic!("handler panicked")
+            })
+        }));
+        assert!(res.is_err());
+        assert!(!crate::csr::mstatus::mie());
+        assert_eq!(threshold.get_threshold::<Priority>(), Priority::P1);
+        assert_eq!(unsafe { claim.get_register() }.read(), 3);
+    }
+
+    #[test]
+    fn check_service() {
+        use std::vec::Vec;
+
+        // claim/complete register with two queued interrupts
+        let mut queue = [Interrupt::I3, Interrupt::I1].into_iter();
+        let mut completed = Vec::new();
+        let mut dispatched = Vec::new();
+
+        let serviced = super::service(
+            || queue.next(),
+            |source| completed.push(source),
+            |source| dispatched.push(source),
+        );
+        assert_eq!(serviced, 2);
+        assert_eq!(dispatched, [Interrupt::I3, Interrupt::I1]);
+        assert_eq!(completed, [Interrupt::I3, Interrupt::I1]);
+
+        // no pending interrupts
+        let mut raw_claim = 0u32;
+        // SAFETY: valid memory address
+        let claim = unsafe { super::claim::CLAIM::new(&mut raw_claim as *mut _ as _) };
+        let serviced = claim.service::<Interrupt, _>(|_| panic!("nothing to dispatch"));
+        assert_eq!(serviced, 0);
+    }
+
+    #[test]
+    fn check_service_claim_register() {
+        use crate::testing::{MockMmio, Record};
+        use std::vec::Vec;
+
+        // claim/complete register with two queued interrupts
+        let mmio = MockMmio::with_log(0x4);
+        mmio.queue_reads::<u32>(0, &[3, 1, 0]);
+        // SAFETY: valid memory address
+        let claim = unsafe { super::claim::CLAIM::new(mmio.base()) };
+
+        let mut dispatched = Vec::new();
+        let serviced = claim.service(|source: Interrupt| {
+            // the source is dispatched before completing it
+            assert_eq!(mmio.records().last(), Some(&Record::read(0, source as u32)));
+            dispatched.push(source)
+        });
+        assert_eq!(serviced, 2);
+        assert_eq!(dispatched, [Interrupt::I3, Interrupt::I1]);
+        assert_eq!(
+            mmio.records(),
+            [
+                Record::read(0, 3u32),
+                Record::write(0, 3u32),
+                Record::read(0, 1u32),
+                Record::write(0, 1u32),
+                Record::read(0, 0u32),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_apply_config() {
+        let mut raw_priorities = [0u32; 1024];
+        let mut raw_enables = [0u32; 32];
+        // SAFETY: valid memory addresses
+        let (priorities, enables) = unsafe {
+            (
+                super::priorities::PRIORITIES::new(raw_priorities.as_mut_ptr() as _),
+                super::enables::ENABLES::new(raw_enables.as_mut_ptr() as _),
+            )
+        };
+        unsafe { enables.enable(Interrupt::I4) };
+
+        let config = [
+            (Interrupt::I1, 3, true),
+            (Interrupt::I2, 1, false),
+            (Interrupt::I4, 2, false),
+        ];
+        assert_eq!(
+            unsafe { super::apply_config::<_, Priority>(priorities, enables, &config) },
+            Ok(())
+        );
+        assert_eq!(raw_priorities[..5], [0, 3, 1, 0, 2]);
+        assert_eq!(raw_enables[0], 1 << 1);
+
+        // invalid priority levels are rejected before modifying any register
+        let config = [(Interrupt::I3, 1, true), (Interrupt::I1, 4, true)];
+        assert_eq!(
+            unsafe { super::apply_config::<_, Priority>(priorities, enables, &config) },
+            Err(crate::error::InvalidPriorityNumber(4))
+        );
+        assert_eq!(raw_priorities[..5], [0, 3, 1, 0, 2]);
+        assert_eq!(raw_enables[0], 1 << 1);
+    }
+
+    #[test]
+    fn check_deliverable() {
+        use crate::csr::{mie, mstatus};
+
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct TestPlic;
+
+        unsafe impl super::Plic for TestPlic {
+            const BASE: usize = 0;
+        }
+
+        type Plic = super::PLIC<TestPlic>;
+
+        assert!(!Plic::mext_deliverable());
+        unsafe { mie::set_mext() };
+        assert!(!Plic::mext_deliverable());
+        unsafe { mstatus::set_mie() };
+        assert!(Plic::mext_deliverable());
+        mie::clear_mext();
+        assert!(!Plic::mext_deliverable());
+        mstatus::clear_mie();
+    }
+
+    #[test]
+    fn check_try_unmask() {
+        use crate::{common::UnmaskToken, error::InvalidInterruptNumber};
+
+        /// Interrupt source that may report numbers out of `1..=MAX_INTERRUPT_NUMBER`.
+        #[derive(Clone, Copy)]
+        struct Raw(u16);
+
+        unsafe impl InterruptNumber for Raw {
+            const MAX_INTERRUPT_NUMBER: u16 = 4;
+
+            fn number(self) -> u16 {
+                self.0
+            }
+
+            fn from_number(value: u16) -> Result<Self, u16> {
+                Ok(Self(value))
+            }
+        }
+
+        let mut raw_priorities = [0u32; 1024];
+        let mut raw_enables = [0u32; 32];
+        // SAFETY: valid memory addresses and no critical sections in the test
+        let (priorities, enables, token) = unsafe {
+            (
+                super::priorities::PRIORITIES::new(raw_priorities.as_mut_ptr() as _),
+                super::enables::ENABLES::new(raw_enables.as_mut_ptr() as _),
+                UnmaskToken::new(),
+            )
+        };
+
+        assert_eq!(enables.try_enable(Raw(4), token), Ok(()));
+        assert_eq!(
+            priorities.try_set_priority(Raw(1), Priority::P2, token),
+            Ok(())
+        );
+        for n in [0, 5] {
+            assert_eq!(
+                enables.try_enable(Raw(n), token),
+                Err(InvalidInterruptNumber(n))
+            );
+            assert_eq!(
+                priorities.try_set_priority(Raw(n), Priority::P3, token),
+                Err(InvalidInterruptNumber(n))
+            );
+        }
+        assert_eq!(raw_enables[0], 1 << 4);
+        assert_eq!(raw_priorities[..6], [0, 2, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn check_custom_offsets() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct CustomPlic;
+
+        unsafe impl super::Plic for CustomPlic {
+            const BASE: usize = 0x1000_0000;
+            const PRIORITIES_OFFSET: usize = 0x4;
+            const PENDING_OFFSET: usize = 0x2000;
+            const ENABLES_OFFSET: usize = 0x3000;
+            const CONTEXT_BASE: usize = 0x10_0000;
+            const CONTEXT_STRIDE: usize = 0x2000;
+        }
+
+        assert_eq!(
+            super::PLIC::<CustomPlic>::priorities().address(),
+            0x1000_0004
+        );
+        assert_eq!(super::PLIC::<CustomPlic>::pendings().address(), 0x1000_2000);
+
+        let ctx = super::PLIC::<CustomPlic>::ctx(Context::C2);
+        assert_eq!(ctx.enables().address(), 0x1000_3000 + 2 * 0x80);
+        assert_eq!(ctx.threshold().get_ptr() as usize, 0x1010_4000);
+        assert_eq!(ctx.claim().get_ptr() as usize, 0x1010_4004);
+    }
+
+    #[test]
+    fn check_custom_strides() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct CustomPlic;
+
+        unsafe impl super::Plic for CustomPlic {
+            const BASE: usize = 0x1000_0000;
+            const ENABLES_STRIDE: usize = 0x100;
+            const CONTEXT_STRIDE: usize = 0x2000;
+        }
+
+        // SAFETY: context 3 is valid for the custom PLIC
+        let ctx = unsafe { super::CTX::<CustomPlic>::new(3) };
+        assert_eq!(ctx.enables().address(), 0x1000_2000 + 3 * 0x100);
+        assert_eq!(ctx.threshold().get_ptr() as usize, 0x1020_6000);
+        assert_eq!(ctx.claim().get_ptr() as usize, 0x1020_6004);
+    }
+
+    #[test]
+    fn check_const_constructors() {
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        struct CustomPlic;
+
+        unsafe impl super::Plic for CustomPlic {
+            const BASE: usize = 0x1000_0000;
+        }
+
+        const _PLIC: super::PLIC<CustomPlic> = super::PLIC::new();
+        const PRIORITIES: super::priorities::PRIORITIES = super::PLIC::<CustomPlic>::priorities();
+        const ENABLES: super::enables::ENABLES = unsafe { super::enables::ENABLES::new(0x2000) };
+        const CTX: super::CTX<CustomPlic> = unsafe { super::CTX::new(3) };
+
+        assert_eq!(PRIORITIES.address(), 0x1000_0000);
+        assert_eq!(ENABLES.address(), 0x2000);
+        assert_eq!(CTX.context(), 3);
     }
 
     #[allow(dead_code)]
@@ -351,6 +1307,9 @@ pub(crate) mod test {
         crate::plic_codegen!(
             base 0x0C00_0000,
             ctxs [ctx0 = (Context::C0, "`C0`"), ctx1 = (Context::C1, "`C1`"), ctx2 = (Context::C2, "`C2`")],
+            ctx_thresholds [ctx0_set_threshold = (Context::C0, "`C0`"), ctx1_set_threshold = (Context::C1, "`C1`"), ctx2_set_threshold = (Context::C2, "`C2`")],
+            ctx_enables [ctx0_enable = (Context::C0, "`C0`"), ctx1_enable = (Context::C1, "`C1`"), ctx2_enable = (Context::C2, "`C2`")],
+            interrupts Interrupt,
         );
 
         let priorities = PLIC::priorities();
@@ -359,12 +1318,25 @@ pub(crate) mod test {
         assert_eq!(priorities.address(), 0x0C00_0000);
         assert_eq!(pendings.address(), 0x0C00_1000);
 
+        // addresses are available in const contexts
+        const THRESHOLD1_ADDR: usize = PLIC::threshold_addr(1);
+        assert_eq!(THRESHOLD1_ADDR, 0x0C20_1000);
+        assert_eq!(PLIC::PRIORITIES_ADDR, priorities.address());
+        assert_eq!(PLIC::PENDINGS_ADDR, pendings.address());
+        assert_eq!(PLIC::priority_addr(3), 0x0C00_000C);
+
         for i in 0..=Context::MAX_CONTEXT_NUMBER {
             let context = Context::from_number(i).unwrap();
             let i = i as usize;
 
             let ctx = PLIC::ctx(context);
 
+            assert_eq!(ctx.enables().address(), PLIC::enables_addr(i as _));
+            assert_eq!(
+                ctx.threshold().get_ptr() as usize,
+                PLIC::threshold_addr(i as _)
+            );
+            assert_eq!(ctx.claim().get_ptr() as usize, PLIC::claim_addr(i as _));
             assert_eq!(ctx.enables().address(), 0x0C00_0000 + 0x2000 + i * 0x80);
             assert_eq!(
                 ctx.threshold().get_ptr() as usize,
@@ -379,5 +1351,9 @@ pub(crate) mod test {
         assert_eq!(PLIC::ctx0(), PLIC::ctx(Context::C0));
         assert_eq!(PLIC::ctx1(), PLIC::ctx(Context::C1));
         assert_eq!(PLIC::ctx2(), PLIC::ctx(Context::C2));
+        assert_eq!(
+            PLIC::ctx_for::<Context>(0, Mode::Supervisor),
+            Some(PLIC::ctx1())
+        );
     }
 }
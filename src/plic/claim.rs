@@ -9,12 +9,23 @@ impl CLAIM {
     /// If no interrupt is pending for this context, it returns [`None`].
     #[inline]
     pub fn claim<I: InterruptNumber>(self) -> Option<I> {
-        match self.register.read() {
+        match self.claim_raw() {
             0 => None,
-            i => Some(I::from_number(i as _).unwrap()),
+            i => Some(I::from_number(i).unwrap()),
         }
     }
 
+    /// Claims the number of a pending interrupt for the PLIC context.
+    /// If no interrupt is pending for this context, it returns 0.
+    ///
+    /// This is the hot-path variant of [`CLAIM::claim`] for high-rate interrupt handlers:
+    /// it is always inlined, and it does not convert the number to an interrupt source
+    /// (i.e., there is no panicking branch). See the `claim_bench` example for a comparison.
+    #[inline(always)]
+    pub fn claim_raw(self) -> u16 {
+        self.register.read() as _
+    }
+
     /// Marks a pending interrupt as complete for the PLIC context.
     ///
     /// # Note
@@ -23,7 +34,36 @@ impl CLAIM {
     /// currently enabled for the target, the completion is silently ignored.
     #[inline]
     pub fn complete<I: InterruptNumber>(self, source: I) {
-        self.register.write(source.number() as _)
+        self.complete_raw(super::source_index(source) as _)
+    }
+
+    /// Marks the interrupt source number `source` as complete for the PLIC context.
+    ///
+    /// This is the hot-path variant of [`CLAIM::complete`]. See [`CLAIM::claim_raw`] for more details.
+    #[inline(always)]
+    pub fn complete_raw(self, source: u16) {
+        self.register.write(source as _)
+    }
+
+    /// Services all the pending interrupts of the PLIC context and returns the number of
+    /// serviced interrupts. See [`crate::plic::CTX::service`] for more details.
+    #[inline]
+    pub fn service<I: InterruptNumber, F: FnMut(I)>(self, dispatch: F) -> usize {
+        super::service(|| self.claim(), |source| self.complete(source), dispatch)
+    }
+}
+
+impl<I: InterruptNumber> crate::InterruptSource<I> for CLAIM {
+    #[inline]
+    fn claim(&self) -> Option<I> {
+        CLAIM::claim(*self)
+    }
+}
+
+impl<I: InterruptNumber> crate::InterruptSink<I> for CLAIM {
+    #[inline]
+    fn complete(&self, source: I) {
+        CLAIM::complete(*self, source)
     }
 }
 
@@ -44,6 +84,60 @@ mod test {
             let interrupt = Interrupt::from_number(i).unwrap();
             claim.complete(interrupt);
             assert_eq!(claim.claim(), Some(interrupt));
+            assert_eq!(claim.claim_raw(), i);
+        }
+
+        claim.complete_raw(0);
+        assert_eq!(claim.claim_raw(), 0);
+        assert_eq!(claim.claim::<Interrupt>(), None);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "interrupt number 0 is reserved")]
+    fn test_complete_zero() {
+        /// Misbehaving interrupt source that returns the reserved number 0.
+        #[derive(Clone, Copy)]
+        struct Reserved;
+
+        unsafe impl InterruptNumber for Reserved {
+            const MAX_INTERRUPT_NUMBER: u16 = 0;
+
+            fn number(self) -> u16 {
+                0
+            }
+
+            fn from_number(number: u16) -> Result<Self, u16> {
+                Err(number)
+            }
+        }
+
+        let mut raw_reg = 0u32;
+        // SAFETY: valid memory address
+        let claim = unsafe { CLAIM::new(&mut raw_reg as *mut _ as _) };
+        claim.complete(Reserved);
+    }
+
+    #[test]
+    fn test_source_sink() {
+        use crate::{InterruptSink, InterruptSource};
+
+        /// Generic driver that handles a single pending interrupt.
+        fn handle_one<I: Copy, C: InterruptSource<I> + InterruptSink<I>>(ctl: &C) -> Option<I> {
+            let source = ctl.claim()?;
+            ctl.complete(source);
+            Some(source)
         }
+
+        let mut raw_reg = 0u32;
+        // SAFETY: valid memory address
+        let claim = unsafe { CLAIM::new(&mut raw_reg as *mut _ as _) };
+
+        assert_eq!(handle_one::<Interrupt, _>(&claim), None);
+        // on memory, the claim register holds the last written source number
+        claim.complete(Interrupt::I3);
+        assert_eq!(handle_one(&claim), Some(Interrupt::I3));
+        // the completion writes the source number back to the register
+        assert_eq!(raw_reg, Interrupt::I3.number() as _);
     }
 }
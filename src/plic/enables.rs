@@ -1,10 +1,14 @@
 //! Interrupt enables register of a PLIC context.
 
 use crate::{
-    common::{Reg, RW},
-    plic::InterruptNumber,
+    common::{Reg, RegBank, UnmaskToken, RW},
+    error::InvalidInterruptNumber,
+    plic::{InterruptNumber, MAX_INTERRUPT_NUMBER},
 };
 
+/// Number of 32-bit words of the enables register of a PLIC context.
+pub const NUM_WORDS: usize = (MAX_INTERRUPT_NUMBER as usize + 1) / u32::BITS as usize;
+
 /// Enables register of a PLIC context.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(transparent)]
@@ -12,6 +16,12 @@ pub struct ENABLES {
     ptr: *mut u32,
 }
 
+// SAFETY: the enable registers are memory-mapped and shared among all the HARTs.
+// Accesses are volatile and the proxy does not hold any state besides the base address.
+unsafe impl Send for ENABLES {}
+// SAFETY: see the `Send` implementation above.
+unsafe impl Sync for ENABLES {}
+
 impl ENABLES {
     /// Creates a new Interrupts enables register from a base address.
     ///
@@ -19,7 +29,7 @@ impl ENABLES {
     ///
     /// The base address must point to a valid Interrupts enables register.
     #[inline]
-    pub(crate) const unsafe fn new(address: usize) -> Self {
+    pub const unsafe fn new(address: usize) -> Self {
         Self { ptr: address as _ }
     }
 
@@ -29,10 +39,52 @@ impl ENABLES {
         self.ptr as _
     }
 
+    /// Returns the word of the enables register that holds the interrupt sources
+    /// `32 * index` to `32 * index + 31`, if any of them is an interrupt source of `I`.
+    #[inline]
+    fn word<I: InterruptNumber>(self, index: usize) -> Option<Reg<u32, RW>> {
+        if index > I::MAX_INTERRUPT_NUMBER as usize / u32::BITS as usize {
+            return None;
+        }
+        // SAFETY: the enables register spans `NUM_WORDS` words
+        let words: RegBank<u32, RW, NUM_WORDS> = unsafe { RegBank::new(self.ptr) };
+        words.get(index)
+    }
+
+    /// Returns the enable bits of the interrupt sources `32 * index` to `32 * index + 31`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is greater than `I::MAX_INTERRUPT_NUMBER / 32`.
+    #[inline]
+    pub fn read_word<I: InterruptNumber>(self, index: usize) -> u32 {
+        match self.word::<I>(index) {
+            Some(reg) => reg.read(),
+            None => panic!("enable word index out of bounds"),
+        }
+    }
+
+    /// Writes the enable bits of the interrupt sources `32 * index` to `32 * index + 31`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is greater than `I::MAX_INTERRUPT_NUMBER / 32`.
+    ///
+    /// # Safety
+    ///
+    /// * Enabling interrupt sources can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn write_word<I: InterruptNumber>(self, index: usize, value: u32) {
+        match self.word::<I>(index) {
+            Some(reg) => reg.write(value),
+            None => panic!("enable word index out of bounds"),
+        }
+    }
+
     /// Checks if an interrupt source is enabled for the PLIC context.
     #[inline]
     pub fn is_enabled<I: InterruptNumber>(self, source: I) -> bool {
-        let source = source.number() as usize;
+        let source = super::source_index(source);
         let offset = (source / u32::BITS as usize) as _;
         // SAFETY: valid interrupt number
         let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(offset)) };
@@ -50,13 +102,34 @@ impl ENABLES {
     /// * Enabling an interrupt source can break mask-based critical sections.
     #[inline]
     pub unsafe fn enable<I: InterruptNumber>(self, source: I) {
-        let source = source.number() as usize;
+        let source = super::source_index(source);
         let offset = (source / u32::BITS as usize) as _;
         // SAFETY: valid interrupt number
         let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(offset)) };
         reg.set_bit(source % u32::BITS as usize);
     }
 
+    /// Enables an interrupt source for the PLIC context, checking that the interrupt source
+    /// is within `1..=I::MAX_INTERRUPT_NUMBER`.
+    ///
+    /// Instead of being `unsafe`, it requires an [`UnmaskToken`] to acknowledge that
+    /// enabling an interrupt source can break mask-based critical sections.
+    ///
+    /// # Note
+    ///
+    /// It performs non-atomic read-modify-write operations, which may lead to **wrong** behavior.
+    #[inline]
+    pub fn try_enable<I: InterruptNumber>(
+        self,
+        source: I,
+        _token: UnmaskToken,
+    ) -> Result<(), InvalidInterruptNumber> {
+        super::check_source(source)?;
+        // SAFETY: the token holder accepts the critical section hazard
+        unsafe { self.enable(source) };
+        Ok(())
+    }
+
     #[cfg(target_has_atomic = "32")]
     /// Enables an interrupt source for the PLIC context atomically.
     ///
@@ -75,7 +148,7 @@ impl ENABLES {
         source: I,
         order: core::sync::atomic::Ordering,
     ) {
-        let source = source.number() as usize;
+        let source = super::source_index(source);
         let offset = (source / u32::BITS as usize) as _;
         // SAFETY: valid interrupt number
         let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(offset)) };
@@ -89,7 +162,7 @@ impl ENABLES {
     /// It performs non-atomic read-modify-write operations, which may lead to **wrong** behavior.
     #[inline]
     pub fn disable<I: InterruptNumber>(self, source: I) {
-        let source = source.number() as usize;
+        let source = super::source_index(source);
         let offset = (source / u32::BITS as usize) as _;
         // SAFETY: valid interrupt number
         let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(offset)) };
@@ -113,7 +186,7 @@ impl ENABLES {
         source: I,
         order: core::sync::atomic::Ordering,
     ) {
-        let source = source.number() as usize;
+        let source = super::source_index(source);
         let offset = (source / u32::BITS as usize) as _;
         // SAFETY: valid interrupt number
         let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(offset)) };
@@ -198,6 +271,40 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_enable_words() {
+        // slice to emulate the interrupt enables register
+        let mut raw_reg = [0u32; NUM_WORDS];
+        // SAFETY: valid memory address
+        let enables = unsafe { ENABLES::new(raw_reg.as_mut_ptr() as _) };
+        assert_eq!(NUM_WORDS, 32);
+
+        unsafe { enables.enable(Interrupt::I1) };
+        unsafe { enables.enable(Interrupt::I3) };
+        let saved = enables.read_word::<Interrupt>(0);
+        assert_eq!(saved, 0b1010);
+
+        unsafe { enables.enable_all::<Interrupt>() };
+        enables.disable(Interrupt::I3);
+        unsafe { enables.write_word::<Interrupt>(0, saved) };
+        assert!(enables.is_enabled(Interrupt::I1));
+        assert!(!enables.is_enabled(Interrupt::I2));
+        assert!(enables.is_enabled(Interrupt::I3));
+        assert!(!enables.is_enabled(Interrupt::I4));
+        assert_eq!(raw_reg[0], saved);
+        assert_eq!(raw_reg[1..], [0; NUM_WORDS - 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "enable word index out of bounds")]
+    fn test_enable_words_bounds() {
+        let mut raw_reg = [0u32; NUM_WORDS];
+        // SAFETY: valid memory address
+        let enables = unsafe { ENABLES::new(raw_reg.as_mut_ptr() as _) };
+        // all the interrupt sources of `Interrupt` fit in the first word
+        enables.read_word::<Interrupt>(1);
+    }
+
     #[cfg(target_has_atomic = "32")]
     #[test]
     fn test_atomic_enables() {
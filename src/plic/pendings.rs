@@ -12,6 +12,12 @@ pub struct PENDINGS {
     ptr: *mut u32,
 }
 
+// SAFETY: the pending registers are memory-mapped and shared among all the HARTs.
+// Accesses are volatile and the proxy does not hold any state besides the base address.
+unsafe impl Send for PENDINGS {}
+// SAFETY: see the `Send` implementation above.
+unsafe impl Sync for PENDINGS {}
+
 impl PENDINGS {
     /// Creates a new Interrupts pending bits register from a base address.
     ///
@@ -19,7 +25,7 @@ impl PENDINGS {
     ///
     /// The base address must point to a valid Interrupts pending bits register.
     #[inline]
-    pub(crate) const unsafe fn new(address: usize) -> Self {
+    pub const unsafe fn new(address: usize) -> Self {
         Self { ptr: address as _ }
     }
 
@@ -32,12 +38,80 @@ impl PENDINGS {
     /// Checks if an interrupt triggered by a given source is pending.
     #[inline]
     pub fn is_pending<I: InterruptNumber>(self, source: I) -> bool {
-        let source = source.number() as usize;
+        let source = super::source_index(source);
         let offset = (source / u32::BITS as usize) as _;
         // SAFETY: valid interrupt number
         let reg: Reg<u32, RO> = unsafe { Reg::new(self.ptr.offset(offset)) };
         reg.read_bit(source % u32::BITS as usize)
     }
+
+    /// Returns the `index`-th 32-bit word of the pending bits register.
+    /// Bit `n` of the word corresponds to the interrupt source number `32 * index + n`.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `index` is greater than `I::MAX_INTERRUPT_NUMBER / 32`.
+    #[inline]
+    pub fn word<I: InterruptNumber>(self, index: usize) -> u32 {
+        if index > I::MAX_INTERRUPT_NUMBER as usize / u32::BITS as usize {
+            panic!("pending word index out of bounds");
+        }
+        // SAFETY: the word holds interrupt sources of `I`, so it is within the pending bits register
+        let reg: Reg<u32, RO> = unsafe { Reg::new(self.ptr.add(index)) };
+        reg.read()
+    }
+
+    /// Returns an iterator over all the pending interrupt sources, in ascending order.
+    ///
+    /// # Note
+    ///
+    /// Each pending word is read only once, and the pending bits are scanned in software.
+    /// Pending bits that do not correspond to a valid interrupt source are skipped.
+    ///
+    /// # Example
+    ///
+    /// Dumping the pending interrupt sources with the names provided by the PAC:
+    ///
+    /// ```
+    /// # use riscv_peripheral::plic::{pendings::PENDINGS, InterruptNumber};
+    /// # #[derive(Clone, Copy)]
+    /// # #[repr(u16)]
+    /// # enum Interrupt { Uart0 = 1, Gpio = 2, Spi = 3 }
+    /// # unsafe impl InterruptNumber for Interrupt {
+    /// #   const MAX_INTERRUPT_NUMBER: u16 = 3;
+    /// #   fn number(self) -> u16 { self as _ }
+    /// #   fn from_number(n: u16) -> Result<Self, u16> {
+    /// #       match n { 1 => Ok(Self::Uart0), 2 => Ok(Self::Gpio), 3 => Ok(Self::Spi), _ => Err(n) }
+    /// #   }
+    /// #   fn name(self) -> Option<&'static str> {
+    /// #       match self { Self::Uart0 => Some("UART0"), Self::Gpio => Some("GPIO"), Self::Spi => None }
+    /// #   }
+    /// # }
+    /// # let mut raw_reg = [0b1010u32];
+    /// # let pendings = unsafe { PENDINGS::new(raw_reg.as_mut_ptr() as _) };
+    /// let dump: Vec<_> = pendings
+    ///     .iter_pending::<Interrupt>()
+    ///     .map(|source| source.display_name())
+    ///     .collect();
+    /// assert_eq!(format!("{:?}", dump), "[UART0, IRQ3]");
+    /// ```
+    #[inline]
+    pub fn iter_pending<I: InterruptNumber>(self) -> impl Iterator<Item = I> {
+        let n_words = I::MAX_INTERRUPT_NUMBER as usize / u32::BITS as usize + 1;
+        (0..n_words).flat_map(move |index| {
+            let mut word = self.word::<I>(index);
+            core::iter::from_fn(move || {
+                while word != 0 {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1; // clear the lowest set bit
+                    if let Ok(source) = I::from_number((index * u32::BITS as usize + bit) as _) {
+                        return Some(source);
+                    }
+                }
+                None
+            })
+        })
+    }
 }
 
 #[cfg(test)]
@@ -61,4 +135,34 @@ mod test {
             assert_eq!(pendings.is_pending(Interrupt::I4), i & 0x10 != 0);
         }
     }
+
+    #[test]
+    fn test_iter_pending() {
+        // slice to emulate the interrupt pendings register
+        let mut raw_reg = [0u32; 32];
+        // SAFETY: valid memory address
+        let pendings = unsafe { PENDINGS::new(raw_reg.as_mut_ptr() as _) };
+
+        assert_eq!(pendings.iter_pending::<Interrupt>().next(), None);
+
+        // bit 0 ("no interrupt") and bits out of range must be ignored
+        raw_reg[0] = (1 << 0) | (1 << 2) | (1 << 4) | (1 << 7);
+        raw_reg[1] = 0xFFFF_FFFF;
+        assert_eq!(pendings.word::<Interrupt>(0), raw_reg[0]);
+
+        let mut iter = pendings.iter_pending::<Interrupt>();
+        assert_eq!(iter.next(), Some(Interrupt::I2));
+        assert_eq!(iter.next(), Some(Interrupt::I4));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "pending word index out of bounds")]
+    fn test_word_bounds() {
+        let mut raw_reg = [0u32; 32];
+        // SAFETY: valid memory address
+        let pendings = unsafe { PENDINGS::new(raw_reg.as_mut_ptr() as _) };
+        // all the interrupt sources of `Interrupt` fit in the first word
+        pendings.word::<Interrupt>(1);
+    }
 }
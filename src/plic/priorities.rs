@@ -1,15 +1,21 @@
 //! Interrupts Priorities register.
 
 use crate::{
-    common::{Reg, RW},
-    plic::{InterruptNumber, PriorityNumber},
+    common::{RegBank, UnmaskToken, RW},
+    error::InvalidInterruptNumber,
+    plic::{InterruptNumber, PriorityNumber, MAX_INTERRUPT_NUMBER},
 };
 
 /// Interrupts priorities register.
+///
+/// # Note
+///
+/// The priority registers are memory-mapped and shared among all the HARTs.
+/// Thus, this proxy is [`Send`] and [`Sync`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(transparent)]
 pub struct PRIORITIES {
-    ptr: *mut u32,
+    bank: RegBank<u32, RW, { MAX_INTERRUPT_NUMBER as usize + 1 }>,
 }
 
 impl PRIORITIES {
@@ -19,26 +25,33 @@ impl PRIORITIES {
     ///
     /// The base address must point to a valid Interrupts priorities register.
     #[inline]
-    pub(crate) const unsafe fn new(address: usize) -> Self {
-        Self { ptr: address as _ }
+    pub const unsafe fn new(address: usize) -> Self {
+        Self {
+            bank: RegBank::new(address as _),
+        }
     }
 
     #[cfg(test)]
     #[inline]
     pub(crate) fn address(self) -> usize {
-        self.ptr as _
+        self.bank.get_ptr() as _
     }
 
     /// Returns the priority assigned to a given interrupt source.
     #[inline]
     pub fn get_priority<I: InterruptNumber, P: PriorityNumber>(self, source: I) -> P {
-        // SAFETY: valid interrupt number
-        let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(source.number() as _)) };
-        P::from_number(reg.read() as _).unwrap()
+        P::from_number(self.bank.read(super::source_index(source)) as _).unwrap()
     }
 
     /// Sets the priority level of a given interrupt source.
     ///
+    /// # Note
+    ///
+    /// In debug builds, it panics if the priority level exceeds `P::MAX_PRIORITY_NUMBER`.
+    /// In release builds, the priority level is not checked and is written as is. The PLIC
+    /// ignores the priority bits it does not implement, so the resulting priority may differ.
+    /// Valid implementations of [`PriorityNumber`] never exceed `P::MAX_PRIORITY_NUMBER`.
+    ///
     /// # Safety
     ///
     /// Changing the priority level can break priority-based critical sections.
@@ -48,9 +61,70 @@ impl PRIORITIES {
         source: I,
         priority: P,
     ) {
-        // SAFETY: valid interrupt number
-        let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(source.number() as _)) };
-        reg.write(priority.number() as _);
+        debug_assert!(priority.number() <= P::MAX_PRIORITY_NUMBER);
+        self.bank
+            .write(super::source_index(source), priority.number() as _);
+    }
+
+    /// Sets the priority level of a given interrupt source, checking that the interrupt source
+    /// is within `1..=I::MAX_INTERRUPT_NUMBER`.
+    ///
+    /// Instead of being `unsafe`, it requires an [`UnmaskToken`] to acknowledge that
+    /// changing the priority level can break priority-based critical sections.
+    ///
+    /// # Note
+    ///
+    /// The priority level is checked as in [`PRIORITIES::set_priority`].
+    #[inline]
+    pub fn try_set_priority<I: InterruptNumber, P: PriorityNumber>(
+        self,
+        source: I,
+        priority: P,
+        _token: UnmaskToken,
+    ) -> Result<(), InvalidInterruptNumber> {
+        super::check_source(source)?;
+        // SAFETY: the token holder accepts the critical section hazard
+        unsafe { self.set_priority(source, priority) };
+        Ok(())
+    }
+
+    /// Sets the priority levels of the interrupt sources `1..=priorities.len()`, i.e.,
+    /// `priorities[i]` is assigned to the interrupt source number `i + 1`.
+    /// It returns the number of written priority levels.
+    ///
+    /// # Note
+    ///
+    /// Priority levels beyond `I::MAX_INTERRUPT_NUMBER` are ignored.
+    /// Priority levels are checked as in [`PRIORITIES::set_priority`].
+    ///
+    /// # Safety
+    ///
+    /// Changing the priority level can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_all<I: InterruptNumber, P: PriorityNumber>(self, priorities: &[P]) -> usize {
+        let n = priorities.len().min(I::MAX_INTERRUPT_NUMBER as usize);
+        for (i, priority) in priorities[..n].iter().enumerate() {
+            debug_assert!(priority.number() <= P::MAX_PRIORITY_NUMBER);
+            self.bank.write(i + 1, priority.number() as _);
+        }
+        n
+    }
+
+    /// Sets the same priority level to all the external interrupt sources `1..=I::MAX_INTERRUPT_NUMBER`.
+    ///
+    /// # Note
+    ///
+    /// The priority level is checked as in [`PRIORITIES::set_priority`].
+    ///
+    /// # Safety
+    ///
+    /// Changing the priority level can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_uniform<I: InterruptNumber, P: PriorityNumber>(self, priority: P) {
+        debug_assert!(priority.number() <= P::MAX_PRIORITY_NUMBER);
+        for source in 1..=I::MAX_INTERRUPT_NUMBER as usize {
+            self.bank.write(source, priority.number() as _);
+        }
     }
 
     /// Resets all the priority levels of all the external interrupt sources to 0.
@@ -61,10 +135,8 @@ impl PRIORITIES {
     /// Thus, this method effectively disables the all the external interrupts.
     #[inline]
     pub fn reset<I: InterruptNumber>(self) {
-        for source in 0..=I::MAX_INTERRUPT_NUMBER as _ {
-            // SAFETY: interrupt number within range
-            let reg: Reg<u32, RW> = unsafe { Reg::new(self.ptr.offset(source)) };
-            reg.write(0);
+        for source in 0..=I::MAX_INTERRUPT_NUMBER as usize {
+            self.bank.write(source, 0);
         }
     }
 }
@@ -95,4 +167,79 @@ mod test {
             assert_eq!(priorities.get_priority::<_, Priority>(source), Priority::P0);
         }
     }
+
+    /// Priority level that reports a number beyond its `MAX_PRIORITY_NUMBER`.
+    #[cfg(debug_assertions)]
+    #[derive(Clone, Copy)]
+    struct Raw(u8);
+
+    #[cfg(debug_assertions)]
+    unsafe impl PriorityNumber for Raw {
+        const MAX_PRIORITY_NUMBER: u8 = 3;
+
+        fn number(self) -> u8 {
+            self.0
+        }
+
+        fn from_number(value: u8) -> Result<Self, u8> {
+            Ok(Self(value))
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_priority_out_of_range() {
+        let mut raw_reg = [0u32; 1024];
+        // SAFETY: valid memory address
+        let priorities = unsafe { PRIORITIES::new(raw_reg.as_mut_ptr() as _) };
+        unsafe { priorities.set_priority(Interrupt::I1, Raw(4)) };
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_uniform_priority_out_of_range() {
+        let mut raw_reg = [0u32; 1024];
+        // SAFETY: valid memory address
+        let priorities = unsafe { PRIORITIES::new(raw_reg.as_mut_ptr() as _) };
+        unsafe { priorities.set_uniform::<Interrupt, _>(Raw(4)) };
+    }
+
+    #[test]
+    fn test_bulk_priorities() {
+        // slice to emulate the interrupt priorities register
+        let mut raw_reg = [0u32; 1024];
+        // SAFETY: valid memory address
+        let priorities = unsafe { PRIORITIES::new(raw_reg.as_mut_ptr() as _) };
+
+        let levels = [Priority::P1, Priority::P3, Priority::P2];
+        assert_eq!(unsafe { priorities.set_all::<Interrupt, _>(&levels) }, 3);
+        assert_eq!(
+            priorities.get_priority::<_, Priority>(Interrupt::I1),
+            Priority::P1
+        );
+        assert_eq!(
+            priorities.get_priority::<_, Priority>(Interrupt::I2),
+            Priority::P3
+        );
+        assert_eq!(
+            priorities.get_priority::<_, Priority>(Interrupt::I3),
+            Priority::P2
+        );
+        assert_eq!(
+            priorities.get_priority::<_, Priority>(Interrupt::I4),
+            Priority::P0
+        );
+
+        // priority levels beyond the last interrupt source are ignored
+        let levels = [Priority::P2; 8];
+        assert_eq!(unsafe { priorities.set_all::<Interrupt, _>(&levels) }, 4);
+        assert_eq!(raw_reg[5], 0);
+
+        unsafe { priorities.set_uniform::<Interrupt, _>(Priority::P3) };
+        assert_eq!(raw_reg[0], 0);
+        assert!(raw_reg[1..=4].iter().all(|&p| p == 3));
+        assert_eq!(raw_reg[5], 0);
+    }
 }
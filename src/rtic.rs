@@ -0,0 +1,150 @@
+//! [RTIC](https://rtic.rs) monotonic timer backed by the `MTIMER` peripheral.
+//!
+//! [`MtimerMonotonic`] implements [`rtic_monotonic::Monotonic`] with the `MTIME` counter shared
+//! among all the HARTs and the `MTIMECMP` register of the HART that runs the RTIC application.
+//! Instants and durations are [`Instant`] and [`Duration`] at the frequency `FREQ` (in Hz) of
+//! the `MTIME` counter.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use riscv_peripheral::{aclint::mtimer::MTIMER, rtic::MtimerMonotonic};
+//!
+//! // SAFETY: valid addresses of the MTIMECMP register of HART 0 and the MTIME register
+//! let mtimer = unsafe { MTIMER::new(0x0200_4000, 0x0200_BFF8) };
+//! let mono = MtimerMonotonic::<32_768>::new(mtimer.mtime, mtimer.mtimecmp0);
+//! ```
+//!
+//! The RTIC application must bind the machine timer interrupt of the HART to the monotonic.
+
+pub use rtic_monotonic; // re-export rtic-monotonic to allow users to use the same version
+
+use crate::aclint::{
+    mtimer::{MTIME, MTIMECMP},
+    time::{Duration, Instant},
+};
+
+/// RTIC monotonic timer backed by the `MTIME` counter and the `MTIMECMP` register of a HART.
+/// `FREQ` is the frequency (in Hz) of the `MTIME` counter.
+///
+/// # Note
+///
+/// The machine timer interrupt is level-triggered: it is pending while `mtime >= mtimecmp`.
+/// Thus, if [`rtic_monotonic::Monotonic::set_compare`] programs an instant that is already in
+/// the past, the interrupt fires right away instead of waiting for `MTIME` to wrap around.
+/// [`rtic_monotonic::Monotonic::clear_compare_flag`] clears the interrupt by moving the
+/// compare value to [`u64::MAX`], as there is no pending bit to clear.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MtimerMonotonic<const FREQ: u32> {
+    mtime: MTIME,
+    mtimecmp: MTIMECMP,
+}
+
+impl<const FREQ: u32> MtimerMonotonic<FREQ> {
+    /// Creates a new monotonic timer from the `MTIME` register and the `MTIMECMP` register of
+    /// the HART that runs the RTIC application.
+    #[inline]
+    pub const fn new(mtime: MTIME, mtimecmp: MTIMECMP) -> Self {
+        Self { mtime, mtimecmp }
+    }
+
+    /// Returns the `MTIME` register of the monotonic timer.
+    #[inline]
+    pub const fn mtime(&self) -> MTIME {
+        self.mtime
+    }
+
+    /// Returns the `MTIMECMP` register of the monotonic timer.
+    #[inline]
+    pub const fn mtimecmp(&self) -> MTIMECMP {
+        self.mtimecmp
+    }
+}
+
+impl<const FREQ: u32> rtic_monotonic::Monotonic for MtimerMonotonic<FREQ> {
+    type Instant = Instant<FREQ>;
+    type Duration = Duration<FREQ>;
+
+    #[inline]
+    fn now(&mut self) -> Self::Instant {
+        self.mtime.now()
+    }
+
+    /// Clears the compare value, so no interrupt fires until the first instant is scheduled.
+    /// The `MTIME` counter is shared among all the HARTs, so it is not reset.
+    #[inline]
+    unsafe fn reset(&mut self) {
+        self.clear_compare_flag();
+    }
+
+    #[inline]
+    fn set_compare(&mut self, instant: Self::Instant) {
+        self.mtimecmp.set_instant(instant);
+    }
+
+    #[inline]
+    fn clear_compare_flag(&mut self) {
+        self.mtimecmp.set_deadline(u64::MAX);
+    }
+
+    #[inline]
+    fn zero() -> Self::Instant {
+        Instant::from_ticks(0)
+    }
+
+    #[inline]
+    fn enable_timer(&mut self) {
+        // SAFETY: RTIC enables the timer interrupt only when a task is scheduled
+        unsafe { crate::csr::mie::set_mtimer() };
+    }
+
+    #[inline]
+    fn disable_timer(&mut self) {
+        crate::csr::mie::clear_mtimer();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rtic_monotonic::Monotonic;
+
+    #[test]
+    fn check_monotonic() {
+        let mut raw_mtimecmp = 0u64;
+        let mut raw_mtime = 0u64;
+        // SAFETY: valid memory addresses
+        let (mtimecmp, mtime) = unsafe {
+            (
+                MTIMECMP::new(&mut raw_mtimecmp as *mut u64 as _),
+                MTIME::new(&mut raw_mtime as *mut u64 as _),
+            )
+        };
+        let mut mono = MtimerMonotonic::<1_000_000>::new(mtime, mtimecmp);
+        assert_eq!(mono.mtime(), mtime);
+        assert_eq!(mono.mtimecmp(), mtimecmp);
+
+        unsafe { mono.reset() };
+        assert_eq!(mtimecmp.read(), u64::MAX);
+        assert_eq!(MtimerMonotonic::<1_000_000>::zero().ticks(), 0);
+
+        mtime.write(1_000);
+        let now = mono.now();
+        assert_eq!(now.ticks(), 1_000);
+
+        mono.set_compare(now + Duration::from_millis(1));
+        assert_eq!(mtimecmp.read(), 2_000);
+        assert!(!mtimecmp.is_expired(mtime));
+
+        // an instant in the past fires the interrupt right away
+        mono.set_compare(now - Duration::from_micros(500));
+        assert!(mtimecmp.is_expired(mtime));
+        mono.clear_compare_flag();
+        assert!(!mtimecmp.is_expired(mtime));
+
+        mono.enable_timer();
+        assert!(crate::csr::mie::mtimer());
+        mono.disable_timer();
+        assert!(!crate::csr::mie::mtimer());
+    }
+}
@@ -0,0 +1,14 @@
+//! Atomic types for the internal shared state of the peripherals.
+//!
+//! With the `portable-atomic` feature, atomics are provided by the `portable-atomic` crate.
+//! This allows using read-modify-write operations (e.g., compare-and-swap) on targets
+//! without the A extension, provided that the final binary enables a `portable-atomic`
+//! backend (e.g., its `critical-section` or `unsafe-assume-single-core` features).
+//! Otherwise, the atomics of [`core::sync::atomic`] are used.
+
+#![allow(unused_imports)] // some atomics are only used with specific features
+
+#[cfg(not(feature = "portable-atomic"))]
+pub(crate) use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "portable-atomic")]
+pub(crate) use portable_atomic::{AtomicBool, Ordering};
@@ -0,0 +1,324 @@
+//! Utilities for testing peripheral interfaces on the host.
+//!
+//! # Note
+//!
+//! This module requires the `testing` feature, which links the crate against `std`.
+//! It is always available to the unit tests of the crate.
+//!
+//! [`MockMmio`] allocates a memory region in the host that can be used as the base address
+//! of peripherals created from runtime addresses (e.g., [`crate::aclint::mswi::MSWI::new`]).
+//! Register accesses performed through [`crate::common::Reg`] that fall within a logged region
+//! are recorded, so tests can check the exact sequence of reads and writes.
+//! Accesses are only recorded in the thread that created the region.
+//! Atomic accesses are not recorded.
+//!
+//! Registers whose reads have side effects (e.g., the PLIC claim/complete register, which
+//! returns a different pending interrupt on each read) can be mocked with
+//! [`MockMmio::queue_reads`].
+//!
+//! # Recording accesses in unit tests
+//!
+//! Unit tests usually back peripherals with stack arrays, which is enough to check the final
+//! state of the registers. To also check the accesses that led to it (e.g., that a single byte
+//! write of `0x07` to offset 3 happened), back the peripheral with [`MockMmio::with_log`]
+//! and inspect [`MockMmio::records`]:
+//!
+//! ```
+//! use riscv_peripheral::testing::{Access, MockMmio, Record};
+//! # use riscv_peripheral::aclint::mswi::MSWI;
+//!
+//! let mmio = MockMmio::with_log(0x10);
+//! // SAFETY: valid memory address
+//! let mswi = unsafe { MSWI::new(mmio.base()) };
+//! unsafe { mswi.msip0.pend() };
+//! assert_eq!(mmio.records(), [Record::write(0, 1u32)]);
+//! ```
+
+use core::cell::{RefCell, UnsafeCell};
+use std::{boxed::Box, collections::VecDeque, rc::Rc, vec::Vec};
+
+/// Kind of access to a mock MMIO region.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Access {
+    /// Volatile read.
+    Read,
+    /// Volatile write.
+    Write,
+}
+
+/// Record of an access to a mock MMIO region.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Record {
+    /// Kind of access.
+    pub access: Access,
+    /// Offset of the access relative to the base address of the region.
+    pub offset: usize,
+    /// Size of the access in bytes.
+    pub size: usize,
+    /// Value read or written.
+    pub value: u128,
+}
+
+impl Record {
+    /// Creates the record of a read of `value` at offset `offset`.
+    pub fn read<T: Copy + Into<u128>>(offset: usize, value: T) -> Self {
+        Self::new(Access::Read, offset, value)
+    }
+
+    /// Creates the record of a write of `value` at offset `offset`.
+    pub fn write<T: Copy + Into<u128>>(offset: usize, value: T) -> Self {
+        Self::new(Access::Write, offset, value)
+    }
+
+    fn new<T: Copy + Into<u128>>(access: Access, offset: usize, value: T) -> Self {
+        Self {
+            access,
+            offset,
+            size: core::mem::size_of::<T>(),
+            value: value.into(),
+        }
+    }
+}
+
+type Log = Rc<RefCell<Vec<Record>>>;
+
+std::thread_local! {
+    static REGIONS: RefCell<Vec<(usize, usize, Log)>> = const { RefCell::new(Vec::new()) };
+    static QUEUED_READS: RefCell<Vec<(usize, VecDeque<u128>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records a read of `value` at the address `address` if it falls within a logged region.
+///
+/// If there are values queued for `address`, it returns (and records) the next queued value
+/// instead of `value`.
+#[inline]
+pub(crate) fn record_read<T: Copy>(address: usize, mut value: T) -> T {
+    let queued = QUEUED_READS.with(|queued| {
+        let mut queued = queued.borrow_mut();
+        let (_, values) = queued.iter_mut().find(|(a, _)| *a == address)?;
+        values.pop_front()
+    });
+    if let Some(queued) = queued {
+        let bytes = queued.to_le_bytes();
+        let n = core::mem::size_of::<T>().min(bytes.len());
+        // SAFETY: `value` is valid for `size_of::<T>()` bytes, and we copy at most 16 of them
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), &mut value as *mut T as *mut u8, n)
+        };
+    }
+    record(Access::Read, address, &value);
+    value
+}
+
+/// Records an access to the address `address` if it falls within a logged region.
+#[inline]
+pub(crate) fn record<T: Copy>(access: Access, address: usize, value: &T) {
+    let size = core::mem::size_of::<T>();
+    let mut bytes = [0u8; 16];
+    let n = size.min(bytes.len());
+    // SAFETY: `value` is valid for `size` bytes, and we copy at most 16 of them
+    unsafe {
+        core::ptr::copy_nonoverlapping(value as *const T as *const u8, bytes.as_mut_ptr(), n)
+    };
+    let value = u128::from_le_bytes(bytes);
+    REGIONS.with(|regions| {
+        for (start, end, log) in regions.borrow().iter() {
+            if (*start..*end).contains(&address) {
+                log.borrow_mut().push(Record {
+                    access,
+                    offset: address - start,
+                    size,
+                    value,
+                });
+            }
+        }
+    });
+}
+
+/// Mock memory-mapped I/O region backed by host memory.
+pub struct MockMmio {
+    mem: Box<[UnsafeCell<u64>]>,
+    log: Option<Log>,
+}
+
+impl MockMmio {
+    /// Allocates a new zero-initialized region of `size` bytes (rounded up to a multiple of 8).
+    /// Accesses to the region are not recorded.
+    pub fn new(size: usize) -> Self {
+        let mem = (0..size.div_ceil(8)).map(|_| UnsafeCell::new(0)).collect();
+        Self { mem, log: None }
+    }
+
+    /// Allocates a new zero-initialized region of `size` bytes (rounded up to a multiple of 8).
+    /// Accesses to the region from the current thread are recorded.
+    pub fn with_log(size: usize) -> Self {
+        let mut res = Self::new(size);
+        let log = Log::default();
+        let (start, end) = (res.base(), res.base() + res.size());
+        REGIONS.with(|regions| regions.borrow_mut().push((start, end, log.clone())));
+        res.log = Some(log);
+        res
+    }
+
+    /// Returns the base address of the region.
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.mem.as_ptr() as usize
+    }
+
+    /// Returns the size of the region in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.mem.len() * core::mem::size_of::<u64>()
+    }
+
+    /// Reads a value at a given offset of the region. This access is not recorded.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the access falls out of the region or is not properly aligned.
+    pub fn read<T: Copy>(&self, offset: usize) -> T {
+        // SAFETY: address within the region and properly aligned
+        unsafe { self.ptr::<T>(offset).read_volatile() }
+    }
+
+    /// Writes a value at a given offset of the region. This access is not recorded.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the access falls out of the region or is not properly aligned.
+    pub fn write<T: Copy>(&self, offset: usize, value: T) {
+        // SAFETY: address within the region and properly aligned
+        unsafe { self.ptr::<T>(offset).write_volatile(value) }
+    }
+
+    /// Queues the values returned by the next reads at a given offset of the region, in order.
+    /// Once the queue is empty, reads return the value stored in the region again.
+    ///
+    /// # Note
+    ///
+    /// Only reads through [`crate::common::Reg`] from the current thread consume queued values.
+    /// Reads with [`MockMmio::read`] always return the value stored in the region.
+    ///
+    /// # Panics
+    ///
+    /// It panics if the access falls out of the region or is not properly aligned.
+    pub fn queue_reads<T: Copy + Into<u128>>(&self, offset: usize, values: &[T]) {
+        let address = self.ptr::<T>(offset) as usize;
+        QUEUED_READS.with(|queued| {
+            let mut queued = queued.borrow_mut();
+            let values = values.iter().map(|&v| v.into());
+            match queued.iter_mut().find(|(a, _)| *a == address) {
+                Some((_, queue)) => queue.extend(values),
+                None => queued.push((address, values.collect())),
+            }
+        });
+    }
+
+    /// Returns the recorded accesses to the region.
+    /// If the region was not created with [`MockMmio::with_log`], it returns an empty vector.
+    pub fn records(&self) -> Vec<Record> {
+        self.log
+            .as_ref()
+            .map(|log| log.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Clears the recorded accesses to the region.
+    pub fn clear_records(&self) {
+        if let Some(log) = &self.log {
+            log.borrow_mut().clear();
+        }
+    }
+
+    fn ptr<T>(&self, offset: usize) -> *mut T {
+        let size = core::mem::size_of::<T>();
+        assert!(offset + size <= self.size(), "access out of the region");
+        assert_eq!(offset % core::mem::align_of::<T>(), 0, "unaligned access");
+        (self.base() + offset) as *mut T
+    }
+}
+
+impl Drop for MockMmio {
+    fn drop(&mut self) {
+        let (start, end) = (self.base(), self.base() + self.size());
+        QUEUED_READS.with(|queued| {
+            queued
+                .borrow_mut()
+                .retain(|(address, _)| !(start..end).contains(address))
+        });
+        if let Some(log) = &self.log {
+            REGIONS.with(|regions| regions.borrow_mut().retain(|(_, _, l)| !Rc::ptr_eq(l, log)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aclint::{mswi::MSWI, test::HartId};
+
+    #[test]
+    fn check_mock_mmio() {
+        let mmio = MockMmio::with_log(0x10);
+        assert_eq!(mmio.size(), 0x10);
+        // SAFETY: valid memory address
+        let mswi = unsafe { MSWI::new(mmio.base()) };
+
+        unsafe { mswi.msip(HartId::H2).pend() };
+        assert!(mswi.msip(HartId::H2).is_pending());
+        assert_eq!(mmio.read::<u32>(8), 1);
+        assert_eq!(
+            mmio.records(),
+            [
+                Record {
+                    access: Access::Write,
+                    offset: 8,
+                    size: 4,
+                    value: 1
+                },
+                Record {
+                    access: Access::Read,
+                    offset: 8,
+                    size: 4,
+                    value: 1
+                },
+            ]
+        );
+
+        mmio.clear_records();
+        mmio.write::<u32>(0, 1);
+        assert!(mswi.msip(HartId::H0).is_pending());
+        assert_eq!(mmio.records().len(), 1);
+
+        // accesses to non-logged regions are not recorded
+        let other = MockMmio::new(0x10);
+        let mswi = unsafe { MSWI::new(other.base()) };
+        unsafe { mswi.msip(HartId::H0).pend() };
+        assert!(other.records().is_empty());
+        assert_eq!(mmio.records().len(), 1);
+    }
+
+    #[test]
+    fn check_queued_reads() {
+        let mmio = MockMmio::with_log(0x10);
+        // SAFETY: valid memory address
+        let mswi = unsafe { MSWI::new(mmio.base()) };
+
+        mmio.queue_reads::<u32>(4, &[1, 0]);
+        assert!(mswi.msip(HartId::H1).is_pending());
+        assert!(!mswi.msip(HartId::H1).is_pending());
+        // the queue is empty, so reads return the stored value again
+        mmio.write::<u32>(4, 1);
+        assert!(mswi.msip(HartId::H1).is_pending());
+        assert_eq!(mmio.read::<u32>(4), 1);
+        assert_eq!(
+            mmio.records(),
+            [
+                Record::read(4, 1u32),
+                Record::read(4, 0u32),
+                Record::read(4, 1u32)
+            ]
+        );
+    }
+}
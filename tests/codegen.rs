@@ -0,0 +1,23 @@
+//! Compile tests pinning the compile-time checks of the codegen macros.
+//!
+//! The expected `.stderr` files contain compiler diagnostics, which change across Rust releases.
+//! Thus, these tests only run with the toolchain that generated them.
+
+/// Toolchain that generated the expected `.stderr` files.
+const PINNED_RUSTC: &str = "rustc 1.95.0 ";
+
+#[test]
+fn codegen() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = std::process::Command::new(rustc)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default();
+    if !version.starts_with(PINNED_RUSTC) {
+        eprintln!("skipping compile tests: expected diagnostics are pinned to {PINNED_RUSTC}");
+        return;
+    }
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/plic-*-fail.rs");
+}
@@ -0,0 +1,7 @@
+//! Compile tests pinning the `Send`/`Sync` behavior of the peripheral proxies.
+
+#[test]
+fn markers() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/markers-pass.rs");
+}
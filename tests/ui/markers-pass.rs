@@ -0,0 +1,32 @@
+//! Memory-mapped registers shared among all the HARTs are `Send` and `Sync`.
+
+use riscv_peripheral::{
+    aclint::{
+        mswi::{MSIP, MSWI},
+        mtimer::{MTIME, MTIMECMP, MTIMER},
+        sswi::SSWI,
+    },
+    common::{Reg, RO, RW, WO},
+    plic::{claim::CLAIM, enables::ENABLES, pendings::PENDINGS, priorities::PRIORITIES, threshold::THRESHOLD},
+};
+
+fn is_send_sync<T: Send + Sync>() {}
+
+fn main() {
+    is_send_sync::<Reg<u32, RO>>();
+    is_send_sync::<Reg<u64, WO>>();
+    is_send_sync::<Reg<usize, RW>>();
+
+    is_send_sync::<MSWI>();
+    is_send_sync::<MSIP>();
+    is_send_sync::<MTIMER>();
+    is_send_sync::<MTIME>();
+    is_send_sync::<MTIMECMP>();
+    is_send_sync::<SSWI>();
+
+    is_send_sync::<PRIORITIES>();
+    is_send_sync::<PENDINGS>();
+    is_send_sync::<ENABLES>();
+    is_send_sync::<THRESHOLD>();
+    is_send_sync::<CLAIM>();
+}
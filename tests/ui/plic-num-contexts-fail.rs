@@ -0,0 +1,30 @@
+//! A context type with more contexts than the PLIC of the target must not compile.
+
+#[derive(Clone, Copy)]
+#[repr(u16)]
+pub enum Context {
+    C0 = 0,
+    C1 = 1,
+    C2 = 2,
+}
+
+unsafe impl riscv_peripheral::plic::ContextNumber for Context {
+    const MAX_CONTEXT_NUMBER: u16 = 2;
+
+    fn number(self) -> u16 {
+        self as _
+    }
+
+    fn from_number(number: u16) -> Result<Self, u16> {
+        match number {
+            0 => Ok(Self::C0),
+            1 => Ok(Self::C1),
+            2 => Ok(Self::C2),
+            _ => Err(number),
+        }
+    }
+}
+
+riscv_peripheral::plic_codegen!(base 0x0C00_0000, num_contexts 2, contexts Context,);
+
+fn main() {}